@@ -1,4 +1,5 @@
 use clap::Parser;
+use regex::Regex;
 use uuid::Uuid;
 
 use crate::usecases::content::ContentQuery;
@@ -6,15 +7,107 @@ use crate::usecases::user::{UserMutation, UserQuery};
 
 pub mod parser;
 
-pub use parser::PartialContentMutation;
 use parser::*;
+pub use parser::{ContentRef, PartialContentMutation};
 
 /// this is a ICEy_PUDDING.
 #[derive(Debug, Clone, Parser)]
-#[clap(author, version)]
+#[clap(author, version, disable_help_subcommand = true)]
 pub struct Cmd {
     #[clap(subcommand)]
     pub cmd: RootMod,
+
+    /// reply with json of users or contents instead of embeds. (only for `get`,
+    /// `gets` and `user contents`)
+    #[clap(long, global = true)]
+    pub json: bool,
+}
+
+impl Cmd {
+    /// stable, dot-separated name of (sub)command, e.g. `content.like.do`.
+    /// not affected by arguments, so usable as a key of logs.
+    pub fn name(&self) -> &'static str {
+        match &self.cmd {
+            RootMod::User { cmd } => match cmd {
+                UserMod::Register(_) => "user.register",
+                UserMod::RegisterFor(_) => "user.register-for",
+                UserMod::Status(_) => "user.status",
+                UserMod::Get(_) => "user.get",
+                UserMod::Gets(_) => "user.gets",
+                UserMod::Similar(_) => "user.similar",
+                UserMod::Contents(_) => "user.contents",
+                UserMod::Edit(_) => "user.edit",
+                UserMod::Bookmark(UserBookmarkCmd { op }) => match op {
+                    UserBookmarkOp::Do { .. } => "user.bookmark.do",
+                    UserBookmarkOp::Undo { .. } => "user.bookmark.undo",
+                    UserBookmarkOp::Toggle { .. } => "user.bookmark.toggle",
+                    UserBookmarkOp::Contents { .. } => "user.bookmark.contents",
+                    UserBookmarkOp::Count { .. } => "user.bookmark.count",
+                    UserBookmarkOp::Export => "user.bookmark.export",
+                    UserBookmarkOp::Show { .. } => "user.bookmark.show",
+                },
+                UserMod::Unregister(_) => "user.unregister",
+            },
+            RootMod::Content { cmd } => match cmd {
+                ContentMod::Post(_) => "content.post",
+                ContentMod::FromTemplate(_) => "content.fromtemplate",
+                ContentMod::Template(ContentTemplateCmd { op }) => match op {
+                    ContentTemplateOp::Save { .. } => "content.template.save",
+                    ContentTemplateOp::Delete { .. } => "content.template.delete",
+                    ContentTemplateOp::Show => "content.template.show",
+                },
+                ContentMod::Get(_) => "content.get",
+                ContentMod::History(_) => "content.history",
+                ContentMod::Gets(_) => "content.gets",
+                ContentMod::Edit(_) => "content.edit",
+                ContentMod::RenameVirtual(_) => "content.rename-virtual",
+                ContentMod::ReassignPosted(_) => "content.reassign-posted",
+                ContentMod::PreviewSed(_) => "content.preview-sed",
+                ContentMod::Like(ContentLikeCmd { op }) => match op {
+                    ContentLikeOp::Do { .. } => "content.like.do",
+                    ContentLikeOp::Undo { .. } => "content.like.undo",
+                    ContentLikeOp::DoMany { .. } => "content.like.do-many",
+                    ContentLikeOp::UndoMany { .. } => "content.like.undo-many",
+                    ContentLikeOp::Count { .. } => "content.like.count",
+                    ContentLikeOp::Show { .. } => "content.like.show",
+                },
+                ContentMod::Pin(ContentPinCmd { op }) => match op {
+                    ContentPinOp::Do { .. } => "content.pin.do",
+                    ContentPinOp::Undo { .. } => "content.pin.undo",
+                    ContentPinOp::Count { .. } => "content.pin.count",
+                    ContentPinOp::Show { .. } => "content.pin.show",
+                },
+                ContentMod::Tag(ContentTagCmd { op }) => match op {
+                    ContentTagOp::Add { .. } => "content.tag.add",
+                    ContentTagOp::Remove { .. } => "content.tag.remove",
+                },
+                ContentMod::Withdraw(_) => "content.withdraw",
+                ContentMod::Restore(_) => "content.restore",
+                ContentMod::Merge(_) => "content.merge",
+            },
+            RootMod::Guild { cmd } => match cmd {
+                GuildMod::DefaultQuery(_) => "guild.default-query",
+                GuildMod::Sticky(_) => "guild.sticky",
+            },
+            RootMod::Stats => "stats",
+            RootMod::Ping => "ping",
+            RootMod::Help { .. } => "help",
+        }
+    }
+}
+
+impl Cmd {
+    /// whether hard to undo, so that confirmation is asked before executing.
+    pub fn is_destructive(&self) -> bool {
+        matches!(
+            &self.cmd,
+            RootMod::User {
+                cmd: UserMod::Unregister(_),
+            } | RootMod::Content {
+                cmd: ContentMod::Withdraw(_) | ContentMod::Merge(_),
+            }
+        )
+    }
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -32,6 +125,29 @@ pub enum RootMod {
         #[clap(subcommand)]
         cmd: ContentMod,
     },
+
+    /// about guild.
+    #[clap(short_flag = 'G')]
+    Guild {
+        #[clap(subcommand)]
+        cmd: GuildMod,
+    },
+
+    /// show summary of contents and users.
+    #[clap(short_flag = 'S')]
+    Stats,
+
+    /// check connection to database.
+    #[clap(short_flag = 'P')]
+    Ping,
+
+    /// show help of (sub)command, with its short forms.
+    #[clap(short_flag = 'H')]
+    Help {
+        /// path to subcommand, e.g. `content like`. defaults to root.
+        #[clap(name = "SUBCOMMAND")]
+        subcommand: Vec<String>,
+    },
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -39,12 +155,24 @@ pub enum UserMod {
     #[clap(short_flag = 'c')]
     Register(UserRegisterCmd),
 
+    #[clap(short_flag = 'r')]
+    RegisterFor(UserRegisterForCmd),
+
+    #[clap(short_flag = 'w')]
+    Status(UserStatusCmd),
+
     #[clap(short_flag = 'g')]
     Get(UserGetCmd),
 
     #[clap(short_flag = 'q')]
     Gets(UserGetsCmd),
 
+    #[clap(short_flag = 's')]
+    Similar(UserSimilarCmd),
+
+    #[clap(short_flag = 'p')]
+    Contents(UserContentsCmd),
+
     #[clap(short_flag = 'e')]
     Edit(UserEditCmd),
 
@@ -60,35 +188,83 @@ pub enum ContentMod {
     #[clap(short_flag = 'c')]
     Post(ContentPostCmd),
 
+    #[clap(name = "fromtemplate", short_flag = 'f')]
+    FromTemplate(ContentFromTemplateCmd),
+
+    #[clap(short_flag = 't')]
+    Template(ContentTemplateCmd),
+
     #[clap(short_flag = 'g')]
     Get(ContentGetCmd),
 
+    #[clap(short_flag = 'r')]
+    History(ContentHistoryCmd),
+
     #[clap(short_flag = 'q')]
     Gets(ContentGetsCmd),
 
     #[clap(short_flag = 'e')]
     Edit(ContentEditCmd),
 
+    #[clap(short_flag = 'v')]
+    RenameVirtual(ContentRenameVirtualCmd),
+
+    #[clap(short_flag = 'o')]
+    ReassignPosted(ContentReassignPostedCmd),
+
+    #[clap(short_flag = 's')]
+    PreviewSed(ContentPreviewSedCmd),
+
     #[clap(short_flag = 'l')]
     Like(ContentLikeCmd),
 
     #[clap(short_flag = 'p')]
     Pin(ContentPinCmd),
 
+    #[clap(short_flag = 'a')]
+    Tag(ContentTagCmd),
+
     #[clap(short_flag = 'd')]
     Withdraw(ContentWithdrawCmd),
+
+    #[clap(short_flag = 'u')]
+    Restore(ContentRestoreCmd),
+
+    #[clap(short_flag = 'm')]
+    Merge(ContentMergeCmd),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub enum GuildMod {
+    #[clap(short_flag = 'q')]
+    DefaultQuery(GuildDefaultQueryCmd),
+
+    #[clap(short_flag = 's')]
+    Sticky(GuildStickyCmd),
 }
 
 /// register user with executed user's id.
 #[derive(Debug, Clone, Parser)]
 pub struct UserRegisterCmd;
 
+/// register user with given id. (admin only)
+#[derive(Debug, Clone, Parser)]
+pub struct UserRegisterForCmd {
+    /// u64 | mention
+    #[clap(name = "USER_ID", parse(try_from_str = parse_user_id))]
+    pub user_id: u64,
+}
+
+/// show executed user's id and whether registered. (no registration needed)
+#[derive(Debug, Clone, Parser)]
+pub struct UserStatusCmd;
+
 /// get user with id.
 /// if not given id, fallback to executed user's id.
 #[derive(Debug, Clone, Parser)]
 pub struct UserGetCmd {
-    /// u64,
-    #[clap(name = "USER_ID")]
+    /// u64 | mention
+    #[clap(name = "USER_ID", parse(try_from_str = parse_user_id))]
     pub user_id: Option<u64>,
 }
 
@@ -100,6 +276,8 @@ pub struct UserGetsCmd {
     /// schema: {
     ///   bookmark?: [uuid],
     ///   bookmark_num?: range<u32>,
+    ///   admin?: bool,
+    ///   sub_admin?: bool,
     /// }
     ///
     /// # example
@@ -119,8 +297,8 @@ pub struct UserGetsCmd {
 /// edit user with id and mutation.
 #[derive(Debug, Clone, Parser)]
 pub struct UserEditCmd {
-    /// u64
-    #[clap(name = "USER_ID")]
+    /// u64 | mention
+    #[clap(name = "USER_ID", parse(try_from_str = parse_user_id))]
     pub user_id: u64,
 
     /// json
@@ -164,11 +342,43 @@ pub enum UserBookmarkOp {
         content_id: Uuid,
     },
 
+    /// bookmark content if not yet, otherwise unbookmark.
+    #[clap(short_flag = 't')]
+    Toggle {
+        /// uuid
+        #[clap(name = "CONTENT_ID")]
+        content_id: Uuid,
+    },
+
+    /// get bookmarks with contents.
+    #[clap(short_flag = 'c')]
+    Contents {
+        /// u64 | mention
+        #[clap(name = "USER_ID", parse(try_from_str = parse_user_id))]
+        user_id: Option<u64>,
+
+        /// u32 (1 =< n)
+        #[clap(name = "PAGE", default_value = "1", parse(try_from_str = parse_nonzero_num))]
+        page: u32,
+    },
+
+    /// count bookmarks, without listing them.
+    #[clap(short_flag = 'n')]
+    Count {
+        /// u64 | mention
+        #[clap(name = "USER_ID", parse(try_from_str = parse_user_id))]
+        user_id: Option<u64>,
+    },
+
+    /// export bookmarks (with author and beginning of content) as JSON file.
+    #[clap(short_flag = 'e')]
+    Export,
+
     /// get bookmarks.
     #[clap(short_flag = 's')]
     Show {
-        /// u64
-        #[clap(name = "USER_ID")]
+        /// u64 | mention
+        #[clap(name = "USER_ID", parse(try_from_str = parse_user_id))]
         user_id: Option<u64>,
 
         /// u32 (1 =< n)
@@ -177,12 +387,44 @@ pub enum UserBookmarkOp {
     },
 }
 
+/// get users with similar bookmarks, most shared first.
+#[derive(Debug, Clone, Parser)]
+pub struct UserSimilarCmd {
+    /// u64 | mention (defaults to executed user)
+    #[clap(name = "USER_ID", parse(try_from_str = parse_user_id))]
+    pub user_id: Option<u64>,
+
+    /// u32 (1 =< n)
+    #[clap(name = "PAGE", default_value = "1", parse(try_from_str = parse_nonzero_num))]
+    pub page: u32,
+}
+
+/// get contents posted by user. (same as `content gets` with `posted`)
+#[derive(Debug, Clone, Parser)]
+pub struct UserContentsCmd {
+    /// u64 | mention (defaults to executed user)
+    #[clap(name = "USER_ID", parse(try_from_str = parse_user_id))]
+    pub user_id: Option<u64>,
+
+    /// u32 (1 =< n)
+    #[clap(name = "PAGE", default_value = "1", parse(try_from_str = parse_nonzero_num))]
+    pub page: u32,
+}
+
 /// unregister user with executed user's id.
 #[derive(Debug, Clone, Parser)]
 pub struct UserUnregisterCmd {
-    /// u64
-    #[clap(name = "USER_ID")]
+    /// u64 | mention
+    #[clap(name = "USER_ID", parse(try_from_str = parse_user_id))]
     pub user_id: u64,
+
+    /// u64 | mention (reassigns posted contents to the user)
+    #[clap(long, value_name = "USER_ID", parse(try_from_str = parse_user_id))]
+    pub reassign_to: Option<u64>,
+
+    /// withdraw posted contents
+    #[clap(long, conflicts_with = "reassign-to")]
+    pub purge: bool,
 }
 
 /// post content with executed user's id.
@@ -193,21 +435,108 @@ pub struct ContentPostCmd {
     #[clap(short = 'v', long, group = "author")]
     pub virt: Option<String>,
 
-    /// u64
-    #[clap(short = 'u', long, group = "author")]
+    /// u64 | mention
+    #[clap(short = 'u', long, group = "author", parse(try_from_str = parse_user_id))]
     pub user_id: Option<u64>,
 
     /// str
     #[clap(short = 'c', long)]
     pub content: String,
+
+    /// str (shown as heading)
+    #[clap(short = 't', long)]
+    pub title: Option<String>,
+
+    /// http(s) url (linked from heading)
+    #[clap(long, parse(try_from_str = parse_source_url))]
+    pub source_url: Option<String>,
+
+    /// hide poster from others
+    #[clap(long)]
+    pub anon: bool,
+
+    /// str (repeatable)
+    #[clap(long = "tag")]
+    pub tags: Vec<String>,
+}
+
+/// post content from template with executed user's id.
+#[derive(Debug, Clone, Parser)]
+#[clap(group = ::clap::ArgGroup::new("author").required(true))]
+pub struct ContentFromTemplateCmd {
+    /// str
+    #[clap(short = 'v', long, group = "author")]
+    pub virt: Option<String>,
+
+    /// u64 | mention
+    #[clap(short = 'u', long, group = "author", parse(try_from_str = parse_user_id))]
+    pub user_id: Option<u64>,
+
+    /// str
+    #[clap(name = "NAME")]
+    pub name: String,
+
+    /// key=value (fills `{key}` in template)
+    #[clap(name = "ARGS", parse(try_from_str = parse_template_arg))]
+    pub args: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct ContentTemplateCmd {
+    #[clap(subcommand)]
+    pub op: ContentTemplateOp,
+}
+
+/// about executed user's templates.
+#[derive(Debug, Clone, Parser)]
+pub enum ContentTemplateOp {
+    /// save template. (overwrites same name)
+    #[clap(short_flag = 'c')]
+    Save {
+        /// str
+        #[clap(name = "NAME")]
+        name: String,
+
+        /// str (`{key}` is placeholder)
+        #[clap(name = "TEMPLATE")]
+        template: String,
+    },
+
+    /// delete template.
+    #[clap(short_flag = 'd')]
+    Delete {
+        /// str
+        #[clap(name = "NAME")]
+        name: String,
+    },
+
+    /// show templates.
+    #[clap(short_flag = 's')]
+    Show,
 }
 
 /// get content with id.
 #[derive(Debug, Clone, Parser)]
 pub struct ContentGetCmd {
-    /// uuid
-    #[clap(name = "CONTENT_ID")]
-    pub content_id: Uuid,
+    /// uuid | short id
+    #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+    pub content_id: ContentRef,
+
+    /// reply only content body as code block, to copy it easily.
+    #[clap(long)]
+    pub raw: bool,
+
+    /// show real poster of anonymous content. (admin only)
+    #[clap(long, conflicts_with = "raw")]
+    pub reveal: bool,
+}
+
+/// get edit history of content, with replaced contents.
+#[derive(Debug, Clone, Parser)]
+pub struct ContentHistoryCmd {
+    /// uuid | short id
+    #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+    pub content_id: ContentRef,
 }
 
 /// get contents with query.
@@ -219,10 +548,34 @@ pub struct ContentGetsCmd {
     ///   author?: Author,
     ///   posted?: Posted,
     ///   content?: regex,
+    ///   content_exact?: string, // exactly equals, not regex
+    ///   text?: string, // full-text search
     ///   liked?: [u64],
     ///   liked_num?: range<u32>,
+    ///   liked_eq?: u32,
+    ///   has_likes?: bool,
     ///   pinned: [u64],
     ///   pinned_num?: range<u32>,
+    ///   pinned_eq?: u32,
+    ///   has_pins?: bool,
+    ///   min_engagement?: u32,
+    ///   lang?: string, // ISO 639-3
+    ///   self_unengaged?: bool,
+    ///   created_hour?: range<u8>, // in UTC
+    ///   created_weekday?: [str], // "Mon", "Tue", ... in UTC
+    ///   created_range?: range<date>, // RFC 3339, e.g.
+    /// "2022-01-01T00:00:00Z.."   edited_after?: date, // RFC 3339
+    ///   pinned_not_liked_by?: u64,
+    ///   tags?: [string], // has all of them
+    ///   bookmarked?: bool,
+    ///   author_registered?: bool, // checks users per author, slow
+    ///   any_of?: [Query],
+    ///   sort?: Sort, // not in any_of
+    /// }
+    ///
+    /// struct Sort {
+    ///   key: "Created" | "LikedCount" | "PinnedCount" | "Edited",
+    ///   desc?: bool,
     /// }
     ///
     /// enum Author {
@@ -230,6 +583,8 @@ pub struct ContentGetsCmd {
     ///   UserName(regex),
     ///   UserNick(regex),
     ///   Any(regex),
+    ///   AnyOfUsers([u64]),
+    ///   NameContains(string), // case-insensitive, not regex
     /// }
     ///
     /// enum Posted {
@@ -247,8 +602,47 @@ pub struct ContentGetsCmd {
     ///   },
     ///   "pinned_num": "10.."
     /// }
-    #[clap(name = "QUERY", default_value = "{}", parse(try_from_str = parse_content_query))]
-    pub query: ContentQuery,
+    ///
+    /// {
+    ///   "any_of": [
+    ///     { "author": { "UserId": 18446744073709551615 } },
+    ///     { "posted": { "UserId": 18446744073709551615 } }
+    ///   ]
+    /// }
+    ///
+    /// `Query` means this schema itself. (nesting is limited to 3)
+    /// `*_eq` cannot be used with `*_num` together.
+    /// `bookmarked` cannot be used in `any_of`.
+    ///
+    /// if not given, fallback to guild's default query (or `{}`).
+    #[clap(name = "QUERY", parse(try_from_str = parse_content_query))]
+    pub query: Option<ContentQuery>,
+
+    /// show current names of authors instead of stored ones. (slow)
+    #[clap(long)]
+    pub resolve_authors: bool,
+
+    /// include archived (withdrawn) contents too. (admin only)
+    #[clap(long)]
+    pub include_archived: bool,
+
+    /// save the query with the name. (per user, not persisted)
+    #[clap(long, value_name = "NAME")]
+    pub save: Option<String>,
+
+    /// use the saved query instead of QUERY. (QUERY is ignored, so `{}` can
+    /// be given to specify PAGE)
+    #[clap(long, value_name = "NAME")]
+    pub load: Option<String>,
+
+    /// uuid (shows contents created right after it instead of PAGE. cannot be
+    /// used with `sort`)
+    #[clap(long, conflicts_with = "before")]
+    pub after: Option<Uuid>,
+
+    /// uuid (same as `--after`, but right before it)
+    #[clap(long)]
+    pub before: Option<Uuid>,
 
     /// u32 (1 =< n)
     #[clap(name = "PAGE", default_value = "1", parse(try_from_str = parse_nonzero_num))]
@@ -267,16 +661,20 @@ pub struct ContentEditCmd {
     /// schema: {
     ///   "author": Author,
     ///   "content": Content,
+    ///   "title": str, // empty removes title
     /// }
     ///
     /// enum Author {
-    ///   User(u64),
+    ///   User(u64 | str), // str accepts mention
     ///   Virtual(regex),
+    ///   VirtualFromUser { id: u64 | str }, // stored as "user:{id}"
     /// }
     ///
     /// enum Content {
     ///   Complete(str),
-    ///   Sed { capture: regex, replace: str }
+    ///   Sed { capture: regex, replace: str },
+    ///   Append(str), // no separator
+    ///   Prepend(str), // no separator
     /// }
     ///
     /// # example
@@ -288,6 +686,26 @@ pub struct ContentEditCmd {
     /// }
     #[clap(name = "MUTATION", default_value = "{}", parse(try_from_str = parse_partial_content_mutation))]
     pub mutation: PartialContentMutation,
+
+    /// show result of sed (`Content::Sed`) without saving.
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// preview result of sed mutation without saving.
+#[derive(Debug, Clone, Parser)]
+pub struct ContentPreviewSedCmd {
+    /// uuid
+    #[clap(name = "CONTENT_ID")]
+    pub content_id: Uuid,
+
+    /// regex
+    #[clap(name = "CAPTURE")]
+    pub capture: Regex,
+
+    /// str
+    #[clap(name = "REPLACE")]
+    pub replace: String,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -302,29 +720,57 @@ pub enum ContentLikeOp {
     /// like content.
     #[clap(short_flag = 'd')]
     Do {
-        /// uuid
-        #[clap(name = "CONTENT_ID")]
-        content_id: Uuid,
+        /// uuid | short id
+        #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+        content_id: ContentRef,
     },
 
     /// unlike content.
     #[clap(short_flag = 'u')]
     Undo {
-        /// uuid
-        #[clap(name = "CONTENT_ID")]
-        content_id: Uuid,
+        /// uuid | short id
+        #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+        content_id: ContentRef,
+    },
+
+    /// like contents at once. (already liked ones are skipped)
+    #[clap(short_flag = 'D')]
+    DoMany {
+        /// [uuid]
+        #[clap(name = "CONTENT_IDS", required = true)]
+        content_ids: Vec<Uuid>,
+    },
+
+    /// unlike contents at once. (not liked ones are skipped)
+    #[clap(short_flag = 'U')]
+    UndoMany {
+        /// [uuid]
+        #[clap(name = "CONTENT_IDS", required = true)]
+        content_ids: Vec<Uuid>,
+    },
+
+    /// count liked users, without listing them.
+    #[clap(short_flag = 'n')]
+    Count {
+        /// uuid | short id
+        #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+        content_id: ContentRef,
     },
 
     /// get liked users.
     #[clap(short_flag = 's')]
     Show {
-        /// uuid
-        #[clap(name = "CONTENT_ID")]
-        content_id: Uuid,
+        /// uuid | short id
+        #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+        content_id: ContentRef,
 
         /// u32 (1 =< n)
         #[clap(name = "PAGE", default_value = "1", parse(try_from_str = parse_nonzero_num))]
         page: u32,
+
+        /// show name and admin status of each user too.
+        #[clap(long)]
+        detailed: bool,
     },
 }
 
@@ -340,25 +786,33 @@ pub enum ContentPinOp {
     /// pin content.
     #[clap(short_flag = 'd')]
     Do {
-        /// uuid
-        #[clap(name = "CONTENT_ID")]
-        content_id: Uuid,
+        /// uuid | short id
+        #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+        content_id: ContentRef,
     },
 
     /// unpin content.
     #[clap(short_flag = 'u')]
     Undo {
-        /// uuid
-        #[clap(name = "CONTENT_ID")]
-        content_id: Uuid,
+        /// uuid | short id
+        #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+        content_id: ContentRef,
+    },
+
+    /// count pinned users, without listing them.
+    #[clap(short_flag = 'n')]
+    Count {
+        /// uuid | short id
+        #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+        content_id: ContentRef,
     },
 
     /// get pinned users.
     #[clap(short_flag = 's')]
     Show {
-        /// uuid
-        #[clap(name = "CONTENT_ID")]
-        content_id: Uuid,
+        /// uuid | short id
+        #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+        content_id: ContentRef,
 
         /// u32 (1 =< n)
         #[clap(name = "PAGE", default_value = "1", parse(try_from_str = parse_nonzero_num))]
@@ -366,6 +820,83 @@ pub enum ContentPinOp {
     },
 }
 
+/// about tags of content. (poster, admin or sub_admin only)
+#[derive(Debug, Clone, Parser)]
+pub struct ContentTagCmd {
+    #[clap(subcommand)]
+    pub op: ContentTagOp,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub enum ContentTagOp {
+    /// tag content.
+    #[clap(short_flag = 'a')]
+    Add {
+        /// uuid
+        #[clap(name = "CONTENT_ID")]
+        content_id: Uuid,
+
+        /// str
+        #[clap(name = "TAG")]
+        tag: String,
+    },
+
+    /// untag content.
+    #[clap(short_flag = 'r')]
+    Remove {
+        /// uuid
+        #[clap(name = "CONTENT_ID")]
+        content_id: Uuid,
+
+        /// str
+        #[clap(name = "TAG")]
+        tag: String,
+    },
+}
+
+/// set default query of `content gets` in executed guild. (admin only)
+/// if not given query, clear it.
+#[derive(Debug, Clone, Parser)]
+pub struct GuildDefaultQueryCmd {
+    /// json (same as `content gets`)
+    #[clap(name = "QUERY", parse(try_from_str = parse_raw_content_query))]
+    pub query: Option<String>,
+}
+
+/// set sticky content of executed channel. (admin only)
+/// it will be re-posted after new messages.
+/// if not given id, clear it.
+#[derive(Debug, Clone, Parser)]
+pub struct GuildStickyCmd {
+    /// uuid | short id
+    #[clap(name = "CONTENT_ID", parse(try_from_str = parse_content_ref))]
+    pub content_id: Option<ContentRef>,
+}
+
+/// rename virtual author in all contents. (admin only)
+#[derive(Debug, Clone, Parser)]
+pub struct ContentRenameVirtualCmd {
+    /// string
+    #[clap(name = "FROM")]
+    pub from: String,
+
+    /// string
+    #[clap(name = "TO")]
+    pub to: String,
+}
+
+/// reassign posted user of content. (admin or sub_admin only)
+#[derive(Debug, Clone, Parser)]
+pub struct ContentReassignPostedCmd {
+    /// uuid
+    #[clap(name = "CONTENT_ID")]
+    pub content_id: Uuid,
+
+    /// u64 | mention
+    #[clap(name = "USER_ID", parse(try_from_str = parse_user_id))]
+    pub user_id: u64,
+}
+
 /// withdraw content with id.
 #[derive(Debug, Clone, Parser)]
 pub struct ContentWithdrawCmd {
@@ -373,3 +904,24 @@ pub struct ContentWithdrawCmd {
     #[clap(name = "CONTENT_ID")]
     pub content_id: Uuid,
 }
+
+/// restore withdrawn content with id. (admin only)
+#[derive(Debug, Clone, Parser)]
+pub struct ContentRestoreCmd {
+    /// uuid
+    #[clap(name = "CONTENT_ID")]
+    pub content_id: Uuid,
+}
+
+/// merge duplicated content into another one: moves likes, pins and
+/// bookmarks, then withdraws it. (admin only)
+#[derive(Debug, Clone, Parser)]
+pub struct ContentMergeCmd {
+    /// uuid (withdrawn)
+    #[clap(name = "FROM")]
+    pub from: Uuid,
+
+    /// uuid
+    #[clap(name = "INTO")]
+    pub into: Uuid,
+}
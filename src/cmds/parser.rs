@@ -1,18 +1,29 @@
 use core::num::NonZeroU32;
+use core::ops::Bound;
 use std::collections::HashSet;
 
 use regex::Regex;
 use uuid::Uuid;
 
-use crate::entities::{ContentId, PartialAuthor, UserId};
-use crate::usecases::content::{AuthorQuery, ContentContentMutation, ContentQuery, PostedQuery};
+use crate::entities::{ContentId, Date, PartialAuthor, UserId};
+use crate::usecases::check_query_ids;
+use crate::usecases::content::{
+    AuthorQuery, ContentContentMutation, ContentQuery, ContentSort, PostedQuery, SortKey,
+};
 use crate::usecases::user::{UserMutation, UserQuery};
 use crate::utils::LetChain;
 
+#[derive(Debug, Clone)]
+pub enum ContentRef {
+    Id(Uuid),
+    Short(String),
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct PartialContentMutation {
     pub author: Option<PartialAuthor>,
     pub content: Option<ContentContentMutation>,
+    pub title: Option<Option<String>>,
 }
 
 pub fn parse_nonzero_num(
@@ -21,11 +32,53 @@ pub fn parse_nonzero_num(
     Ok(s.parse::<::core::num::NonZeroU32>()?.get())
 }
 
+/// accepts mention (`<@id>` or `<@!id>`) as well as plain id.
+pub fn parse_user_id(s: &str) -> ::core::result::Result<u64, String> {
+    let id = s
+        .strip_prefix("<@")
+        .and_then(|s| s.strip_suffix('>'))
+        .map(|s| s.strip_prefix('!').unwrap_or(s))
+        .unwrap_or(s);
+
+    id.parse::<u64>()
+        .map_err(|_| format!("expected user id or mention, found: {}", s))
+}
+
+pub fn parse_content_ref(s: &str) -> ::core::result::Result<ContentRef, String> {
+    if let Ok(i) = s.parse::<Uuid>() {
+        return Ok(ContentRef::Id(i));
+    }
+
+    let short = s.to_lowercase();
+    match short.len() == 6 && short.chars().all(|c| c.is_ascii_alphanumeric()) {
+        true => Ok(ContentRef::Short(short)),
+        false => Err(format!("expected uuid or 6-chars short id, found: {}", s)),
+    }
+}
+
+pub fn parse_source_url(s: &str) -> ::core::result::Result<String, String> {
+    let url = ::reqwest::Url::parse(s).map_err(|e| format!("invalid url: {} ({})", s, e))?;
+
+    match matches!(url.scheme(), "http" | "https") && url.host().is_some() {
+        true => Ok(url.into()),
+        false => Err(format!("expected http(s) url with host, found: {}", s)),
+    }
+}
+
+pub fn parse_template_arg(s: &str) -> ::core::result::Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((k, v)) if !k.is_empty() => Ok((k.to_string(), v.to_string())),
+        _ => Err(format!("expected key=value, found: {}", s)),
+    }
+}
+
 pub fn parse_user_query(s: &str) -> ::core::result::Result<UserQuery, String> {
     #[derive(::serde::Deserialize)]
     struct UserQueryModel {
         bookmark: Option<HashSet<Uuid>>,
         bookmark_num: Option<String>,
+        admin: Option<bool>,
+        sub_admin: Option<bool>,
     }
 
     // --- parsing json ---
@@ -33,8 +86,16 @@ pub fn parse_user_query(s: &str) -> ::core::result::Result<UserQuery, String> {
     let UserQueryModel {
         bookmark: bookmark_raw,
         bookmark_num: bookmark_num_raw,
+        admin,
+        sub_admin,
     } = serde_json::from_str(s).map_err(|e| e.to_string())?;
 
+    // --- validating ---
+
+    if let Some(s) = &bookmark_raw {
+        check_query_ids("bookmark", s.len(), MAX_QUERY_IDS)?;
+    }
+
     // --- converting ---
 
     let bookmark = bookmark_raw.map(|mut s| s.drain().map(ContentId).collect());
@@ -48,6 +109,8 @@ pub fn parse_user_query(s: &str) -> ::core::result::Result<UserQuery, String> {
     Ok(UserQuery {
         bookmark,
         bookmark_num,
+        admin,
+        sub_admin,
     })
 }
 
@@ -65,19 +128,61 @@ pub fn parse_user_mutation(s: &str) -> ::core::result::Result<UserMutation, Stri
 
     // --- finalize ---
 
-    Ok(UserMutation { admin, sub_admin })
+    Ok(UserMutation {
+        admin,
+        sub_admin,
+        // set by interactor, not by mutation.
+        granted_by: None,
+        granted_at: None,
+    })
 }
 
+/// limits nesting of `any_of` in content query.
+const MAX_QUERY_DEPTH: usize = 3;
+
+/// limits number of ids in `liked` / `pinned` (content query) and `bookmark`
+/// (user query), not to issue huge `$in` queries. `Config::max_query_ids` can
+/// only lower this.
+pub const MAX_QUERY_IDS: usize = 100;
+
 pub fn parse_content_query(s: &str) -> ::core::result::Result<ContentQuery, String> {
     #[derive(::serde::Deserialize)]
     struct ContentQueryModel<'a> {
         pub author: Option<AuthorQueryModel<'a>>,
         pub posted: Option<PostedQueryModel<'a>>,
         pub content: Option<&'a str>,
+        // not borrowed, since compared as is (escapes in json cannot be borrowed).
+        pub content_exact: Option<String>,
+        pub text: Option<&'a str>,
         pub liked: Option<HashSet<u64>>,
         pub liked_num: Option<&'a str>,
+        pub liked_eq: Option<u32>,
+        pub has_likes: Option<bool>,
         pub pinned: Option<HashSet<u64>>,
         pub pinned_num: Option<&'a str>,
+        pub pinned_eq: Option<u32>,
+        pub has_pins: Option<bool>,
+        pub min_engagement: Option<u32>,
+        pub lang: Option<&'a str>,
+        pub self_unengaged: Option<bool>,
+        pub created_hour: Option<&'a str>,
+        #[serde(borrow)]
+        pub created_weekday: Option<Vec<&'a str>>,
+        pub created_range: Option<&'a str>,
+        pub edited_after: Option<&'a str>,
+        pub pinned_not_liked_by: Option<u64>,
+        pub tags: Option<HashSet<String>>,
+        pub bookmarked: Option<bool>,
+        pub author_registered: Option<bool>,
+        #[serde(borrow)]
+        pub any_of: Option<Vec<ContentQueryModel<'a>>>,
+        pub sort: Option<ContentSortModel>,
+    }
+    #[derive(::serde::Deserialize)]
+    pub struct ContentSortModel {
+        pub key: SortKey,
+        #[serde(default)]
+        pub desc: bool,
     }
     #[derive(::serde::Deserialize)]
     pub enum AuthorQueryModel<'a> {
@@ -86,6 +191,8 @@ pub fn parse_content_query(s: &str) -> ::core::result::Result<ContentQuery, Stri
         UserNick(&'a str),
         Virtual(&'a str),
         Any(&'a str),
+        AnyOfUsers(HashSet<u64>),
+        NameContains(&'a str),
     }
     #[derive(::serde::Deserialize)]
     pub enum PostedQueryModel<'a> {
@@ -95,80 +202,250 @@ pub fn parse_content_query(s: &str) -> ::core::result::Result<ContentQuery, Stri
         Any(&'a str),
     }
 
-    // --- parsing json ---
-
-    let ContentQueryModel {
-        author: author_raw,
-        posted: posted_raw,
-        content: content_raw,
-        liked: liked_raw,
-        liked_num: liked_num_raw,
-        pinned: pinned_raw,
-        pinned_num: pinned_num_raw,
-    } = serde_json::from_str(s).map_err(|e| e.to_string())?;
-
-    // --- converting ---
-
-    let author = author_raw
-        .map(|m| match m {
-            AuthorQueryModel::UserId(n) => n.let_(Ok).map(UserId).map(AuthorQuery::UserId),
-            AuthorQueryModel::UserName(s) => Regex::new(s)
-                .map(AuthorQuery::UserName)
-                .map_err(|e| e.to_string()),
-            AuthorQueryModel::UserNick(s) => Regex::new(s)
-                .map(AuthorQuery::UserNick)
-                .map_err(|e| e.to_string()),
-            AuthorQueryModel::Virtual(s) => Regex::new(s)
-                .map(AuthorQuery::Virtual)
-                .map_err(|e| e.to_string()),
-            AuthorQueryModel::Any(s) => Regex::new(s)
-                .map(AuthorQuery::Any)
-                .map_err(|e| e.to_string()),
+    fn convert(
+        ContentQueryModel {
+            author: author_raw,
+            posted: posted_raw,
+            content: content_raw,
+            content_exact,
+            text: text_raw,
+            liked: liked_raw,
+            liked_num: liked_num_raw,
+            liked_eq,
+            has_likes,
+            pinned: pinned_raw,
+            pinned_num: pinned_num_raw,
+            pinned_eq,
+            has_pins,
+            min_engagement,
+            lang: lang_raw,
+            self_unengaged,
+            created_hour: created_hour_raw,
+            created_weekday: created_weekday_raw,
+            created_range: created_range_raw,
+            edited_after: edited_after_raw,
+            pinned_not_liked_by: pinned_not_liked_by_raw,
+            tags,
+            bookmarked,
+            author_registered,
+            any_of: any_of_raw,
+            sort: sort_raw,
+        }: ContentQueryModel<'_>,
+        depth: usize,
+    ) -> ::core::result::Result<ContentQuery, String> {
+        // --- validating ---
+
+        if liked_num_raw.is_some() && liked_eq.is_some() {
+            return Err("liked_num and liked_eq cannot be used together".to_string());
+        }
+
+        if pinned_num_raw.is_some() && pinned_eq.is_some() {
+            return Err("pinned_num and pinned_eq cannot be used together".to_string());
+        }
+
+        if has_likes.is_some() && (liked_num_raw.is_some() || liked_eq.is_some()) {
+            return Err("has_likes cannot be used with liked_num or liked_eq".to_string());
+        }
+
+        if has_pins.is_some() && (pinned_num_raw.is_some() || pinned_eq.is_some()) {
+            return Err("has_pins cannot be used with pinned_num or pinned_eq".to_string());
+        }
+
+        if depth > 0 && bookmarked.is_some() {
+            return Err("bookmarked cannot be used in any_of".to_string());
+        }
+
+        if depth > 0 && author_registered.is_some() {
+            return Err("author_registered cannot be used in any_of".to_string());
+        }
+
+        if depth > 0 && text_raw.is_some() {
+            return Err("text cannot be used in any_of".to_string());
+        }
+
+        if depth > 0 && sort_raw.is_some() {
+            return Err("sort cannot be used in any_of".to_string());
+        }
+
+        if let Some(s) = &liked_raw {
+            check_query_ids("liked", s.len(), MAX_QUERY_IDS)?;
+        }
+
+        if let Some(s) = &pinned_raw {
+            check_query_ids("pinned", s.len(), MAX_QUERY_IDS)?;
+        }
+
+        // --- converting ---
+
+        let author = author_raw
+            .map(|m| match m {
+                AuthorQueryModel::UserId(n) => n.let_(Ok).map(UserId).map(AuthorQuery::UserId),
+                AuthorQueryModel::UserName(s) => Regex::new(s)
+                    .map(AuthorQuery::UserName)
+                    .map_err(|e| e.to_string()),
+                AuthorQueryModel::UserNick(s) => Regex::new(s)
+                    .map(AuthorQuery::UserNick)
+                    .map_err(|e| e.to_string()),
+                AuthorQueryModel::Virtual(s) => Regex::new(s)
+                    .map(AuthorQuery::Virtual)
+                    .map_err(|e| e.to_string()),
+                AuthorQueryModel::Any(s) => Regex::new(s)
+                    .map(AuthorQuery::Any)
+                    .map_err(|e| e.to_string()),
+                AuthorQueryModel::AnyOfUsers(mut s) => s
+                    .drain()
+                    .map(UserId)
+                    .collect::<HashSet<_>>()
+                    .let_(AuthorQuery::AnyOfUsers)
+                    .let_(Ok),
+                AuthorQueryModel::NameContains(s) =>
+                    s.to_lowercase().let_(AuthorQuery::NameContains).let_(Ok),
+            })
+            .transpose()?;
+
+        let posted = posted_raw
+            .map(|m| match m {
+                PostedQueryModel::UserId(n) => n.let_(Ok).map(UserId).map(PostedQuery::UserId),
+                PostedQueryModel::UserName(s) => Regex::new(s)
+                    .map(PostedQuery::UserName)
+                    .map_err(|e| e.to_string()),
+                PostedQueryModel::UserNick(s) => Regex::new(s)
+                    .map(PostedQuery::UserNick)
+                    .map_err(|e| e.to_string()),
+                PostedQueryModel::Any(s) => Regex::new(s)
+                    .map(PostedQuery::Any)
+                    .map_err(|e| e.to_string()),
+            })
+            .transpose()?;
+
+        let content = content_raw
+            .map(|s| Regex::new(s).map_err(|e| e.to_string()))
+            .transpose()?;
+
+        let text = text_raw.map(|s| s.to_string());
+
+        let liked = liked_raw.map(|mut s| s.drain().map(UserId).collect());
+
+        let liked_num = liked_num_raw
+            .map(|s| range_parser::parse(s.to_string()).map_err(|e| e.to_string()))
+            .transpose()?;
+
+        let pinned = pinned_raw.map(|mut s| s.drain().map(UserId).collect());
+
+        let pinned_num = pinned_num_raw
+            .map(|s| range_parser::parse(s.to_string()).map_err(|e| e.to_string()))
+            .transpose()?;
+
+        let created_hour = created_hour_raw
+            .map(|s| range_parser::parse(s.to_string()).map_err(|e| e.to_string()))
+            .transpose()?;
+
+        let created_weekday = created_weekday_raw
+            .map(|v| {
+                v.iter()
+                    .map(|s| {
+                        s.parse::<::chrono::Weekday>()
+                            .map_err(|_| format!("invalid weekday: {}", s))
+                    })
+                    .collect::<::core::result::Result<HashSet<_>, _>>()
+            })
+            .transpose()?;
+
+        let created_range = created_range_raw.map(parse_date_range).transpose()?;
+
+        let edited_after = edited_after_raw.map(parse_date).transpose()?;
+
+        let pinned_not_liked_by = pinned_not_liked_by_raw.map(UserId);
+
+        let sort = sort_raw.map(|ContentSortModel { key, desc }| ContentSort { key, desc });
+
+        let any_of = any_of_raw
+            .map(|mut v| {
+                if depth >= MAX_QUERY_DEPTH {
+                    return Err(format!(
+                        "any_of is nested too deeply (max: {})",
+                        MAX_QUERY_DEPTH
+                    ));
+                }
+
+                v.drain(..)
+                    .map(|m| convert(m, depth + 1))
+                    .collect::<::core::result::Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+
+        let lang = lang_raw.map(|s| s.to_lowercase());
+
+        // --- finalize ---
+
+        Ok(ContentQuery {
+            author,
+            posted,
+            content,
+            content_exact,
+            text,
+            liked,
+            liked_num,
+            liked_eq,
+            has_likes,
+            pinned,
+            pinned_num,
+            pinned_eq,
+            has_pins,
+            min_engagement,
+            lang,
+            self_unengaged,
+            created_hour,
+            created_weekday,
+            created_range,
+            edited_after,
+            pinned_not_liked_by,
+            tags,
+            bookmarked,
+            author_registered,
+            any_of,
+            sort,
+            // given by flag, not by query.
+            include_archived: false,
         })
-        .transpose()?;
-
-    let posted = posted_raw
-        .map(|m| match m {
-            PostedQueryModel::UserId(n) => n.let_(Ok).map(UserId).map(PostedQuery::UserId),
-            PostedQueryModel::UserName(s) => Regex::new(s)
-                .map(PostedQuery::UserName)
-                .map_err(|e| e.to_string()),
-            PostedQueryModel::UserNick(s) => Regex::new(s)
-                .map(PostedQuery::UserNick)
-                .map_err(|e| e.to_string()),
-            PostedQueryModel::Any(s) => Regex::new(s)
-                .map(PostedQuery::Any)
-                .map_err(|e| e.to_string()),
-        })
-        .transpose()?;
-
-    let content = content_raw
-        .map(|s| Regex::new(s).map_err(|e| e.to_string()))
-        .transpose()?;
+    }
 
-    let liked = liked_raw.map(|mut s| s.drain().map(UserId).collect());
+    // --- parsing json ---
 
-    let liked_num = liked_num_raw
-        .map(|s| range_parser::parse(s.to_string()).map_err(|e| e.to_string()))
-        .transpose()?;
+    let model = serde_json::from_str(s).map_err(|e| e.to_string())?;
 
-    let pinned = pinned_raw.map(|mut s| s.drain().map(UserId).collect());
+    convert(model, 0)
+}
 
-    let pinned_num = pinned_num_raw
-        .map(|s| range_parser::parse(s.to_string()).map_err(|e| e.to_string()))
-        .transpose()?;
+fn parse_date(s: &str) -> ::core::result::Result<Date, String> {
+    ::chrono::DateTime::parse_from_rfc3339(s)
+        .map(|d| d.with_timezone(&::chrono::Utc))
+        .map_err(|e| format!("invalid date: {} ({})", s, e))
+}
 
-    // --- finalize ---
+/// `a..b`, `a..=b`, `a..`, `..b` or `..=b`.
+fn parse_date_range(s: &str) -> ::core::result::Result<(Bound<Date>, Bound<Date>), String> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or_else(|| format!("invalid range: {}", s))?;
+
+    let start = match start {
+        "" => Bound::Unbounded,
+        s => parse_date(s)?.let_(Bound::Included),
+    };
+    let end = match end.strip_prefix('=') {
+        Some("") => return Err(format!("invalid range: {}", s)),
+        Some(s) => parse_date(s)?.let_(Bound::Included),
+        None if end.is_empty() => Bound::Unbounded,
+        None => parse_date(end)?.let_(Bound::Excluded),
+    };
+
+    Ok((start, end))
+}
 
-    Ok(ContentQuery {
-        author,
-        posted,
-        content,
-        liked,
-        liked_num,
-        pinned,
-        pinned_num,
-    })
+/// validates query, but keeps it raw.
+pub fn parse_raw_content_query(s: &str) -> ::core::result::Result<String, String> {
+    parse_content_query(s).map(|_| s.to_string())
 }
 
 pub fn parse_partial_content_mutation(
@@ -178,16 +455,26 @@ pub fn parse_partial_content_mutation(
     struct PartialContentMutationModel {
         author: Option<PartialAuthorModel>,
         content: Option<ContentContentMutationModel>,
+        title: Option<String>,
     }
     #[derive(::serde::Deserialize)]
     enum PartialAuthorModel {
-        User(u64),
+        User(UserIdModel),
         Virtual(String),
+        VirtualFromUser { id: UserIdModel },
+    }
+    #[derive(::serde::Deserialize)]
+    #[serde(untagged)]
+    enum UserIdModel {
+        Num(u64),
+        Str(String),
     }
     #[derive(::serde::Deserialize)]
     enum ContentContentMutationModel {
         Complete(String),
         Sed { capture: String, replace: String },
+        Append(String),
+        Prepend(String),
     }
 
     // --- parsing json ---
@@ -195,14 +482,26 @@ pub fn parse_partial_content_mutation(
     let PartialContentMutationModel {
         author: author_raw,
         content: content_raw,
+        title: title_raw,
     } = serde_json::from_str(s).map_err(|e| e.to_string())?;
 
     // --- converting ---
 
-    let author = author_raw.map(|m| match m {
-        PartialAuthorModel::User(n) => n.let_(UserId).let_(PartialAuthor::User),
-        PartialAuthorModel::Virtual(s) => s.let_(PartialAuthor::Virtual),
-    });
+    let author = author_raw
+        .map(|m| match m {
+            PartialAuthorModel::User(UserIdModel::Num(n)) =>
+                n.let_(UserId).let_(PartialAuthor::User).let_(Ok),
+            PartialAuthorModel::User(UserIdModel::Str(s)) => parse_user_id(s.as_str())
+                .map(UserId)
+                .map(PartialAuthor::User),
+            PartialAuthorModel::Virtual(s) => s.let_(PartialAuthor::Virtual).let_(Ok),
+            PartialAuthorModel::VirtualFromUser { id } => match id {
+                UserIdModel::Num(n) => Ok(n),
+                UserIdModel::Str(s) => parse_user_id(s.as_str()),
+            }
+            .map(|n| PartialAuthor::VirtualFromUser { id: UserId(n) }),
+        })
+        .transpose()?;
 
     let content = content_raw
         .map(|m| match m {
@@ -216,10 +515,76 @@ pub fn parse_partial_content_mutation(
                 .let_(Regex::new)
                 .map(|capture| ContentContentMutation::Sed { capture, replace })
                 .map_err(|e| e.to_string()),
+            ContentContentMutationModel::Append(s) =>
+                s.let_(ContentContentMutation::Append).let_(Ok),
+            ContentContentMutationModel::Prepend(s) =>
+                s.let_(ContentContentMutation::Prepend).let_(Ok),
         })
         .transpose()?;
 
+    // empty string removes title.
+    let title = title_raw.map(|s| Some(s).filter(|s| !s.is_empty()));
+
     // --- finalize ---
 
-    Ok(PartialContentMutation { author, content })
+    Ok(PartialContentMutation {
+        author,
+        content,
+        title,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // serializes, deserializes and serializes again.
+    fn roundtrip<T>(query: T) -> (String, String)
+    where T: ::serde::Serialize + ::serde::de::DeserializeOwned {
+        let first = ::serde_json::to_string(&query).unwrap();
+        let query = ::serde_json::from_str::<T>(first.as_str()).unwrap();
+        let second = ::serde_json::to_string(&query).unwrap();
+
+        (first, second)
+    }
+
+    #[test]
+    fn content_query_roundtrip() {
+        let (first, second) = roundtrip(
+            parse_content_query(
+                r#"{
+                    "author": {"UserName": "^al"},
+                    "content": "^he(llo)?$",
+                    "liked_num": "1..=3",
+                    "pinned_num": "..2",
+                    "created_hour": "9..",
+                    "created_weekday": ["Mon"],
+                    "created_range": "2022-01-01T00:00:00Z..2023-01-01T00:00:00Z",
+                    "tags": ["rust"],
+                    "sort": {"key": "Created", "desc": true}
+                }"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(first, second);
+
+        let v: ::serde_json::Value = ::serde_json::from_str(first.as_str()).unwrap();
+        assert_eq!(v["author"]["UserName"], "^al");
+        assert_eq!(v["content"], "^he(llo)?$");
+        assert_eq!(v["liked_num"], "1..=3");
+        assert_eq!(v["pinned_num"], "..2");
+        assert_eq!(v["created_hour"], "9..");
+        assert_eq!(v["created_weekday"][0], "Mon");
+    }
+
+    #[test]
+    fn user_query_roundtrip() {
+        let (first, second) =
+            roundtrip(parse_user_query(r#"{"bookmark_num": "2..", "admin": true}"#).unwrap());
+        assert_eq!(first, second);
+
+        let v: ::serde_json::Value = ::serde_json::from_str(first.as_str()).unwrap();
+        assert_eq!(v["bookmark_num"], "2..");
+        assert_eq!(v["admin"], true);
+    }
 }
@@ -1,35 +1,72 @@
+mod appcmd;
+
+use alloc::sync::Arc;
 use std::collections::HashMap;
 
 use async_trait::async_trait;
 use serde_json::{json, Number, Value};
+use serenity::builder::{CreateEmbed, CreateMessage};
 use serenity::client::{Context, EventHandler};
-use serenity::model::channel::Message;
+use serenity::model::application::command::Command;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::{Interaction, InteractionResponseType};
+use serenity::model::channel::{AttachmentType, Embed, Message, Reaction, ReactionType};
+use serenity::model::gateway::Ready;
 use serenity::model::id::{ChannelId, GuildId, MessageId};
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
 use tracing::Instrument;
 
-use crate::controllers::serenity::SerenityReturnController;
+use crate::controllers::serenity::{
+    Executor, Pending, SerenityReturnController, StickyPost, CANCEL_EMOJI, CONFIRM_EMOJI,
+};
+use crate::entities::{ContentId, UserId};
+use crate::messages::{Locale, Msg};
+use crate::presenters::impls::serenity::{error_color, Attachment, Response, View};
 use crate::utils::{AlsoChain, LetChain};
 
+// discord rejects embeds with description longer than this.
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+// discord rejects messages with content longer than this.
+const MESSAGE_CONTENT_LIMIT: usize = 2000;
+// "```\n" + "\n```"
+const CODE_BLOCK_OVERHEAD: usize = 8;
+const MAX_ERROR_EMBEDS: usize = 5;
+// discord rejects messages with more embeds than this.
+const MAX_EMBEDS: usize = 10;
+
+// executed without confirmation. (see `withdraw_by_reaction`)
+const WITHDRAW_EMOJI: &str = "\u{274c}";
+
+const PREV_PAGE_EMOJI: &str = "\u{25c0}\u{fe0f}";
+const NEXT_PAGE_EMOJI: &str = "\u{25b6}\u{fe0f}";
+// pages are held in memory, so forgotten after this.
+const PAGER_TTL: Duration = Duration::from_secs(60 * 10);
+
 pub struct Conductor {
-    pub contr: SerenityReturnController,
+    pub contr: Arc<SerenityReturnController>,
+    /// paged replies keyed by message id. not persisted.
+    pub pagers: Mutex<HashMap<u64, Pager>>,
+    /// commands waiting for confirmation keyed by message id asking it. not
+    /// persisted.
+    pub confirmations: Mutex<HashMap<u64, Pending>>,
 }
 
-#[async_trait]
-impl EventHandler for Conductor {
-    async fn message(&self, ctx: Context, msg: Message) {
-        tracing::trace!("msg - {:?}", msg);
-
-        if msg.author.bot {
-            return;
-        }
+pub struct Pager {
+    pub pages: Vec<CreateEmbed>,
+    pub current: usize,
+    pub created: Instant,
+}
 
-        let res = match match self.contr.parse(&msg, &ctx).await {
+impl Conductor {
+    async fn reply(&self, ctx: &Context, msg: &Message) {
+        let res = match match self.contr.parse(msg, ctx.clone()).await {
             Some(r) => r,
             None => return,
         } {
-            Ok(mut sv) =>
+            Ok(Response::Embeds(mut sv)) =>
                 msg.channel_id
-                    .send_message(&ctx, |cm| {
+                    .send_message(ctx, |cm| {
                         #[allow(clippy::unit_arg)]
                         sv.drain(..)
                             .for_each(|v| cm.add_embed(v).let_(::core::mem::drop))
@@ -45,10 +82,31 @@ impl EventHandler for Conductor {
                     })
                     .instrument(tracing::trace_span!("send_message"))
                     .await,
-            Err(e) =>
+            Ok(Response::Paged(sv)) => self.reply_paged(ctx, msg, sv).await,
+            Ok(Response::Confirm(view, pending)) => match msg
+                .channel_id
+                .send_message(ctx, |cm| {
+                    cm.add_embed(view).also_(|cm| {
+                        append_message_reference(&mut cm.0, msg.id, msg.channel_id, msg.guild_id)
+                    })
+                })
+                .instrument(tracing::trace_span!("send_message"))
+                .await
+            {
+                Ok(m) => {
+                    self.ask_confirmation(ctx, &m, pending).await;
+                    Ok(m)
+                },
+                Err(e) => Err(e),
+            },
+            Ok(Response::File(Attachment { name, data })) =>
                 msg.channel_id
-                    .send_message(&ctx, |cm| {
-                        cm.content(format!("```{}```", e)).also_(|cm| {
+                    .send_message(ctx, |cm| {
+                        cm.add_file(AttachmentType::Bytes {
+                            data: data.into(),
+                            filename: name,
+                        })
+                        .also_(|cm| {
                             append_message_reference(
                                 &mut cm.0,
                                 msg.id,
@@ -59,6 +117,28 @@ impl EventHandler for Conductor {
                     })
                     .instrument(tracing::trace_span!("send_message"))
                     .await,
+            Ok(Response::Text(text)) =>
+                reply_chunks(ctx, msg, code_blocks(text.as_str(), ""), |cm, chunk| {
+                    cm.content(chunk)
+                })
+                .await,
+            Ok(Response::Json(text)) =>
+                reply_chunks(ctx, msg, code_blocks(text.as_str(), "json"), |cm, chunk| {
+                    cm.content(chunk)
+                })
+                .await,
+            Err(e) => {
+                let color = error_color(&e);
+                reply_chunks(
+                    ctx,
+                    msg,
+                    split_error(e.to_string().as_str(), self.contr.prefix.as_str()),
+                    |cm, chunk| {
+                        cm.embed(|ce| ce.color(color).description(error_description(&chunk)))
+                    },
+                )
+                .await
+            },
         };
 
         let e = match res {
@@ -81,8 +161,7 @@ impl EventHandler for Conductor {
             .channel_id
             .send_message(ctx, |cm| {
                 cm.content(format!(
-                    "error occurred.
-please send this message to administrator.
+                    "{}
 ```
 # from_msg
   - mid  : {}
@@ -96,6 +175,7 @@ please send this message to administrator.
 # err_msg
 {}
 ```",
+                    self.contr.locale.get(Msg::ErrorOccurred),
                     msg.id,
                     msg.channel_id,
                     msg.guild_id
@@ -125,6 +205,511 @@ please send this message to administrator.
             Err(e) => tracing::error!("cannot report err - {}", e),
         }
     }
+
+    async fn reply_paged(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        mut sv: ::smallvec::SmallVec<[Box<View>; 20]>,
+    ) -> ::serenity::Result<Message> {
+        let total = sv.len();
+        let pages = sv
+            .drain(..)
+            .enumerate()
+            .map(|(i, v)| {
+                let mut ce = CreateEmbed::default();
+                v(&mut ce);
+
+                // keeps footer set by presenter (e.g. page of list).
+                if total > 1 {
+                    let position = format!("{} / {}", i + 1, total);
+                    let text = match ce
+                        .0
+                        .get("footer")
+                        .and_then(|f| f.get("text"))
+                        .and_then(|t| t.as_str())
+                    {
+                        Some(t) => format!("{} | {}", t, position),
+                        None => position,
+                    };
+                    ce.footer(|cf| cf.text(text));
+                }
+
+                ce
+            })
+            .collect::<Vec<_>>();
+
+        let first = pages.first().cloned();
+        let replied = msg
+            .channel_id
+            .send_message(ctx, |cm| {
+                if let Some(page) = first {
+                    cm.add_embed(|ce| {
+                        *ce = page;
+                        ce
+                    });
+                }
+
+                cm.also_(|cm| {
+                    append_message_reference(&mut cm.0, msg.id, msg.channel_id, msg.guild_id)
+                })
+            })
+            .instrument(tracing::trace_span!("send_message"))
+            .await?;
+
+        if total <= 1 {
+            return Ok(replied);
+        }
+
+        for emoji in [PREV_PAGE_EMOJI, NEXT_PAGE_EMOJI] {
+            if let Err(e) = replied
+                .react(ctx, ReactionType::Unicode(emoji.to_string()))
+                .instrument(tracing::trace_span!("create_reaction"))
+                .await
+            {
+                tracing::warn!("reacting err - {:?}", e);
+            }
+        }
+
+        let mut guard = self.pagers.lock().await;
+        guard.retain(|_, p| p.created.elapsed() < PAGER_TTL);
+        guard.insert(replied.id.0, Pager {
+            pages,
+            current: 0,
+            created: Instant::now(),
+        });
+        drop(guard);
+
+        Ok(replied)
+    }
+
+    /// reacts for answers, then waits for them. see `answer_confirmation`.
+    async fn ask_confirmation(&self, ctx: &Context, asking: &Message, pending: Pending) {
+        for emoji in [CONFIRM_EMOJI, CANCEL_EMOJI] {
+            if let Err(e) = asking
+                .react(ctx, ReactionType::Unicode(emoji.to_string()))
+                .instrument(tracing::trace_span!("create_reaction"))
+                .await
+            {
+                tracing::warn!("reacting err - {:?}", e);
+            }
+        }
+
+        let mut guard = self.confirmations.lock().await;
+        guard.retain(|_, p| !p.is_expired());
+        guard.insert(asking.id.0, pending);
+    }
+
+    /// executes or cancels pending command if `reaction` is to the message
+    /// asking it, replacing the message with the result. returns whether
+    /// `reaction` was for confirmation.
+    async fn answer_confirmation(
+        &self,
+        ctx: &Context,
+        reaction: &Reaction,
+        user_id: UserId,
+        accept: bool,
+    ) -> bool {
+        let mut guard = self.confirmations.lock().await;
+        let pending = match guard.get(&reaction.message_id.0) {
+            // only the executor can answer.
+            Some(p) if p.user_id() == user_id => guard.remove(&reaction.message_id.0).unwrap(),
+            Some(_) => return true,
+            None => return false,
+        };
+        drop(guard);
+
+        let res = match accept {
+            true => self.contr.confirm(pending, ctx.clone()).await,
+            false => Ok(self.contr.cancelled(pending)),
+        };
+
+        let (embeds, content) = match res {
+            Ok(Response::Embeds(sv) | Response::Paged(sv)) => (
+                sv.into_iter()
+                    .take(MAX_EMBEDS)
+                    .map(|v| {
+                        let mut ce = CreateEmbed::default();
+                        v(&mut ce);
+                        ce
+                    })
+                    .collect(),
+                None,
+            ),
+            Ok(Response::Text(text)) =>
+                (vec![], Some(code_blocks(text.as_str(), "").swap_remove(0))),
+            Ok(Response::Json(text)) => (
+                vec![],
+                Some(code_blocks(text.as_str(), "json").swap_remove(0)),
+            ),
+            // destructive commands don't reply with them.
+            Ok(Response::File(_) | Response::Confirm(..)) => {
+                tracing::warn!("unexpected response to confirmation");
+                return true;
+            },
+            Err(e) => {
+                let mut ce = CreateEmbed::default();
+                ce.color(error_color(&e)).description(format!(
+                    "```\n{}\n```",
+                    split_error(e.to_string().as_str(), self.contr.prefix.as_str()).swap_remove(0)
+                ));
+                (vec![ce], None)
+            },
+        };
+
+        if let Err(e) = reaction
+            .channel_id
+            .edit_message(ctx, reaction.message_id, |em| {
+                if let Some(c) = content {
+                    em.content(c);
+                }
+                em.set_embeds(embeds)
+            })
+            .instrument(tracing::trace_span!("edit_message"))
+            .await
+        {
+            tracing::warn!("editing err - {:?}", e);
+        }
+
+        true
+    }
+
+    async fn interact(&self, ctx: &Context, cmd: &ApplicationCommandInteraction) {
+        let ex = Executor {
+            user_id: cmd.user.id.0.let_(UserId),
+            user_name: cmd.user.name.clone(),
+            user_nick: cmd.member.as_ref().and_then(|m| m.nick.clone()),
+            guild_id: cmd.guild_id.map(|i| i.0),
+            channel_id: cmd.channel_id.0,
+            timestamp: ::chrono::Utc::now(),
+        };
+
+        let res = match appcmd::to_args(&cmd.data, ex.user_id.0) {
+            Ok(args) => self.contr.interact(args, ex, ctx.clone()).await,
+            Err(e) => Err(e),
+        };
+
+        // responded as embed, then asked via the response.
+        let (res, pending) = match res {
+            Ok(Response::Confirm(view, pending)) => (
+                Ok(Response::Embeds(::smallvec::smallvec![view])),
+                Some(pending),
+            ),
+            res => (res, None),
+        };
+
+        let res = cmd
+            .create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| match res {
+                        // interaction responses cannot be paged by reactions, so only heads.
+                        Ok(Response::Embeds(mut sv) | Response::Paged(mut sv)) => d.add_embeds(
+                            sv.drain(..)
+                                .take(MAX_EMBEDS)
+                                .map(|v| {
+                                    let mut ce = CreateEmbed::default();
+                                    v(&mut ce);
+                                    ce
+                                })
+                                .collect(),
+                        ),
+                        Ok(Response::Confirm(..)) => unreachable!("converted above"),
+                        Ok(Response::File(Attachment { name, data })) =>
+                            d.add_file(AttachmentType::Bytes {
+                                data: data.into(),
+                                filename: name,
+                            }),
+                        Ok(Response::Text(text)) =>
+                            d.content(code_blocks(text.as_str(), "").swap_remove(0)),
+                        Ok(Response::Json(text)) =>
+                            d.content(code_blocks(text.as_str(), "json").swap_remove(0)),
+                        Err(e) => {
+                            let color = error_color(&e);
+                            let chunk =
+                                split_error(e.to_string().as_str(), self.contr.prefix.as_str())
+                                    .swap_remove(0);
+
+                            d.ephemeral(true).embed(|ce| {
+                                ce.color(color).description(format!("```\n{}\n```", chunk))
+                            })
+                        },
+                    })
+            })
+            .instrument(tracing::trace_span!("create_interaction_response"))
+            .await;
+
+        match res {
+            Ok(()) => tracing::info!(
+                "responded - id {} | channel_id {} | guild_id {}",
+                cmd.id,
+                cmd.channel_id,
+                cmd.guild_id
+                    .map(|i| i.to_string())
+                    .unwrap_or_else(|| "None".to_string()),
+            ),
+            Err(e) => return tracing::warn!("responding err - {:?}", e),
+        }
+
+        let pending = match pending {
+            Some(p) => p,
+            None => return,
+        };
+
+        match cmd
+            .get_interaction_response(&ctx.http)
+            .instrument(tracing::trace_span!("get_interaction_response"))
+            .await
+        {
+            Ok(m) => self.ask_confirmation(ctx, &m, pending).await,
+            Err(e) => tracing::warn!("getting response err - {:?}", e),
+        }
+    }
+
+    async fn turn_page(&self, ctx: &Context, reaction: &Reaction, forward: bool) {
+        let mut guard = self.pagers.lock().await;
+        let pager = match guard.get_mut(&reaction.message_id.0) {
+            Some(p) => p,
+            None => return,
+        };
+
+        if pager.created.elapsed() >= PAGER_TTL {
+            guard.remove(&reaction.message_id.0);
+            return;
+        }
+
+        let total = pager.pages.len();
+        pager.current = match forward {
+            true => (pager.current + 1) % total,
+            false => (pager.current + total - 1) % total,
+        };
+        let page = pager.pages[pager.current].clone();
+        drop(guard);
+
+        if let Err(e) = reaction
+            .channel_id
+            .edit_message(ctx, reaction.message_id, |em| {
+                em.embed(|ce| {
+                    *ce = page;
+                    ce
+                })
+            })
+            .instrument(tracing::trace_span!("edit_message"))
+            .await
+        {
+            return tracing::warn!("editing err - {:?}", e);
+        }
+
+        // so that same reaction can be used again. needs permission in guild, and
+        // impossible in DM.
+        if let Err(e) = reaction
+            .delete(ctx)
+            .instrument(tracing::trace_span!("delete_reaction"))
+            .await
+        {
+            tracing::debug!("deleting reaction err - {:?}", e);
+        }
+    }
+
+    async fn withdraw(&self, ctx: &Context, reaction: &Reaction, user_id: UserId) {
+        let bot_id = ctx.cache.current_user_id();
+
+        let mut msg = match reaction
+            .message(ctx)
+            .instrument(tracing::trace_span!("get_message"))
+            .await
+        {
+            Ok(m) => m,
+            Err(e) => return tracing::warn!("getting message err - {:?}", e),
+        };
+
+        if msg.author.id != bot_id {
+            return;
+        }
+
+        let content_id = match msg
+            .embeds
+            .first()
+            .and_then(|e| posted_content_id(e, self.contr.locale))
+        {
+            Some(i) => i,
+            None => return,
+        };
+
+        let view = match self.contr.withdraw_by_reaction(content_id, user_id).await {
+            Ok(v) => v,
+            Err(e) => return tracing::info!("ignored reaction - {}", e),
+        };
+
+        match msg
+            .edit(ctx, |em| em.embed(view))
+            .instrument(tracing::trace_span!("edit_message"))
+            .await
+        {
+            Ok(()) => tracing::info!(
+                "withdrew by reaction - id {} | channel_id {} | content_id {}",
+                msg.id,
+                msg.channel_id,
+                content_id
+            ),
+            Err(e) => tracing::warn!("editing err - {:?}", e),
+        }
+    }
+
+    async fn push_sticky(&self, ctx: &Context, msg: &Message) {
+        let channel_id = msg.channel_id;
+
+        let mut delay = match repost_sticky(&self.contr, ctx, channel_id, false).await {
+            Some(d) => d,
+            None => return,
+        };
+
+        // or it stays buried until another message arrives.
+        let (contr, ctx) = (self.contr.clone(), ctx.clone());
+        ::tokio::spawn(async move {
+            loop {
+                ::tokio::time::sleep(delay).await;
+
+                match repost_sticky(&contr, &ctx, channel_id, true).await {
+                    Some(d) => delay = d,
+                    None => break,
+                }
+            }
+        });
+    }
+}
+
+/// re-posts sticky content in the channel if it should be now. returns delay
+/// if throttled.
+async fn repost_sticky(
+    contr: &SerenityReturnController,
+    ctx: &Context,
+    channel_id: ChannelId,
+    deferred: bool,
+) -> Option<Duration> {
+    let (view, prev) = match contr.sticky(channel_id.0, deferred).await? {
+        StickyPost::Now(v, p) => (v, p),
+        StickyPost::Later(d) => return Some(d),
+    };
+
+    if let Some(i) = prev {
+        if let Err(e) = channel_id
+            .delete_message(ctx, i)
+            .instrument(tracing::trace_span!("delete_message"))
+            .await
+        {
+            tracing::warn!("deleting sticky err - {:?}", e);
+        }
+    }
+
+    match channel_id
+        .send_message(ctx, |cm| cm.add_embed(view))
+        .instrument(tracing::trace_span!("send_message"))
+        .await
+    {
+        Ok(m) => contr.sticky_posted(channel_id.0, m.id.0).await,
+        Err(e) => tracing::warn!("posting sticky err - {:?}", e),
+    }
+
+    None
+}
+
+#[async_trait]
+impl EventHandler for Conductor {
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        tracing::info!(
+            "ready - user {} | guilds {}",
+            ready.user.id,
+            ready.guilds.len()
+        );
+
+        match Command::set_global_application_commands(&ctx.http, appcmd::create_commands)
+            .instrument(tracing::trace_span!("set_global_application_commands"))
+            .await
+        {
+            Ok(cs) => tracing::info!("registered slash commands - {}", cs.len()),
+            Err(e) => tracing::warn!("registering slash commands err - {:?}", e),
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        tracing::trace!("interaction - {:?}", interaction);
+
+        if let Interaction::ApplicationCommand(cmd) = interaction {
+            self.interact(&ctx, &cmd).await;
+        }
+    }
+
+    async fn message(&self, ctx: Context, msg: Message) {
+        tracing::trace!("msg - {:?}", msg);
+
+        if msg.author.bot {
+            return;
+        }
+
+        self.reply(&ctx, &msg).await;
+        self.push_sticky(&ctx, &msg).await;
+    }
+
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        tracing::trace!("reaction - {:?}", reaction);
+
+        let user_id = match reaction.user_id {
+            Some(i) => i,
+            None => return,
+        };
+
+        if user_id == ctx.cache.current_user_id() {
+            return;
+        }
+
+        let emoji = match &reaction.emoji {
+            ReactionType::Unicode(s) => s.as_str(),
+            _ => return,
+        };
+
+        let accept = match emoji {
+            CONFIRM_EMOJI => Some(true),
+            CANCEL_EMOJI => Some(false),
+            _ => None,
+        };
+        if let Some(a) = accept {
+            if self
+                .answer_confirmation(&ctx, &reaction, user_id.0.let_(UserId), a)
+                .await
+            {
+                return;
+            }
+        }
+
+        match emoji {
+            WITHDRAW_EMOJI => self.withdraw(&ctx, &reaction, user_id.0.let_(UserId)).await,
+            PREV_PAGE_EMOJI => self.turn_page(&ctx, &reaction, false).await,
+            NEXT_PAGE_EMOJI => self.turn_page(&ctx, &reaction, true).await,
+            _ => (),
+        }
+    }
+}
+
+// recognized by prefix of title (see presenters), so contents posted in other
+// locale are not.
+fn posted_content_id(embed: &Embed, locale: Locale) -> Option<ContentId> {
+    if !embed
+        .title
+        .as_deref()?
+        .starts_with(locale.get(Msg::PostedContent))
+    {
+        return None;
+    }
+
+    // description is formatted as "{id} ({short_id})".
+    embed
+        .description
+        .as_deref()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+        .map(ContentId)
 }
 
 fn append_message_reference(
@@ -144,3 +729,190 @@ fn append_message_reference(
 
     raw.insert("message_reference", mr);
 }
+
+/// replies each chunk as separated message, and stops at first failure.
+async fn reply_chunks<F>(
+    ctx: &Context,
+    msg: &Message,
+    chunks: Vec<String>,
+    build: F,
+) -> ::serenity::Result<Message>
+where
+    F: for<'a, 'b> Fn(&'b mut CreateMessage<'a>, String) -> &'b mut CreateMessage<'a>,
+{
+    let mut res = None;
+
+    for chunk in chunks {
+        let r = msg
+            .channel_id
+            .send_message(ctx, |cm| {
+                build(cm, chunk).also_(|cm| {
+                    append_message_reference(&mut cm.0, msg.id, msg.channel_id, msg.guild_id)
+                })
+            })
+            .instrument(tracing::trace_span!("send_message"))
+            .await;
+
+        let is_err = r.is_err();
+        res = Some(r);
+
+        if is_err {
+            break;
+        }
+    }
+
+    res.expect("no chunks to reply")
+}
+
+/// `text` in code blocks highlighted as `lang`, split to fit in messages.
+/// text is kept as is, so that it can be copied.
+fn code_blocks(text: &str, lang: &str) -> Vec<String> {
+    // longer than any backtick run in text, or code block will be closed by it.
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    let overhead = fence.len() * 2 + lang.len() + 2;
+
+    let mut chunks = split_by_sections(text, MESSAGE_CONTENT_LIMIT.saturating_sub(overhead).max(1));
+
+    if chunks.is_empty() {
+        chunks.push(String::new());
+    }
+
+    chunks
+        .into_iter()
+        .map(|c| format!("{}{}\n{}\n{}", fence, lang, c, fence))
+        .collect()
+}
+
+fn split_error(text: &str, prefix: &str) -> Vec<String> {
+    let mut chunks = split_by_sections(text, EMBED_DESCRIPTION_LIMIT - CODE_BLOCK_OVERHEAD);
+
+    // the pointer takes the last one.
+    if chunks.len() > MAX_ERROR_EMBEDS {
+        chunks.truncate(MAX_ERROR_EMBEDS - 1);
+        chunks.push(format!(
+            "output is too long, truncated.\nsee `{} help <subcommand>`.",
+            prefix
+        ));
+    }
+
+    if chunks.is_empty() {
+        chunks.push(String::new());
+    }
+
+    chunks
+}
+
+fn error_description(chunk: &str) -> String { format!("```\n{}\n```", chunk) }
+
+/// splits `text` into chunks not longer than `limit`.
+/// breaks at blank lines (= clap's help sections) if possible, otherwise at
+/// lines.
+fn split_by_sections(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut current = String::new();
+
+    for section in text.split("\n\n") {
+        if section.len() <= limit {
+            append_piece(&mut chunks, &mut current, section, "\n\n", limit);
+            continue;
+        }
+
+        let mut sep = "\n\n";
+        for line in section.lines() {
+            let mut rest = line;
+
+            while rest.len() > limit {
+                let mut at = limit;
+                while !rest.is_char_boundary(at) {
+                    at -= 1;
+                }
+
+                let (head, tail) = rest.split_at(at);
+                append_piece(&mut chunks, &mut current, head, sep, limit);
+                sep = "\n";
+                rest = tail;
+            }
+
+            append_piece(&mut chunks, &mut current, rest, sep, limit);
+            sep = "\n";
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+fn append_piece(
+    chunks: &mut Vec<String>,
+    current: &mut String,
+    piece: &str,
+    sep: &str,
+    limit: usize,
+) {
+    if current.is_empty() {
+        current.push_str(piece);
+    } else if current.len() + sep.len() + piece.len() <= limit {
+        current.push_str(sep);
+        current.push_str(piece);
+    } else {
+        chunks.push(::core::mem::take(current));
+        current.push_str(piece);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_error_fits_embeds() {
+        let line = "x".repeat(100);
+        let section = vec![line.as_str(); 30].join("\n");
+        // long lines, many sections, and multibyte chars around limits.
+        for text in [
+            vec![section.as_str(); 3].join("\n\n"),
+            vec![section.as_str(); 100].join("\n\n"),
+            "y".repeat(EMBED_DESCRIPTION_LIMIT * 3),
+            "\u{3042}".repeat(EMBED_DESCRIPTION_LIMIT),
+        ] {
+            let chunks = split_error(text.as_str(), "*ip");
+
+            assert!(chunks.len() <= MAX_ERROR_EMBEDS);
+            for c in chunks {
+                assert!(error_description(&c).len() <= EMBED_DESCRIPTION_LIMIT);
+            }
+        }
+    }
+
+    #[test]
+    fn code_blocks_keep_fences_in_text() {
+        let text = "a\n```rust\nfn main() {}\n```\nb ```` c";
+        let blocks = code_blocks(text, "");
+
+        assert_eq!(blocks, vec![format!("`````\n{}\n`````", text)]);
+    }
+
+    #[test]
+    fn code_blocks_fit_messages() {
+        let text = format!("```{}", "d".repeat(MESSAGE_CONTENT_LIMIT * 3));
+
+        for b in code_blocks(text.as_str(), "json") {
+            assert!(b.len() <= MESSAGE_CONTENT_LIMIT);
+            assert!(b.starts_with("````json\n"));
+        }
+    }
+
+    #[test]
+    fn split_error_points_help_when_truncated() {
+        let text = "z".repeat(EMBED_DESCRIPTION_LIMIT * (MAX_ERROR_EMBEDS + 2));
+        let chunks = split_error(text.as_str(), "!ip");
+
+        assert_eq!(chunks.len(), MAX_ERROR_EMBEDS);
+        assert!(chunks.last().unwrap().contains("truncated"));
+        assert!(chunks.last().unwrap().contains("`!ip help <subcommand>`"));
+    }
+}
@@ -0,0 +1,152 @@
+//! slash commands (application commands). each one is a shorthand of text
+//! command, and mapped to its arguments.
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+use serenity::builder::{CreateApplicationCommandOption, CreateApplicationCommands};
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::application::interaction::application_command::CommandData;
+
+fn content_id_option(
+    o: &mut CreateApplicationCommandOption,
+) -> &mut CreateApplicationCommandOption {
+    o.name("content_id")
+        .description("uuid")
+        .kind(CommandOptionType::String)
+        .required(true)
+}
+
+fn undo_option(
+    o: &mut CreateApplicationCommandOption,
+    description: &str,
+) -> &mut CreateApplicationCommandOption {
+    o.name("undo")
+        .description(description)
+        .kind(CommandOptionType::Boolean)
+}
+
+pub(super) fn create_commands(
+    cs: &mut CreateApplicationCommands,
+) -> &mut CreateApplicationCommands {
+    cs.create_application_command(|c| c.name("register").description("register yourself."))
+        .create_application_command(|c| {
+            c.name("get").description("get user.").create_option(|o| {
+                o.name("user")
+                    .description("defaults to yourself.")
+                    .kind(CommandOptionType::User)
+            })
+        })
+        .create_application_command(|c| {
+            c.name("post")
+                .description("post content.")
+                .create_option(|o| {
+                    o.name("content")
+                        .description("str")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+                .create_option(|o| {
+                    o.name("virtual")
+                        .description("name of author. (exclusive with `user`)")
+                        .kind(CommandOptionType::String)
+                })
+                .create_option(|o| {
+                    o.name("user")
+                        .description("author. defaults to yourself.")
+                        .kind(CommandOptionType::User)
+                })
+                .create_option(|o| {
+                    o.name("title")
+                        .description("shown as heading")
+                        .kind(CommandOptionType::String)
+                })
+                .create_option(|o| {
+                    o.name("source_url")
+                        .description("http(s) url, linked from heading")
+                        .kind(CommandOptionType::String)
+                })
+                .create_option(|o| {
+                    o.name("anon")
+                        .description("hide poster from others")
+                        .kind(CommandOptionType::Boolean)
+                })
+        })
+        .create_application_command(|c| {
+            c.name("like")
+                .description("like content.")
+                .create_option(content_id_option)
+                .create_option(|o| undo_option(o, "unlike instead."))
+        })
+        .create_application_command(|c| {
+            c.name("bookmark")
+                .description("bookmark content.")
+                .create_option(content_id_option)
+                .create_option(|o| undo_option(o, "unbookmark instead."))
+        })
+}
+
+/// arguments of text command (without prefix), so that parsed (and
+/// validated) as same as text.
+pub(super) fn to_args(data: &CommandData, ex_user_id: u64) -> Result<Vec<String>> {
+    let value = |name: &str| {
+        data.options
+            .iter()
+            .find(|o| o.name == name)
+            .and_then(|o| o.value.as_ref())
+    };
+    // users are given as id strings too.
+    let string = |name: &str| value(name).and_then(Value::as_str).map(|s| s.to_string());
+    let flag = |name: &str| value(name).and_then(Value::as_bool).unwrap_or(false);
+    let required = |name: &str| string(name).ok_or_else(|| anyhow!("{} is required.", name));
+    let do_or_undo = || match flag("undo") {
+        true => "undo".to_string(),
+        false => "do".to_string(),
+    };
+
+    let args = match data.name.as_str() {
+        "register" => vec!["user".to_string(), "register".to_string()],
+        "get" => ["user".to_string(), "get".to_string()]
+            .into_iter()
+            .chain(string("user"))
+            .collect(),
+        "post" => {
+            let mut args = vec![
+                "content".to_string(),
+                "post".to_string(),
+                // `=` form, so that values starting with `-` are not taken as flags.
+                format!("--content={}", required("content")?),
+            ];
+
+            // both are passed if given, and rejected by parser.
+            match (string("virtual"), string("user")) {
+                (None, None) => args.push(format!("--user-id={}", ex_user_id)),
+                (v, u) => {
+                    args.extend(v.map(|v| format!("--virt={}", v)));
+                    args.extend(u.map(|u| format!("--user-id={}", u)));
+                },
+            }
+            args.extend(string("title").map(|t| format!("--title={}", t)));
+            args.extend(string("source_url").map(|u| format!("--source-url={}", u)));
+            if flag("anon") {
+                args.push("--anon".to_string());
+            }
+
+            args
+        },
+        "like" => vec![
+            "content".to_string(),
+            "like".to_string(),
+            do_or_undo(),
+            required("content_id")?,
+        ],
+        "bookmark" => vec![
+            "user".to_string(),
+            "bookmark".to_string(),
+            do_or_undo(),
+            required("content_id")?,
+        ],
+        n => bail!("unknown command: {}", n),
+    };
+
+    Ok(args)
+}
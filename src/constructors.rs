@@ -1,244 +1,819 @@
 use alloc::sync::Arc;
+use std::collections::HashMap;
 
 use serenity::client::EventHandler;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex, RwLock};
 
 use crate::conductors::Conductor;
 use crate::controllers::ret::content::ReturnContentController;
 use crate::controllers::ret::user::ReturnUserController;
 use crate::controllers::serenity::content::SerenityContentController;
 use crate::controllers::serenity::user::SerenityUserController;
-use crate::controllers::serenity::SerenityReturnController;
+use crate::controllers::serenity::{RateLimiter, SerenityReturnController};
 use crate::entities::*;
 use crate::interactors::content::*;
 use crate::interactors::user::*;
-use crate::presenters::impls::ret::content::ReturnContentGetPresenter;
-use crate::presenters::impls::ret::user::ReturnUserGetPresenter;
+use crate::messages::Locale;
+use crate::notifications::{HttpSink, NoopSink};
+use crate::presenters::impls::ret::content::{
+    ReturnContentGetPresenter, ReturnContentGetsPresenter, ReturnContentResolvePresenter,
+};
+use crate::presenters::impls::ret::user::{ReturnUserGetPresenter, ReturnUserGetsPresenter};
 use crate::presenters::impls::serenity::content::*;
 use crate::presenters::impls::serenity::user::*;
 use crate::repositories::*;
 
+/// max items in a page. each item is an embed, paged by reactions, so this is
+/// not discord's limit of embeds in a message (10); slash command replies show
+/// only first 10 of them.
+pub const MAX_PAGE_SIZE: usize = 25;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// max number of items listed in a embed field.
+    pub list_cap: usize,
+    /// max number of contents posted by a user (admins are exempt). `None`
+    /// means unlimited.
+    pub max_contents_per_user: Option<usize>,
+    /// max number of chars in content, on posting and editing. defaults to
+    /// discord's limit of embed field value.
+    pub max_content_len: usize,
+    /// min interval between posts of a user. `None` means no cooldown.
+    pub post_cooldown: Option<::core::time::Duration>,
+    /// max edit distance to suggest subcommand for unrecognized one. 0
+    /// disables (shows help instead).
+    pub suggest_distance: usize,
+    /// first token of commands. leading mention of the bot is also accepted.
+    pub prefix: String,
+    /// max number of commands per user in a window. `None` means unlimited.
+    /// admins are exempt.
+    pub rate_limit: Option<(u32, ::core::time::Duration)>,
+    /// whether to store authors not existing on discord (e.g. deleted) with
+    /// name `<unknown>`, instead of rejecting.
+    pub allow_unknown_author: bool,
+    /// whether to store authors as placeholder `user:{id}` (virtual) on
+    /// editing, if cannot look up them on discord.
+    pub virtual_author_fallback: bool,
+    /// language of replies.
+    pub locale: Locale,
+    /// max attempts and first delay of connecting to mongo on startup. delay
+    /// doubles for each retry.
+    pub mongo_retry: (u32, ::core::time::Duration),
+    /// max number of contents cached in memory (by id). `None` disables
+    /// caching.
+    pub content_cache: Option<usize>,
+    /// max number of ids in sets of queries (e.g. `liked`). larger than
+    /// parser's limit (100) has no effect.
+    pub max_query_ids: usize,
+    /// time to wait for confirmation of destructive commands (e.g.
+    /// `content withdraw`) by reaction. `None` executes them immediately.
+    pub confirm_timeout: Option<::core::time::Duration>,
+    /// whether to reject posting the same content as existing one by the
+    /// same user. only for in-memory.
+    pub reject_duplicate_body: bool,
+    /// url to post json of new contents to. `None` notifies nothing.
+    pub notify_url: Option<String>,
+    /// operations on mongo taking longer than this are warned in logs. `None`
+    /// disables.
+    pub slow_query: Option<::core::time::Duration>,
+    /// number of items per page of `gets` and `similar`. at most
+    /// [`MAX_PAGE_SIZE`].
+    pub gets_page_size: usize,
+    /// number of items per page of liked, pinned and bookmarked lists. at
+    /// most [`MAX_PAGE_SIZE`].
+    pub list_page_size: usize,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            list_cap: 20,
+            max_contents_per_user: None,
+            max_content_len: 1024,
+            post_cooldown: None,
+            suggest_distance: 2,
+            prefix: "*ip".to_string(),
+            rate_limit: None,
+            allow_unknown_author: false,
+            virtual_author_fallback: false,
+            locale: Locale::default(),
+            mongo_retry: (5, ::core::time::Duration::from_secs(1)),
+            content_cache: None,
+            max_query_ids: crate::cmds::parser::MAX_QUERY_IDS,
+            confirm_timeout: Some(::core::time::Duration::from_secs(30)),
+            reject_duplicate_body: false,
+            notify_url: None,
+            slow_query: Some(::core::time::Duration::from_millis(500)),
+            gets_page_size: 5,
+            list_page_size: 20,
+        }
+    }
+}
+
 fn contr(
+    config: &Config,
     user_contr: SerenityUserController,
     content_contr: SerenityContentController,
     user_repo: Arc<dyn UserRepository + Sync + Send>,
     content_repo: Arc<dyn ContentRepository + Sync + Send>,
+    health: Arc<dyn HealthRepository + Sync + Send>,
 ) -> SerenityReturnController {
     let (user_in, user_out) = mpsc::channel(1);
+    let (user_gets_in, user_gets_out) = mpsc::channel(1);
     let (content_in, content_out) = mpsc::channel(1);
+    let (content_gets_in, content_gets_out) = mpsc::channel(1);
+    let (resolve_in, resolve_out) = mpsc::channel(1);
 
     SerenityReturnController {
         user: user_contr,
         content: content_contr,
+        guild_default_queries: RwLock::new(HashMap::new()),
+        templates: RwLock::new(HashMap::new()),
+        saved_queries: RwLock::new(HashMap::new()),
+        stickies: Mutex::new(HashMap::new()),
+        suggest_distance: config.suggest_distance,
+        prefix: config.prefix.clone(),
+        rate_limiter: config.rate_limit.map(|(n, w)| RateLimiter::new(n, w)),
+        health,
+        allow_unknown_author: config.allow_unknown_author,
+        virtual_author_fallback: config.virtual_author_fallback,
+        confirm_timeout: config.confirm_timeout,
+        locale: config.locale,
         return_user_contr: ReturnUserController {
             usecase: Arc::new(UserGetInteractor {
                 user_repository: user_repo.clone(),
+                content_repository: content_repo.clone(),
                 pres: Arc::new(ReturnUserGetPresenter { ret: user_in }),
             }),
             lock: Mutex::new(()),
             ret: Mutex::new(user_out),
+
+            gets_usecase: Arc::new(UserGetsInteractor {
+                user_repository: user_repo.clone(),
+                max_query_ids: config.max_query_ids,
+                page_size: config.gets_page_size,
+                pres: Arc::new(ReturnUserGetsPresenter { ret: user_gets_in }),
+            }),
+            gets_lock: Mutex::new(()),
+            gets_ret: Mutex::new(user_gets_out),
         },
         return_content_contr: ReturnContentController {
             usecase: Arc::new(ContentGetInteractor {
+                user_repository: user_repo.clone(),
                 content_repository: content_repo.clone(),
                 pres: Arc::new(ReturnContentGetPresenter { ret: content_in }),
             }),
             lock: Mutex::new(()),
             ret: Mutex::new(content_out),
+
+            gets_usecase: Arc::new(ContentGetsInteractor {
+                content_repository: content_repo.clone(),
+                user_repository: user_repo.clone(),
+                bookmarked_cache: Mutex::new(None),
+                max_query_ids: config.max_query_ids,
+                page_size: config.gets_page_size,
+                pres: Arc::new(ReturnContentGetsPresenter {
+                    ret: content_gets_in,
+                }),
+            }),
+            gets_lock: Mutex::new(()),
+            gets_ret: Mutex::new(content_gets_out),
+
+            resolve_usecase: Arc::new(ContentResolveInteractor {
+                content_repository: content_repo.clone(),
+                pres: Arc::new(ReturnContentResolvePresenter { ret: resolve_in }),
+            }),
+            resolve_lock: Mutex::new(()),
+            resolve_ret: Mutex::new(resolve_out),
         },
     }
 }
 
-fn user(repo: Arc<dyn UserRepository + Sync + Send>) -> SerenityUserController {
+fn user(
+    repo: Arc<dyn UserRepository + Sync + Send>,
+    content_repo: Arc<dyn ContentRepository + Sync + Send>,
+    config: &Config,
+) -> SerenityUserController {
     let (register_in, register_out) = mpsc::channel(1);
+    let (status_in, status_out) = mpsc::channel(1);
     let (get_in, get_out) = mpsc::channel(1);
     let (gets_in, gets_out) = mpsc::channel(1);
+    let (similar_in, similar_out) = mpsc::channel(1);
     let (edit_in, edit_out) = mpsc::channel(1);
     let (unregister_in, unregister_out) = mpsc::channel(1);
     let (get_bookmark_in, get_bookmark_out) = mpsc::channel(1);
+    let (get_bookmark_contents_in, get_bookmark_contents_out) = mpsc::channel(1);
+    let (count_bookmark_in, count_bookmark_out) = mpsc::channel(1);
+    let (export_bookmark_in, export_bookmark_out) = mpsc::channel(1);
     let (bookmark_in, bookmark_out) = mpsc::channel(1);
     let (unbookmark_in, unbookmark_out) = mpsc::channel(1);
+    let (toggle_bookmark_in, toggle_bookmark_out) = mpsc::channel(1);
 
     SerenityUserController {
         register: Arc::new(UserRegisterInteractor {
             user_repository: repo.clone(),
-            pres: Arc::new(SerenityUserRegisterPresenter { out: register_in }),
+            pres: Arc::new(SerenityUserRegisterPresenter {
+                out: register_in,
+                locale: config.locale,
+            }),
         }),
         register_ret: Mutex::new(register_out),
         register_lock: Mutex::new(()),
 
+        status: Arc::new(UserStatusInteractor {
+            user_repository: repo.clone(),
+            pres: Arc::new(SerenityUserStatusPresenter {
+                out: status_in,
+                locale: config.locale,
+            }),
+        }),
+        status_ret: Mutex::new(status_out),
+        status_lock: Mutex::new(()),
+
         get: Arc::new(UserGetInteractor {
             user_repository: repo.clone(),
-            pres: Arc::new(SerenityUserGetPresenter { out: get_in }),
+            content_repository: content_repo.clone(),
+            pres: Arc::new(SerenityUserGetPresenter {
+                out: get_in,
+                locale: config.locale,
+            }),
         }),
         get_ret: Mutex::new(get_out),
         get_lock: Mutex::new(()),
 
         gets: Arc::new(UserGetsInteractor {
             user_repository: repo.clone(),
-            pres: Arc::new(SerenityUserGetsPresenter { out: gets_in }),
+            max_query_ids: config.max_query_ids,
+            page_size: config.gets_page_size,
+            pres: Arc::new(SerenityUserGetsPresenter {
+                out: gets_in,
+                locale: config.locale,
+            }),
         }),
         gets_ret: Mutex::new(gets_out),
         gets_lock: Mutex::new(()),
 
+        similar: Arc::new(UserSimilarInteractor {
+            user_repository: repo.clone(),
+            page_size: config.gets_page_size,
+            pres: Arc::new(SerenityUserSimilarPresenter {
+                out: similar_in,
+                locale: config.locale,
+            }),
+        }),
+        similar_ret: Mutex::new(similar_out),
+        similar_lock: Mutex::new(()),
+
         edit: Arc::new(UserEditInteractor {
             user_repository: repo.clone(),
-            pres: Arc::new(SerenityUserEditPresenter { out: edit_in }),
+            pres: Arc::new(SerenityUserEditPresenter {
+                out: edit_in,
+                locale: config.locale,
+            }),
         }),
         edit_ret: Mutex::new(edit_out),
         edit_lock: Mutex::new(()),
 
         unregister: Arc::new(UserUnregisterInteractor {
             user_repository: repo.clone(),
-            pres: Arc::new(SerenityUserUnregisterPresenter { out: unregister_in }),
+            content_repository: content_repo.clone(),
+            pres: Arc::new(SerenityUserUnregisterPresenter {
+                out: unregister_in,
+                list_cap: config.list_cap,
+                prefix: config.prefix.clone(),
+                locale: config.locale,
+            }),
         }),
         unregister_ret: Mutex::new(unregister_out),
         unregister_lock: Mutex::new(()),
 
         get_bookmark: Arc::new(UserBookmarkGetInteractor {
             user_repository: repo.clone(),
+            page_size: config.list_page_size,
             pres: Arc::new(SerenityUserBookmarkGetPresenter {
                 out: get_bookmark_in,
+                locale: config.locale,
             }),
         }),
         get_bookmark_ret: Mutex::new(get_bookmark_out),
         get_bookmark_lock: Mutex::new(()),
 
+        get_bookmark_contents: Arc::new(UserBookmarkContentsInteractor {
+            user_repository: repo.clone(),
+            content_repository: content_repo.clone(),
+            page_size: config.list_page_size,
+            pres: Arc::new(SerenityUserBookmarkContentsPresenter {
+                out: get_bookmark_contents_in,
+                locale: config.locale,
+            }),
+        }),
+        get_bookmark_contents_ret: Mutex::new(get_bookmark_contents_out),
+        get_bookmark_contents_lock: Mutex::new(()),
+
+        count_bookmark: Arc::new(UserBookmarkCountInteractor {
+            user_repository: repo.clone(),
+            pres: Arc::new(SerenityUserBookmarkCountPresenter {
+                out: count_bookmark_in,
+                locale: config.locale,
+            }),
+        }),
+        count_bookmark_ret: Mutex::new(count_bookmark_out),
+        count_bookmark_lock: Mutex::new(()),
+
+        export_bookmark: Arc::new(UserBookmarkExportInteractor {
+            user_repository: repo.clone(),
+            content_repository: content_repo,
+            pres: Arc::new(SerenityUserBookmarkExportPresenter {
+                out: export_bookmark_in,
+            }),
+        }),
+        export_bookmark_ret: Mutex::new(export_bookmark_out),
+        export_bookmark_lock: Mutex::new(()),
+
         bookmark: Arc::new(UserBookmarkInteractor {
             user_repository: repo.clone(),
-            pres: Arc::new(SerenityUserBookmarkPresenter { out: bookmark_in }),
+            pres: Arc::new(SerenityUserBookmarkPresenter {
+                out: bookmark_in,
+                locale: config.locale,
+            }),
         }),
         bookmark_ret: Mutex::new(bookmark_out),
         bookmark_lock: Mutex::new(()),
 
         unbookmark: Arc::new(UserUnbookmarkInteractor {
             user_repository: repo.clone(),
-            pres: Arc::new(SerenityUserUnbookmarkPresenter { out: unbookmark_in }),
+            pres: Arc::new(SerenityUserUnbookmarkPresenter {
+                out: unbookmark_in,
+                locale: config.locale,
+            }),
         }),
         unbookmark_ret: Mutex::new(unbookmark_out),
         unbookmark_lock: Mutex::new(()),
+
+        toggle_bookmark: Arc::new(UserBookmarkToggleInteractor {
+            user_repository: repo.clone(),
+            pres: Arc::new(SerenityUserBookmarkTogglePresenter {
+                out: toggle_bookmark_in,
+                locale: config.locale,
+            }),
+        }),
+        toggle_bookmark_ret: Mutex::new(toggle_bookmark_out),
+        toggle_bookmark_lock: Mutex::new(()),
     }
 }
 
 fn content(
     repo: Arc<dyn ContentRepository + Sync + Send>,
     user_repo: Arc<dyn UserRepository + Sync + Send>,
+    config: &Config,
 ) -> SerenityContentController {
     let (post_in, post_out) = mpsc::channel(1);
     let (get_in, get_out) = mpsc::channel(1);
+    let (history_in, history_out) = mpsc::channel(1);
     let (gets_in, gets_out) = mpsc::channel(1);
     let (edit_in, edit_out) = mpsc::channel(1);
+    let (rename_virtual_in, rename_virtual_out) = mpsc::channel(1);
+    let (reassign_posted_in, reassign_posted_out) = mpsc::channel(1);
+    let (preview_sed_in, preview_sed_out) = mpsc::channel(1);
     let (withdraw_in, withdraw_out) = mpsc::channel(1);
+    let (merge_in, merge_out) = mpsc::channel(1);
+    let (restore_in, restore_out) = mpsc::channel(1);
+    let (tag_in, tag_out) = mpsc::channel(1);
+    let (untag_in, untag_out) = mpsc::channel(1);
     let (get_like_in, get_like_out) = mpsc::channel(1);
+    let (count_like_in, count_like_out) = mpsc::channel(1);
     let (like_in, like_out) = mpsc::channel(1);
     let (unlike_in, unlike_out) = mpsc::channel(1);
+    let (like_many_in, like_many_out) = mpsc::channel(1);
+    let (unlike_many_in, unlike_many_out) = mpsc::channel(1);
     let (get_pin_in, get_pin_out) = mpsc::channel(1);
+    let (count_pin_in, count_pin_out) = mpsc::channel(1);
     let (pin_in, pin_out) = mpsc::channel(1);
     let (unpin_in, unpin_out) = mpsc::channel(1);
+    let (stats_in, stats_out) = mpsc::channel(1);
 
     SerenityContentController {
         post: Arc::new(ContentPostInteractor {
             user_repository: user_repo.clone(),
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentPostPresenter { out: post_in }),
+            max_contents_per_user: config.max_contents_per_user,
+            max_content_len: config.max_content_len,
+            post_cooldown: config.post_cooldown,
+            notifier: match &config.notify_url {
+                Some(u) => Arc::new(HttpSink::new(u.as_str())),
+                None => Arc::new(NoopSink),
+            },
+            pres: Arc::new(SerenityContentPostPresenter {
+                out: post_in,
+                list_cap: config.list_cap,
+                locale: config.locale,
+            }),
         }),
         post_ret: Mutex::new(post_out),
         post_lock: Mutex::new(()),
 
         get: Arc::new(ContentGetInteractor {
+            user_repository: user_repo.clone(),
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentGetPresenter { out: get_in }),
+            pres: Arc::new(SerenityContentGetPresenter {
+                out: get_in,
+                list_cap: config.list_cap,
+                locale: config.locale,
+            }),
         }),
         get_ret: Mutex::new(get_out),
         get_lock: Mutex::new(()),
 
+        history: Arc::new(ContentHistoryInteractor {
+            content_repository: repo.clone(),
+            pres: Arc::new(SerenityContentHistoryPresenter {
+                out: history_in,
+                locale: config.locale,
+            }),
+        }),
+        history_ret: Mutex::new(history_out),
+        history_lock: Mutex::new(()),
+
         gets: Arc::new(ContentGetsInteractor {
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentGetsPresenter { out: gets_in }),
+            user_repository: user_repo.clone(),
+            bookmarked_cache: Mutex::new(None),
+            max_query_ids: config.max_query_ids,
+            page_size: config.gets_page_size,
+            pres: Arc::new(SerenityContentGetsPresenter {
+                out: gets_in,
+                list_cap: config.list_cap,
+                locale: config.locale,
+            }),
         }),
         gets_ret: Mutex::new(gets_out),
         gets_lock: Mutex::new(()),
 
         edit: Arc::new(ContentEditInteractor {
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentEditPresenter { out: edit_in }),
+            max_content_len: config.max_content_len,
+            pres: Arc::new(SerenityContentEditPresenter {
+                out: edit_in,
+                locale: config.locale,
+            }),
         }),
         edit_ret: Mutex::new(edit_out),
         edit_lock: Mutex::new(()),
 
+        rename_virtual: Arc::new(ContentRenameVirtualInteractor {
+            content_repository: repo.clone(),
+            pres: Arc::new(SerenityContentRenameVirtualPresenter {
+                out: rename_virtual_in,
+                locale: config.locale,
+            }),
+        }),
+        rename_virtual_ret: Mutex::new(rename_virtual_out),
+        rename_virtual_lock: Mutex::new(()),
+
+        reassign_posted: Arc::new(ContentReassignPostedInteractor {
+            content_repository: repo.clone(),
+            user_repository: user_repo.clone(),
+            pres: Arc::new(SerenityContentReassignPostedPresenter {
+                out: reassign_posted_in,
+                locale: config.locale,
+            }),
+        }),
+        reassign_posted_ret: Mutex::new(reassign_posted_out),
+        reassign_posted_lock: Mutex::new(()),
+
+        preview_sed: Arc::new(ContentPreviewSedInteractor {
+            content_repository: repo.clone(),
+            pres: Arc::new(SerenityContentPreviewSedPresenter {
+                out: preview_sed_in,
+                locale: config.locale,
+            }),
+        }),
+        preview_sed_ret: Mutex::new(preview_sed_out),
+        preview_sed_lock: Mutex::new(()),
+
         withdraw: Arc::new(ContentWithdrawInteractor {
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentWithdrawPresenter { out: withdraw_in }),
+            pres: Arc::new(SerenityContentWithdrawPresenter {
+                out: withdraw_in,
+                list_cap: config.list_cap,
+                prefix: config.prefix.clone(),
+                locale: config.locale,
+            }),
         }),
         withdraw_ret: Mutex::new(withdraw_out),
         withdraw_lock: Mutex::new(()),
 
+        merge: Arc::new(ContentMergeInteractor {
+            content_repository: repo.clone(),
+            user_repository: user_repo.clone(),
+            pres: Arc::new(SerenityContentMergePresenter {
+                out: merge_in,
+                locale: config.locale,
+            }),
+        }),
+        merge_ret: Mutex::new(merge_out),
+        merge_lock: Mutex::new(()),
+
+        restore: Arc::new(ContentRestoreInteractor {
+            content_repository: repo.clone(),
+            pres: Arc::new(SerenityContentRestorePresenter {
+                out: restore_in,
+                locale: config.locale,
+            }),
+        }),
+        restore_ret: Mutex::new(restore_out),
+        restore_lock: Mutex::new(()),
+
+        tag: Arc::new(ContentTagInteractor {
+            content_repository: repo.clone(),
+            pres: Arc::new(SerenityContentTagPresenter {
+                out: tag_in,
+                list_cap: config.list_cap,
+                locale: config.locale,
+            }),
+        }),
+        tag_ret: Mutex::new(tag_out),
+        tag_lock: Mutex::new(()),
+
+        untag: Arc::new(ContentUntagInteractor {
+            content_repository: repo.clone(),
+            pres: Arc::new(SerenityContentUntagPresenter {
+                out: untag_in,
+                list_cap: config.list_cap,
+                locale: config.locale,
+            }),
+        }),
+        untag_ret: Mutex::new(untag_out),
+        untag_lock: Mutex::new(()),
+
         get_like: Arc::new(ContentLikeGetInteractor {
+            user_repository: user_repo.clone(),
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentLikeGetPresenter { out: get_like_in }),
+            page_size: config.list_page_size,
+            pres: Arc::new(SerenityContentLikeGetPresenter {
+                out: get_like_in,
+                locale: config.locale,
+            }),
         }),
         get_like_ret: Mutex::new(get_like_out),
         get_like_lock: Mutex::new(()),
 
+        count_like: Arc::new(ContentLikeCountInteractor {
+            content_repository: repo.clone(),
+            pres: Arc::new(SerenityContentLikeCountPresenter {
+                out: count_like_in,
+                locale: config.locale,
+            }),
+        }),
+        count_like_ret: Mutex::new(count_like_out),
+        count_like_lock: Mutex::new(()),
+
         like: Arc::new(ContentLikeInteractor {
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentLikePresenter { out: like_in }),
+            pres: Arc::new(SerenityContentLikePresenter {
+                out: like_in,
+                locale: config.locale,
+            }),
         }),
         like_ret: Mutex::new(like_out),
         like_lock: Mutex::new(()),
 
         unlike: Arc::new(ContentUnlikeInteractor {
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentUnlikePresenter { out: unlike_in }),
+            pres: Arc::new(SerenityContentUnlikePresenter {
+                out: unlike_in,
+                locale: config.locale,
+            }),
         }),
         unlike_ret: Mutex::new(unlike_out),
         unlike_lock: Mutex::new(()),
 
+        like_many: Arc::new(ContentLikeManyInteractor {
+            content_repository: repo.clone(),
+            pres: Arc::new(SerenityContentLikeManyPresenter {
+                out: like_many_in,
+                list_cap: config.list_cap,
+                locale: config.locale,
+            }),
+        }),
+        like_many_ret: Mutex::new(like_many_out),
+        like_many_lock: Mutex::new(()),
+
+        unlike_many: Arc::new(ContentUnlikeManyInteractor {
+            content_repository: repo.clone(),
+            pres: Arc::new(SerenityContentUnlikeManyPresenter {
+                out: unlike_many_in,
+                list_cap: config.list_cap,
+                locale: config.locale,
+            }),
+        }),
+        unlike_many_ret: Mutex::new(unlike_many_out),
+        unlike_many_lock: Mutex::new(()),
+
         get_pin: Arc::new(ContentPinGetInteractor {
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentPinGetPresenter { out: get_pin_in }),
+            page_size: config.list_page_size,
+            pres: Arc::new(SerenityContentPinGetPresenter {
+                out: get_pin_in,
+                locale: config.locale,
+            }),
         }),
         get_pin_ret: Mutex::new(get_pin_out),
         get_pin_lock: Mutex::new(()),
 
+        count_pin: Arc::new(ContentPinCountInteractor {
+            content_repository: repo.clone(),
+            pres: Arc::new(SerenityContentPinCountPresenter {
+                out: count_pin_in,
+                locale: config.locale,
+            }),
+        }),
+        count_pin_ret: Mutex::new(count_pin_out),
+        count_pin_lock: Mutex::new(()),
+
         pin: Arc::new(ContentPinInteractor {
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentPinPresenter { out: pin_in }),
+            pres: Arc::new(SerenityContentPinPresenter {
+                out: pin_in,
+                locale: config.locale,
+            }),
         }),
         pin_ret: Mutex::new(pin_out),
         pin_lock: Mutex::new(()),
 
         unpin: Arc::new(ContentUnpinInteractor {
             content_repository: repo.clone(),
-            pres: Arc::new(SerenityContentUnpinPresenter { out: unpin_in }),
+            pres: Arc::new(SerenityContentUnpinPresenter {
+                out: unpin_in,
+                locale: config.locale,
+            }),
         }),
         unpin_ret: Mutex::new(unpin_out),
         unpin_lock: Mutex::new(()),
+
+        stats: Arc::new(ContentStatsInteractor {
+            user_repository: user_repo,
+            content_repository: repo,
+            pres: Arc::new(SerenityContentStatsPresenter {
+                out: stats_in,
+                locale: config.locale,
+            }),
+        }),
+        stats_ret: Mutex::new(stats_out),
+        stats_lock: Mutex::new(()),
+    }
+}
+
+/// wraps with [`CachingContentRepository`] if enabled.
+fn content_repo(
+    repo: Arc<dyn ContentRepository + Sync + Send>,
+    config: &Config,
+) -> Arc<dyn ContentRepository + Sync + Send> {
+    match config.content_cache {
+        Some(cap) => Arc::new(CachingContentRepository::new(repo, cap)),
+        None => repo,
     }
 }
 
-pub fn in_memory() -> impl EventHandler {
+pub(crate) fn in_memory_contr(config: Config) -> SerenityReturnController {
     let ur = Arc::new(InMemoryRepository::<User>::new());
-    let cr = Arc::new(InMemoryRepository::<Content>::new());
+    let cr = content_repo(
+        Arc::new(InMemoryRepository::<Content>::new_with(
+            config.reject_duplicate_body,
+        )),
+        &config,
+    );
+
+    contr(
+        &config,
+        user(ur.clone(), cr.clone(), &config),
+        content(cr.clone(), ur.clone(), &config),
+        ur.clone(),
+        cr,
+        ur,
+    )
+}
 
+pub fn in_memory(config: Config) -> impl EventHandler {
     Conductor {
-        contr: contr(user(ur.clone()), content(cr.clone(), ur.clone()), ur, cr),
+        contr: Arc::new(in_memory_contr(config)),
+        pagers: Default::default(),
+        confirmations: Default::default(),
+    }
+}
+
+/// client connects lazily, so also initializes repositories to ensure the
+/// connection. retried on any error, since mongo may be still starting (e.g.
+/// launched with the bot).
+async fn connect_mongo(
+    uri_str: &str,
+    db_name: &str,
+    (attempts, base_delay): (u32, ::core::time::Duration),
+    slow_query: Option<::core::time::Duration>,
+) -> ::anyhow::Result<(MongoUserRepository, MongoContentRepository)> {
+    let mut delay = base_delay;
+    let mut attempt = 1;
+
+    loop {
+        tracing::info!("connecting to mongo - attempt {}/{}", attempt, attempts);
+
+        let res: ::anyhow::Result<_> = try {
+            let c = ::mongodb::Client::with_uri_str(uri_str).await?;
+            let db = c.database(db_name);
+
+            let ur = MongoUserRepository::new_with(c.clone(), db.clone(), slow_query).await?;
+            let cr = MongoContentRepository::new_with(c, db, slow_query).await?;
+
+            (ur, cr)
+        };
+
+        match res {
+            Ok(r) => break Ok(r),
+            Err(e) if attempt < attempts => {
+                tracing::warn!(
+                    "cannot connect to mongo - attempt {}/{} | retry in {:?} | err {}",
+                    attempt,
+                    attempts,
+                    delay,
+                    e
+                );
+
+                ::tokio::time::sleep(delay).await;
+                delay = delay.saturating_mul(2);
+                attempt += 1;
+            },
+            Err(e) => {
+                tracing::error!("gave up connecting to mongo - attempts {}", attempts);
+
+                break Err(e);
+            },
+        }
     }
 }
 
 pub async fn mongo(
     uri_str: impl AsRef<str>,
     db_name: impl AsRef<str>,
+    config: Config,
 ) -> ::anyhow::Result<impl EventHandler> {
-    let c = ::mongodb::Client::with_uri_str(uri_str).await?;
-    let db = c.database(db_name.as_ref());
-
-    let ur = Arc::new(MongoUserRepository::new_with(c.clone(), db.clone()).await?);
-    let cr = Arc::new(MongoContentRepository::new_with(c, db).await?);
+    let (ur, cr) = connect_mongo(
+        uri_str.as_ref(),
+        db_name.as_ref(),
+        config.mongo_retry,
+        config.slow_query,
+    )
+    .await?;
+    let ur = Arc::new(ur);
+    let cr = content_repo(Arc::new(cr), &config);
 
     let eh = Conductor {
-        contr: contr(user(ur.clone()), content(cr.clone(), ur.clone()), ur, cr),
+        contr: Arc::new(contr(
+            &config,
+            user(ur.clone(), cr.clone(), &config),
+            content(cr.clone(), ur.clone(), &config),
+            ur.clone(),
+            cr,
+            ur,
+        )),
+        pagers: Default::default(),
+        confirmations: Default::default(),
     };
 
     Ok(eh)
 }
+
+/// writes all contents in mongo to `path` as NDJSON, without loading them at
+/// once. returns number of exported contents.
+///
+/// collection is only read. (neither indexes nor migrations are applied)
+pub async fn export_mongo(
+    uri_str: impl AsRef<str>,
+    db_name: impl AsRef<str>,
+    path: impl AsRef<::std::path::Path>,
+) -> ::anyhow::Result<usize> {
+    let c = ::mongodb::Client::with_uri_str(uri_str).await?;
+    let db = c.database(db_name.as_ref());
+
+    // slow queries are not warned, since it reads all anyway.
+    export_to(&MongoContentRepository::open(c, db, None), path).await
+}
+
+/// same as [`export_mongo`], but for in-memory. (contents are not kept
+/// across processes, so this writes empty file)
+pub async fn export_in_memory(path: impl AsRef<::std::path::Path>) -> ::anyhow::Result<usize> {
+    export_to(&InMemoryRepository::<Content>::new(), path).await
+}
+
+async fn export_to(
+    cr: &(dyn ContentRepository + Sync + Send),
+    path: impl AsRef<::std::path::Path>,
+) -> ::anyhow::Result<usize> {
+    use tokio::io::AsyncWriteExt;
+
+    let file = ::tokio::fs::File::create(path).await?;
+    let mut out = ::tokio::io::BufWriter::new(file);
+
+    let count = cr.export(&mut out).await?;
+    out.flush().await?;
+
+    Ok(count)
+}
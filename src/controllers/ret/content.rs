@@ -4,22 +4,63 @@ use anyhow::Result;
 use tokio::sync::{mpsc, Mutex};
 
 use crate::entities::{Content, ContentId};
-use crate::usecases::content::get;
+use crate::usecases::content::{get, gets, resolve};
 
 pub struct ReturnContentController {
     pub usecase: Arc<dyn get::Usecase + Sync + Send>,
     pub lock: Mutex<()>,
-    pub ret: Mutex<mpsc::Receiver<Content>>,
+    pub ret: Mutex<mpsc::Receiver<get::Output>>,
+
+    pub gets_usecase: Arc<dyn gets::Usecase + Sync + Send>,
+    pub gets_lock: Mutex<()>,
+    pub gets_ret: Mutex<mpsc::Receiver<gets::Output>>,
+
+    pub resolve_usecase: Arc<dyn resolve::Usecase + Sync + Send>,
+    pub resolve_lock: Mutex<()>,
+    pub resolve_ret: Mutex<mpsc::Receiver<ContentId>>,
 }
 impl ReturnContentController {
     pub async fn get(&self, content_id: ContentId) -> Result<Content> {
+        self.get_output(get::Input {
+            content_id,
+            requester: None,
+        })
+        .await
+        .map(|o| o.content)
+    }
+
+    pub async fn get_output(&self, data: get::Input) -> Result<get::Output> {
         let guard = self.lock.lock().await;
 
-        self.usecase.handle(get::Input { content_id }).await?;
-        let content = self.ret.lock().await.recv().await.unwrap();
+        self.usecase.handle(data).await?;
+        let output = self.ret.lock().await.recv().await.unwrap();
+
+        drop(guard);
+
+        Ok(output)
+    }
+
+    pub async fn gets(&self, data: gets::Input) -> Result<gets::Output> {
+        let guard = self.gets_lock.lock().await;
+
+        self.gets_usecase.handle(data).await?;
+        let output = self.gets_ret.lock().await.recv().await.unwrap();
+
+        drop(guard);
+
+        Ok(output)
+    }
+
+    pub async fn resolve(&self, short_id: String) -> Result<ContentId> {
+        let guard = self.resolve_lock.lock().await;
+
+        self.resolve_usecase
+            .handle(resolve::Input { short_id })
+            .await?;
+        let content_id = self.resolve_ret.lock().await.recv().await.unwrap();
 
         drop(guard);
 
-        Ok(content)
+        Ok(content_id)
     }
 }
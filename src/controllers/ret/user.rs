@@ -4,22 +4,45 @@ use anyhow::Result;
 use tokio::sync::{mpsc, Mutex};
 
 use crate::entities::{User, UserId};
-use crate::usecases::user::get;
+use crate::usecases::user::{get, gets};
 
 pub struct ReturnUserController {
     pub usecase: Arc<dyn get::Usecase + Sync + Send>,
     pub lock: Mutex<()>,
     pub ret: Mutex<mpsc::Receiver<User>>,
+
+    pub gets_usecase: Arc<dyn gets::Usecase + Sync + Send>,
+    pub gets_lock: Mutex<()>,
+    pub gets_ret: Mutex<mpsc::Receiver<gets::Output>>,
 }
 impl ReturnUserController {
     pub async fn get(&self, user_id: UserId) -> Result<User> {
+        // not shown to others.
+        self.get_as(user_id, user_id).await
+    }
+
+    /// same as `get`, but `posted_count` is seen by `requester`.
+    pub async fn get_as(&self, user_id: UserId, requester: UserId) -> Result<User> {
         let guard = self.lock.lock().await;
 
-        self.usecase.handle(get::Input { user_id }).await?;
+        self.usecase
+            .handle(get::Input { user_id, requester })
+            .await?;
         let user = self.ret.lock().await.recv().await.unwrap();
 
         drop(guard);
 
         Ok(user)
     }
+
+    pub async fn gets(&self, data: gets::Input) -> Result<gets::Output> {
+        let guard = self.gets_lock.lock().await;
+
+        self.gets_usecase.handle(data).await?;
+        let output = self.gets_ret.lock().await.recv().await.unwrap();
+
+        drop(guard);
+
+        Ok(output)
+    }
 }
@@ -7,7 +7,9 @@ use tokio::sync::{mpsc, Mutex};
 
 use crate::presenters::impls::serenity::View;
 use crate::usecases::content::{
-    edit, get, get_like, get_pin, gets, like, pin, post, unlike, unpin, withdraw,
+    count_like, count_pin, edit, get, get_like, get_pin, gets, history, like, like_many, merge,
+    pin, post, preview_sed, reassign_posted, rename_virtual, restore, stats, tag, unlike,
+    unlike_many, unpin, untag, withdraw,
 };
 
 pub struct SerenityContentController {
@@ -19,6 +21,10 @@ pub struct SerenityContentController {
     pub get_lock: Mutex<()>,
     pub get_ret: Mutex<mpsc::Receiver<Box<View>>>,
 
+    pub history: Arc<dyn history::Usecase + Sync + Send>,
+    pub history_lock: Mutex<()>,
+    pub history_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
     pub gets: Arc<dyn gets::Usecase + Sync + Send>,
     pub gets_lock: Mutex<()>,
     pub gets_ret: Mutex<mpsc::Receiver<SmallVec<[Box<View>; 5]>>>,
@@ -27,14 +33,46 @@ pub struct SerenityContentController {
     pub edit_lock: Mutex<()>,
     pub edit_ret: Mutex<mpsc::Receiver<Box<View>>>,
 
+    pub rename_virtual: Arc<dyn rename_virtual::Usecase + Sync + Send>,
+    pub rename_virtual_lock: Mutex<()>,
+    pub rename_virtual_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
+    pub reassign_posted: Arc<dyn reassign_posted::Usecase + Sync + Send>,
+    pub reassign_posted_lock: Mutex<()>,
+    pub reassign_posted_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
+    pub preview_sed: Arc<dyn preview_sed::Usecase + Sync + Send>,
+    pub preview_sed_lock: Mutex<()>,
+    pub preview_sed_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
     pub withdraw: Arc<dyn withdraw::Usecase + Sync + Send>,
     pub withdraw_lock: Mutex<()>,
     pub withdraw_ret: Mutex<mpsc::Receiver<Box<View>>>,
 
+    pub merge: Arc<dyn merge::Usecase + Sync + Send>,
+    pub merge_lock: Mutex<()>,
+    pub merge_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
+    pub restore: Arc<dyn restore::Usecase + Sync + Send>,
+    pub restore_lock: Mutex<()>,
+    pub restore_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
+    pub tag: Arc<dyn tag::Usecase + Sync + Send>,
+    pub tag_lock: Mutex<()>,
+    pub tag_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
+    pub untag: Arc<dyn untag::Usecase + Sync + Send>,
+    pub untag_lock: Mutex<()>,
+    pub untag_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
     pub get_like: Arc<dyn get_like::Usecase + Sync + Send>,
     pub get_like_lock: Mutex<()>,
     pub get_like_ret: Mutex<mpsc::Receiver<SmallVec<[Box<View>; 20]>>>,
 
+    pub count_like: Arc<dyn count_like::Usecase + Sync + Send>,
+    pub count_like_lock: Mutex<()>,
+    pub count_like_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
     pub like: Arc<dyn like::Usecase + Sync + Send>,
     pub like_lock: Mutex<()>,
     pub like_ret: Mutex<mpsc::Receiver<Box<View>>>,
@@ -43,10 +81,22 @@ pub struct SerenityContentController {
     pub unlike_lock: Mutex<()>,
     pub unlike_ret: Mutex<mpsc::Receiver<Box<View>>>,
 
+    pub like_many: Arc<dyn like_many::Usecase + Sync + Send>,
+    pub like_many_lock: Mutex<()>,
+    pub like_many_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
+    pub unlike_many: Arc<dyn unlike_many::Usecase + Sync + Send>,
+    pub unlike_many_lock: Mutex<()>,
+    pub unlike_many_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
     pub get_pin: Arc<dyn get_pin::Usecase + Sync + Send>,
     pub get_pin_lock: Mutex<()>,
     pub get_pin_ret: Mutex<mpsc::Receiver<SmallVec<[Box<View>; 20]>>>,
 
+    pub count_pin: Arc<dyn count_pin::Usecase + Sync + Send>,
+    pub count_pin_lock: Mutex<()>,
+    pub count_pin_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
     pub pin: Arc<dyn pin::Usecase + Sync + Send>,
     pub pin_lock: Mutex<()>,
     pub pin_ret: Mutex<mpsc::Receiver<Box<View>>>,
@@ -54,6 +104,10 @@ pub struct SerenityContentController {
     pub unpin: Arc<dyn unpin::Usecase + Sync + Send>,
     pub unpin_lock: Mutex<()>,
     pub unpin_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
+    pub stats: Arc<dyn stats::Usecase + Sync + Send>,
+    pub stats_lock: Mutex<()>,
+    pub stats_ret: Mutex<mpsc::Receiver<Box<View>>>,
 }
 
 impl SerenityContentController {
@@ -77,6 +131,16 @@ impl SerenityContentController {
         )
     }
 
+    #[async_recursion]
+    pub async fn history(&self, data: history::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use history,
+            lock history_lock,
+            ret history_ret,
+            data data
+        )
+    }
+
     #[async_recursion]
     pub async fn gets(&self, data: gets::Input) -> Result<SmallVec<[Box<View>; 5]>> {
         return_inner!(self =>
@@ -97,6 +161,46 @@ impl SerenityContentController {
         )
     }
 
+    #[async_recursion]
+    pub async fn rename_virtual(&self, data: rename_virtual::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use rename_virtual,
+            lock rename_virtual_lock,
+            ret rename_virtual_ret,
+            data data
+        )
+    }
+
+    #[async_recursion]
+    pub async fn reassign_posted(&self, data: reassign_posted::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use reassign_posted,
+            lock reassign_posted_lock,
+            ret reassign_posted_ret,
+            data data
+        )
+    }
+
+    #[async_recursion]
+    pub async fn preview_sed(&self, data: preview_sed::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use preview_sed,
+            lock preview_sed_lock,
+            ret preview_sed_ret,
+            data data
+        )
+    }
+
+    #[async_recursion]
+    pub async fn merge(&self, data: merge::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use merge,
+            lock merge_lock,
+            ret merge_ret,
+            data data
+        )
+    }
+
     #[async_recursion]
     pub async fn withdraw(&self, data: withdraw::Input) -> Result<Box<View>> {
         return_inner!(self =>
@@ -107,6 +211,36 @@ impl SerenityContentController {
         )
     }
 
+    #[async_recursion]
+    pub async fn restore(&self, data: restore::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use restore,
+            lock restore_lock,
+            ret restore_ret,
+            data data
+        )
+    }
+
+    #[async_recursion]
+    pub async fn tag(&self, data: tag::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use tag,
+            lock tag_lock,
+            ret tag_ret,
+            data data
+        )
+    }
+
+    #[async_recursion]
+    pub async fn untag(&self, data: untag::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use untag,
+            lock untag_lock,
+            ret untag_ret,
+            data data
+        )
+    }
+
     #[async_recursion]
     pub async fn get_like(&self, data: get_like::Input) -> Result<SmallVec<[Box<View>; 20]>> {
         return_inner!(self =>
@@ -117,6 +251,16 @@ impl SerenityContentController {
         )
     }
 
+    #[async_recursion]
+    pub async fn count_like(&self, data: count_like::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use count_like,
+            lock count_like_lock,
+            ret count_like_ret,
+            data data
+        )
+    }
+
     #[async_recursion]
     pub async fn like(&self, data: like::Input) -> Result<Box<View>> {
         return_inner!(self =>
@@ -137,6 +281,26 @@ impl SerenityContentController {
         )
     }
 
+    #[async_recursion]
+    pub async fn like_many(&self, data: like_many::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use like_many,
+            lock like_many_lock,
+            ret like_many_ret,
+            data data
+        )
+    }
+
+    #[async_recursion]
+    pub async fn unlike_many(&self, data: unlike_many::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use unlike_many,
+            lock unlike_many_lock,
+            ret unlike_many_ret,
+            data data
+        )
+    }
+
     #[async_recursion]
     pub async fn get_pin(&self, data: get_pin::Input) -> Result<SmallVec<[Box<View>; 20]>> {
         return_inner!(self =>
@@ -147,6 +311,16 @@ impl SerenityContentController {
         )
     }
 
+    #[async_recursion]
+    pub async fn count_pin(&self, data: count_pin::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use count_pin,
+            lock count_pin_lock,
+            ret count_pin_ret,
+            data data
+        )
+    }
+
     #[async_recursion]
     pub async fn pin(&self, data: pin::Input) -> Result<Box<View>> {
         return_inner!(self =>
@@ -166,4 +340,14 @@ impl SerenityContentController {
             data data
         )
     }
+
+    #[async_recursion]
+    pub async fn stats(&self, data: stats::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use stats,
+            lock stats_lock,
+            ret stats_ret,
+            data data
+        )
+    }
 }
@@ -14,45 +14,200 @@ macro_rules! return_inner {
 pub mod content;
 pub mod user;
 
+use alloc::sync::Arc;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
 use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use regex::{Captures, Regex};
 use serenity::http::CacheHttp;
 use serenity::model::channel::Message;
 use smallvec::{smallvec, SmallVec};
+use tokio::sync::{Mutex, RwLock};
+use tokio::time::{Duration, Instant};
+use tracing::Instrument;
 
 use super::ret::content::ReturnContentController;
 use super::ret::user::ReturnUserController;
+use crate::cmds::parser::{parse_content_query, parse_user_id};
 use crate::cmds::{
-    Cmd, ContentEditCmd, ContentGetCmd, ContentGetsCmd, ContentLikeCmd, ContentLikeOp, ContentMod,
-    ContentPinCmd, ContentPinOp, ContentPostCmd, ContentWithdrawCmd, PartialContentMutation,
-    RootMod, UserBookmarkCmd, UserBookmarkOp, UserEditCmd, UserGetCmd, UserGetsCmd, UserMod,
-    UserRegisterCmd, UserUnregisterCmd,
+    Cmd, ContentEditCmd, ContentFromTemplateCmd, ContentGetCmd, ContentGetsCmd, ContentHistoryCmd,
+    ContentLikeCmd, ContentLikeOp, ContentMergeCmd, ContentMod, ContentPinCmd, ContentPinOp,
+    ContentPostCmd, ContentPreviewSedCmd, ContentReassignPostedCmd, ContentRef,
+    ContentRenameVirtualCmd, ContentRestoreCmd, ContentTagCmd, ContentTagOp, ContentTemplateCmd,
+    ContentTemplateOp, ContentWithdrawCmd, GuildDefaultQueryCmd, GuildMod, GuildStickyCmd,
+    PartialContentMutation, RootMod, UserBookmarkCmd, UserBookmarkOp, UserContentsCmd, UserEditCmd,
+    UserGetCmd, UserGetsCmd, UserMod, UserRegisterCmd, UserRegisterForCmd, UserSimilarCmd,
+    UserStatusCmd, UserUnregisterCmd,
+};
+use crate::entities::{Author, ContentId, Date, PartialAuthor, Posted, UserId};
+use crate::errors::DomainError;
+use crate::messages::{Locale, Msg};
+use crate::presenters::impls::serenity::{
+    content_to_json, contents_to_json, user_to_json, users_to_json, Response, View,
 };
-use crate::entities::{Author, ContentId, PartialAuthor, Posted, UserId};
-use crate::presenters::impls::serenity::View;
+use crate::repositories::HealthRepository;
 use crate::usecases;
-use crate::usecases::content::ContentMutation;
+use crate::usecases::content::{
+    AuthorResolver, ContentContentMutation, ContentCursor, ContentMutation, ContentQuery,
+    PostedQuery,
+};
 use crate::utils::{FutureTranspose, LetChain};
 
+// templates are shown as embed fields, so limited by discord's limits.
+const MAX_TEMPLATES: usize = 20;
+const MAX_TEMPLATE_NAME_LEN: usize = 256;
+const MAX_TEMPLATE_LEN: usize = 1024;
+
+const MAX_SAVED_QUERIES: usize = 20;
+
+// stored as name of authors not existing on discord (if allowed).
+const UNKNOWN_AUTHOR_NAME: &str = "<unknown>";
+
+// number of contents in each ranking of `stats`.
+const STATS_TOP: usize = 5;
+
+pub const CONFIRM_EMOJI: &str = "\u{2705}";
+// differs from withdrawing one, not to be confused on posted contents.
+pub const CANCEL_EMOJI: &str = "\u{1f6ab}";
+
+// re-posting sticky more frequently is just spamming.
+const STICKY_INTERVAL: Duration = Duration::from_secs(30);
+
 pub struct SerenityReturnController {
     pub user: user::SerenityUserController,
     pub content: content::SerenityContentController,
     pub return_user_contr: ReturnUserController,
     pub return_content_contr: ReturnContentController,
+    /// raw (validated) queries keyed by guild id. not persisted.
+    pub guild_default_queries: RwLock<HashMap<u64, String>>,
+    /// templates keyed by name, per user. not persisted.
+    pub templates: RwLock<HashMap<UserId, HashMap<String, String>>>,
+    /// serialized queries keyed by name, per user. not persisted.
+    pub saved_queries: RwLock<HashMap<UserId, HashMap<String, String>>>,
+    /// sticky contents keyed by channel id. not persisted.
+    pub stickies: Mutex<HashMap<u64, Sticky>>,
+    /// max edit distance to suggest subcommand for unrecognized one. 0
+    /// disables.
+    pub suggest_distance: usize,
+    pub prefix: String,
+    /// `None` means unlimited.
+    pub rate_limiter: Option<RateLimiter>,
+    pub health: Arc<dyn HealthRepository + Sync + Send>,
+    /// whether to store authors not existing on discord (e.g. deleted) with
+    /// unknown name, instead of rejecting.
+    pub allow_unknown_author: bool,
+    /// whether to store authors as placeholder (`Author::virtual_from_user`)
+    /// on editing, if cannot look up them.
+    pub virtual_author_fallback: bool,
+    /// `None` executes destructive commands without confirmation.
+    pub confirm_timeout: Option<Duration>,
+    pub locale: Locale,
+}
+
+/// destructive command waiting for confirmation by the executor.
+pub struct Pending {
+    cmd: Cmd,
+    ex: Executor,
+    deadline: Instant,
+}
+
+impl Pending {
+    pub fn user_id(&self) -> UserId { self.ex.user_id }
+
+    pub fn is_expired(&self) -> bool { Instant::now() >= self.deadline }
+}
+
+pub struct Sticky {
+    pub content_id: ContentId,
+    /// message id (if posted successfully) and time of last post.
+    pub last: Option<(Option<u64>, Instant)>,
+    /// whether re-post is scheduled after throttling.
+    pub deferred: bool,
+}
+
+pub enum StickyPost {
+    /// view of sticky content, with previous sticky message.
+    Now(Box<View>, Option<u64>),
+    /// throttled. should be re-posted after this.
+    Later(Duration),
+}
+
+/// token bucket per user, refilled `capacity` tokens per `window`.
+pub struct RateLimiter {
+    pub capacity: u32,
+    pub window: Duration,
+    buckets: Mutex<HashMap<UserId, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(capacity: u32, window: Duration) -> Self {
+        Self {
+            capacity,
+            window,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// consumes a token. if exhausted, returns time until next token.
+    pub async fn acquire(&self, user_id: UserId) -> ::core::result::Result<(), Duration> {
+        let now = Instant::now();
+        let capacity = self.capacity as f64;
+        let rate = capacity / self.window.as_secs_f64();
+
+        let mut guard = self.buckets.lock().await;
+        // buckets unused for a window are full, same as absent.
+        guard.retain(|_, b| now - b.last < self.window);
+
+        let bucket = guard.entry(user_id).or_insert(Bucket {
+            tokens: capacity,
+            last: now,
+        });
+        bucket.tokens = (bucket.tokens + (now - bucket.last).as_secs_f64() * rate).min(capacity);
+        bucket.last = now;
+
+        if bucket.tokens < 1.0 {
+            return Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate));
+        }
+
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+}
+
+/// who executed a command, and where.
+#[derive(Debug, Clone)]
+pub struct Executor {
+    pub user_id: UserId,
+    pub user_name: String,
+    pub user_nick: Option<String>,
+    pub guild_id: Option<u64>,
+    pub channel_id: u64,
+    pub timestamp: Date,
 }
 
 impl SerenityReturnController {
     pub async fn parse(
         &self,
         msg: &Message,
-        http: impl CacheHttp + Clone,
-    ) -> Option<Result<SmallVec<[Box<View>; 20]>>> {
-        let parsed = match match Self::parse_str(msg.content.as_str()).await {
-            Some(r) => r,
-            None => return None,
-        } {
-            Ok(o) => o,
-            Err(e) => return Some(Err(anyhow!(e))),
-        };
+        http: impl CacheHttp + Clone + 'static,
+    ) -> Option<Result<Response>> {
+        let raw = self.replace_mention_prefix(msg, &http).await;
+        let parsed =
+            match match Self::parse_str(raw.as_str(), self.prefix.as_str(), self.suggest_distance)
+                .await
+            {
+                Some(r) => r,
+                None => return None,
+            } {
+                Ok(o) => o,
+                Err(e) => return Some(Err(anyhow!(e))),
+            };
 
         tracing::info!(
             "recognized - id {} | channel_id {} | guild_id {} | time {} | cmd {:?}",
@@ -65,7 +220,20 @@ impl SerenityReturnController {
             parsed
         );
 
-        let res = match self.handle_cmd(parsed, msg, http).await {
+        let ex = Executor {
+            user_id: (&msg.author.id).let_(|i| i.0).let_(UserId),
+            user_name: msg.author.name.clone(),
+            user_nick: msg.author_nick(&http).await,
+            guild_id: msg.guild_id.as_ref().map(|i| i.0),
+            channel_id: msg.channel_id.0,
+            timestamp: *msg.timestamp,
+        };
+
+        if let Err(e) = self.check_rate_limit(ex.user_id).await {
+            return Some(Err(e));
+        }
+
+        let res = match self.handle_or_confirm(parsed, ex, http).await {
             Ok(o) => o,
             Err(e) => return Some(Err(e)),
         };
@@ -73,7 +241,186 @@ impl SerenityReturnController {
         Some(Ok(res))
     }
 
-    async fn parse_str(raw: &str) -> Option<Result<Cmd>> {
+    /// executes slash command, given as `args` (without prefix) of text
+    /// command.
+    pub async fn interact(
+        &self,
+        args: Vec<String>,
+        ex: Executor,
+        http: impl CacheHttp + Clone + 'static,
+    ) -> Result<Response> {
+        use clap::Parser;
+
+        let parsed = Cmd::try_parse_from(::core::iter::once(self.prefix.clone()).chain(args))
+            .map_err(|e| anyhow!(e.to_string()))?;
+
+        tracing::info!(
+            "recognized - interaction | channel_id {} | guild_id {} | cmd {:?}",
+            ex.channel_id,
+            ex.guild_id
+                .map(|i| i.to_string())
+                .unwrap_or_else(|| "None".to_string()),
+            parsed
+        );
+
+        self.check_rate_limit(ex.user_id).await?;
+
+        self.handle_or_confirm(parsed, ex, http).await
+    }
+
+    /// executes command, or asks for confirmation instead if it's destructive
+    /// (see [`Pending`]).
+    pub(crate) async fn handle_or_confirm(
+        &self,
+        app: Cmd,
+        ex: Executor,
+        http: impl CacheHttp + Clone + 'static,
+    ) -> Result<Response> {
+        const COLOR: (u8, u8, u8) = (0xfa, 0xbd, 0x2f);
+
+        let timeout = match self.confirm_timeout {
+            Some(t) if app.is_destructive() => t,
+            _ => return self.handle_cmd(app, &ex, http).await,
+        };
+
+        // not to ask who cannot execute anyway. authorized again on execution.
+        let app = self.authorize_cmd(app, ex.user_id).await?;
+
+        let locale = self.locale;
+        let description = format!(
+            "react {} within {}s to execute `{}`, or {} to cancel.",
+            CONFIRM_EMOJI,
+            timeout.as_secs(),
+            app.name(),
+            CANCEL_EMOJI
+        );
+        let view: Box<View> = box move |ce| {
+            ce.title(locale.get(Msg::ConfirmCommand))
+                .color(COLOR)
+                .description(description)
+        };
+
+        Ok(Response::Confirm(view, Pending {
+            cmd: app,
+            ex,
+            deadline: Instant::now() + timeout,
+        }))
+    }
+
+    /// executes command confirmed by the executor, as executed now.
+    pub(crate) async fn confirm(
+        &self,
+        pending: Pending,
+        http: impl CacheHttp + Clone + 'static,
+    ) -> Result<Response> {
+        if pending.is_expired() {
+            bail!(DomainError::Invalid(
+                "confirmation expired. please execute again.".to_string()
+            ));
+        }
+
+        let Pending { cmd, mut ex, .. } = pending;
+        tracing::info!(
+            "recognized - confirmation | user_id {} | cmd {}",
+            ex.user_id,
+            cmd.name()
+        );
+
+        ex.timestamp = ::chrono::Utc::now();
+
+        self.handle_cmd(cmd, &ex, http).await
+    }
+
+    /// reply for cancelled confirmation.
+    pub(crate) fn cancelled(&self, Pending { cmd, .. }: Pending) -> Response {
+        const COLOR: (u8, u8, u8) = (0x92, 0x83, 0x74);
+
+        let locale = self.locale;
+        let view: Box<View> = box move |ce| {
+            ce.title(locale.get(Msg::CancelledCommand))
+                .color(COLOR)
+                .description(format!("`{}`", cmd.name()))
+        };
+
+        Response::Embeds(smallvec![view])
+    }
+
+    async fn check_rate_limit(&self, user_id: UserId) -> Result<()> {
+        let wait = match &self.rate_limiter {
+            Some(l) => match l.acquire(user_id).await {
+                Ok(()) => return Ok(()),
+                Err(w) => w,
+            },
+            None => return Ok(()),
+        };
+
+        // looked up only if exceeded, to not hit repository on every command.
+        if let Ok(u) = self.return_user_contr.get(user_id).await {
+            if u.admin {
+                return Ok(());
+            }
+        }
+
+        tracing::info!("rate limited - user_id {} | wait {:?}", user_id, wait);
+
+        bail!(DomainError::Forbidden(format!(
+            "slow down. retry after {}s.",
+            wait.as_secs() + (wait.subsec_nanos() > 0) as u64
+        )));
+    }
+
+    /// not confirmed unlike `content withdraw` (see
+    /// [`Self::handle_or_confirm`]), since the reaction points the content
+    /// itself and can be undone by `content restore`.
+    pub async fn withdraw_by_reaction(
+        &self,
+        content_id: ContentId,
+        ex_user_id: UserId,
+    ) -> Result<Box<View>> {
+        tracing::info!(
+            "recognized - withdraw by reaction | content_id {} | user_id {}",
+            content_id,
+            ex_user_id,
+        );
+
+        if !self.is_content_operable(content_id, ex_user_id).await? {
+            bail!(DomainError::Forbidden(
+                "not permitted operation".to_string()
+            ));
+        }
+
+        self.content
+            .withdraw(usecases::content::withdraw::Input {
+                content_id,
+                archived_at: ::chrono::Utc::now(),
+            })
+            .await
+    }
+
+    /// replaces leading mention of the bot with prefix, so that it works as
+    /// prefix too.
+    async fn replace_mention_prefix(&self, msg: &Message, http: impl CacheHttp) -> String {
+        if !msg.mentions_me(http).await.unwrap_or(false) {
+            return msg.content.clone();
+        }
+
+        let (head, rest) = match msg.content.trim_start().split_once(char::is_whitespace) {
+            Some(t) => t,
+            None => return msg.content.clone(),
+        };
+
+        match parse_user_id(head) {
+            Ok(id) if msg.mentions.iter().any(|u| u.bot && u.id.0 == id) =>
+                format!("{} {}", self.prefix, rest),
+            _ => msg.content.clone(),
+        }
+    }
+
+    pub(crate) async fn parse_str(
+        raw: &str,
+        prefix: &str,
+        suggest_distance: usize,
+    ) -> Option<Result<Cmd>> {
         let split_res = ::shell_words::split(raw)
             .map(|mut v| {
                 v.drain(..)
@@ -87,8 +434,7 @@ impl SerenityReturnController {
             Err(e) => return Some(Err(anyhow!(e))),
         };
 
-        if let Some("*ip") = splitted.get(0).map(|s| s.to_str().unwrap()) {
-        } else {
+        if splitted.get(0).and_then(|s| s.to_str()) != Some(prefix) {
             return None;
         }
 
@@ -96,27 +442,98 @@ impl SerenityReturnController {
 
         use clap::Parser;
 
-        Cmd::try_parse_from(splitted)
-            .map_err(|e| anyhow!(e.to_string()))
+        Cmd::try_parse_from(&splitted)
+            .map_err(|e| match suggest_subcommand(&splitted, suggest_distance) {
+                Some(s) => anyhow!(s),
+                None => anyhow!(e.to_string()),
+            })
             .let_(Some)
     }
 
-    async fn handle_cmd(
+    /// [`Self::dispatch_cmd`] in span, logging its outcome and elapsed time.
+    pub(crate) async fn handle_cmd(
         &self,
         app: Cmd,
-        msg: &Message,
-        http: impl CacheHttp + Clone,
-    ) -> Result<SmallVec<[Box<View>; 20]>> {
-        let ex_guild_id = msg.guild_id.as_ref().map(|i| i.0);
-        let ex_timestamp = &msg.timestamp;
+        ex: &Executor,
+        http: impl CacheHttp + Clone + 'static,
+    ) -> Result<Response> {
+        let span = tracing::info_span!(
+            "cmd",
+            name = app.name(),
+            user_id = ex.user_id.0,
+            guild_id = ex.guild_id,
+        );
+
+        let started = Instant::now();
+        let res = self
+            .dispatch_cmd(app, ex, http)
+            .instrument(span.clone())
+            .await;
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+        span.in_scope(|| match &res {
+            Ok(_) => tracing::info!(ok = true, elapsed_ms, "executed"),
+            Err(e) => tracing::info!(ok = false, elapsed_ms, err = %e, "executed"),
+        });
+
+        res
+    }
+
+    async fn dispatch_cmd(
+        &self,
+        app: Cmd,
+        ex: &Executor,
+        http: impl CacheHttp + Clone + 'static,
+    ) -> Result<Response> {
+        let ex_guild_id = ex.guild_id;
+        let ex_timestamp = &ex.timestamp;
 
-        let ex_user_id = (&msg.author.id).let_(|i| i.0).let_(UserId);
-        let ex_user_name = &msg.author.name;
-        let ex_user_nick = msg.author_nick(&http).await;
+        let ex_user_id = ex.user_id;
+        let ex_user_name = &ex.user_name;
+        let ex_user_nick = ex.user_nick.clone();
 
         use usecases::{content, user};
-        let Cmd { cmd } = self.authorize_cmd(app, ex_user_id).await?;
-        match cmd {
+        let Cmd { cmd, json } = self.authorize_cmd(app, ex_user_id).await?;
+        let paged = matches!(
+            &cmd,
+            RootMod::User {
+                cmd: UserMod::Gets(_)
+                    | UserMod::Similar(_)
+                    | UserMod::Contents(_)
+                    | UserMod::Bookmark(UserBookmarkCmd {
+                        op: UserBookmarkOp::Show { .. } | UserBookmarkOp::Contents { .. },
+                    }),
+            } | RootMod::Content {
+                cmd: ContentMod::Gets(_)
+                    | ContentMod::Like(ContentLikeCmd {
+                        op: ContentLikeOp::Show { .. },
+                    })
+                    | ContentMod::Pin(ContentPinCmd {
+                        op: ContentPinOp::Show { .. },
+                    }),
+            }
+        );
+        // serialized from outputs of usecases, so only ones returning users or
+        // contents.
+        let json_available = matches!(
+            &cmd,
+            RootMod::User {
+                cmd: UserMod::Get(_) | UserMod::Gets(_) | UserMod::Contents(_),
+            } | RootMod::Content {
+                cmd: ContentMod::Get(ContentGetCmd {
+                    raw: false,
+                    reveal: false,
+                    ..
+                }) | ContentMod::Gets(_),
+            }
+        );
+        if json && !json_available {
+            bail!(DomainError::Invalid(
+                "--json is only available with `get`, `gets` and `user contents`.".to_string()
+            ));
+        }
+        let locale = self.locale;
+        let res: Result<SmallVec<[Box<View>; 20]>> = match cmd {
             RootMod::User { cmd } => match cmd {
                 UserMod::Register(UserRegisterCmd) => self
                     .user
@@ -126,33 +543,142 @@ impl SerenityReturnController {
                     .await
                     .map(|v| smallvec![v]),
 
-                UserMod::Get(UserGetCmd { user_id }) => self
+                UserMod::RegisterFor(UserRegisterForCmd { user_id }) => {
+                    match ::serenity::model::id::UserId(user_id)
+                        .to_user(http.clone())
+                        .await
+                    {
+                        Ok(_) => (),
+                        Err(e) if is_unknown_user(&e) =>
+                            bail!(DomainError::NotFound(format!("discord user: {}", user_id))),
+                        Err(e) => {
+                            tracing::warn!("cannot get user - id {} | err {:?}", user_id, e);
+
+                            bail!(DomainError::Internal(anyhow!(
+                                "cannot get user from discord: {}. retry later.",
+                                user_id
+                            )));
+                        },
+                    }
+
+                    self.user
+                        .register(user::register::Input {
+                            user_id: user_id.let_(UserId),
+                        })
+                        .await
+                        .map(|v| smallvec![v])
+                },
+
+                UserMod::Status(UserStatusCmd) => self
                     .user
-                    .get(user::get::Input {
-                        user_id: user_id.map(UserId).unwrap_or(ex_user_id),
+                    .status(user::status::Input {
+                        user_id: ex_user_id,
                     })
                     .await
                     .map(|v| smallvec![v]),
 
-                UserMod::Gets(UserGetsCmd { page, query }) => self
+                UserMod::Get(UserGetCmd { user_id }) => {
+                    let user_id = user_id.map(UserId).unwrap_or(ex_user_id);
+
+                    match json {
+                        true => {
+                            let user = self.return_user_contr.get_as(user_id, ex_user_id).await?;
+                            return Ok(Response::Json(user_to_json(user)));
+                        },
+                        false => self
+                            .user
+                            .get(user::get::Input {
+                                user_id,
+                                requester: ex_user_id,
+                            })
+                            .await
+                            .map(|v| smallvec![v]),
+                    }
+                },
+
+                UserMod::Gets(UserGetsCmd { page, query }) => {
+                    let input = user::gets::Input { query, page };
+
+                    match json {
+                        true => {
+                            let output = self.return_user_contr.gets(input).await?;
+                            return Ok(Response::Json(users_to_json(output)));
+                        },
+                        false => self
+                            .user
+                            .gets(input)
+                            .await
+                            .map(|mut v| v.drain(..).collect()),
+                    }
+                },
+
+                UserMod::Similar(UserSimilarCmd { user_id, page }) => self
                     .user
-                    .gets(user::gets::Input { query, page })
+                    .similar(user::similar::Input {
+                        user_id: user_id.map(UserId).unwrap_or(ex_user_id),
+                        page,
+                    })
                     .await
                     .map(|mut v| v.drain(..).collect()),
 
+                UserMod::Contents(UserContentsCmd { user_id, page }) => {
+                    let input = content::gets::Input {
+                        query: ContentQuery {
+                            posted: user_id
+                                .map(UserId)
+                                .unwrap_or(ex_user_id)
+                                .let_(PostedQuery::UserId)
+                                .let_(Some),
+                            ..Default::default()
+                        },
+                        page,
+                        cursor: None,
+                        resolver: None,
+                    };
+
+                    match json {
+                        true => {
+                            let output = self.return_content_contr.gets(input).await?;
+                            return Ok(Response::Json(contents_to_json(output)));
+                        },
+                        false => self
+                            .content
+                            .gets(input)
+                            .await
+                            .map(|mut v| v.drain(..).collect()),
+                    }
+                },
+
                 UserMod::Edit(UserEditCmd { user_id, mutation }) => self
                     .user
                     .edit(user::edit::Input {
                         user_id: user_id.let_(UserId),
                         mutation,
+                        executor_id: ex_user_id,
+                        executed_at: *ex_timestamp,
                     })
                     .await
                     .map(|v| smallvec![v]),
 
-                UserMod::Unregister(UserUnregisterCmd { user_id }) => self
+                UserMod::Unregister(UserUnregisterCmd {
+                    user_id,
+                    reassign_to,
+                    purge,
+                }) => self
                     .user
                     .unregister(user::unregister::Input {
                         user_id: user_id.let_(UserId),
+                        posted: match (reassign_to, purge) {
+                            (Some(to), _) => user::PostedContents::Reassign {
+                                to: to.let_(UserId),
+                                resolver: Arc::new(HttpAuthorResolver {
+                                    http,
+                                    guild_id: ex_guild_id,
+                                }),
+                            },
+                            (None, true) => user::PostedContents::Withdraw { at: *ex_timestamp },
+                            (None, false) => user::PostedContents::Keep,
+                        },
                     })
                     .await
                     .map(|v| smallvec![v]),
@@ -176,6 +702,24 @@ impl SerenityReturnController {
                         .await
                         .map(|v| smallvec![v]),
 
+                    UserBookmarkOp::Toggle { content_id } => self
+                        .user
+                        .toggle_bookmark(user::toggle_bookmark::Input {
+                            user_id: ex_user_id,
+                            content_id: content_id.let_(ContentId),
+                        })
+                        .await
+                        .map(|v| smallvec![v]),
+
+                    UserBookmarkOp::Export =>
+                        return self
+                            .user
+                            .export_bookmark(user::export_bookmark::Input {
+                                user_id: ex_user_id,
+                            })
+                            .await
+                            .map(Response::File),
+
                     UserBookmarkOp::Show { page, user_id } =>
                         self.user
                             .get_bookmark(user::get_bookmark::Input {
@@ -183,6 +727,22 @@ impl SerenityReturnController {
                                 page,
                             })
                             .await,
+
+                    UserBookmarkOp::Count { user_id } => self
+                        .user
+                        .count_bookmark(user::count_bookmark::Input {
+                            user_id: user_id.map(UserId).unwrap_or(ex_user_id),
+                        })
+                        .await
+                        .map(|v| smallvec![v]),
+
+                    UserBookmarkOp::Contents { page, user_id } =>
+                        self.user
+                            .get_bookmark_contents(user::get_bookmark_contents::Input {
+                                user_id: user_id.map(UserId).unwrap_or(ex_user_id),
+                                page,
+                            })
+                            .await,
                 },
             },
 
@@ -191,89 +751,322 @@ impl SerenityReturnController {
                     virt,
                     user_id,
                     content,
+                    title,
+                    source_url,
+                    anon,
+                    tags,
                 }) => {
                     let posted = Posted {
                         id: ex_user_id,
                         name: ex_user_name.clone(),
                         nick: ex_user_nick,
                     };
-                    let author = match (user_id, virt) {
-                        (Some(i), None) => {
-                            let user = http
-                                .http()
-                                .get_user(i)
-                                .await
-                                .map_err(|e| anyhow!("cannot get author: {}", e))?;
-
-                            let nick = ex_guild_id
-                                .map(|i| user.nick_in(http, i))
-                                .transpose()
-                                .await
-                                .flatten();
-                            let id = user.id.let_(|i| i.0).let_(UserId);
-                            let name = user.name;
-
-                            Author::User { id, name, nick }
-                        },
-                        (None, Some(s)) => Author::Virtual(s),
-                        _ => bail!("internal processing error"),
+                    let author = self
+                        .resolve_author(user_id, virt, ex_guild_id, http)
+                        .await?;
+
+                    self.content
+                        .post(content::post::Input {
+                            content,
+                            title,
+                            source_url,
+                            anonymous: anon,
+                            posted,
+                            author,
+                            created: *ex_timestamp,
+                            tags: tags.into_iter().collect(),
+                        })
+                        .await
+                        .map(|v| smallvec![v])
+                },
+
+                ContentMod::FromTemplate(ContentFromTemplateCmd {
+                    virt,
+                    user_id,
+                    name,
+                    args,
+                }) => {
+                    let template = self
+                        .templates
+                        .read()
+                        .await
+                        .get(&ex_user_id)
+                        .and_then(|m| m.get(&name))
+                        .cloned()
+                        .ok_or_else(|| anyhow!("cannot find template: {}", name))?;
+                    let content = fill_template(template.as_str(), args)?;
+
+                    let posted = Posted {
+                        id: ex_user_id,
+                        name: ex_user_name.clone(),
+                        nick: ex_user_nick,
                     };
+                    let author = self
+                        .resolve_author(user_id, virt, ex_guild_id, http)
+                        .await?;
 
                     self.content
                         .post(content::post::Input {
                             content,
+                            title: None,
+                            source_url: None,
+                            anonymous: false,
                             posted,
                             author,
-                            created: **ex_timestamp,
+                            created: *ex_timestamp,
+                            tags: HashSet::new(),
                         })
                         .await
                         .map(|v| smallvec![v])
                 },
 
-                ContentMod::Get(ContentGetCmd { content_id }) => self
+                ContentMod::Template(ContentTemplateCmd { op }) => {
+                    const SAVE_COLOR: (u8, u8, u8) = (0xb8, 0xbb, 0x26);
+                    const SHOW_COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+
+                    let mut guard = self.templates.write().await;
+                    let templates = guard.entry(ex_user_id).or_default();
+
+                    let view: Box<View> = match op {
+                        ContentTemplateOp::Save { name, template } => {
+                            if !templates.contains_key(&name) && templates.len() >= MAX_TEMPLATES {
+                                bail!("too many templates. (max: {})", MAX_TEMPLATES);
+                            }
+                            if name.len() > MAX_TEMPLATE_NAME_LEN {
+                                bail!(
+                                    "template name is too long. (max: {})",
+                                    MAX_TEMPLATE_NAME_LEN
+                                );
+                            }
+                            if template.len() > MAX_TEMPLATE_LEN {
+                                bail!("template is too long. (max: {})", MAX_TEMPLATE_LEN);
+                            }
+
+                            templates.insert(name.clone(), template.clone());
+
+                            box move |ce| {
+                                ce.title(locale.get(Msg::SavedTemplate))
+                                    .color(SAVE_COLOR)
+                                    .field(name, template, false)
+                            }
+                        },
+                        ContentTemplateOp::Delete { name } => {
+                            let template = templates
+                                .remove(&name)
+                                .ok_or_else(|| anyhow!("cannot find template: {}", name))?;
+
+                            box move |ce| {
+                                ce.title(locale.get(Msg::DeletedTemplate))
+                                    .color(SAVE_COLOR)
+                                    .field(name, template, false)
+                            }
+                        },
+                        ContentTemplateOp::Show => {
+                            let mut list = templates
+                                .iter()
+                                .map(|(k, v)| (k.clone(), v.clone(), false))
+                                .collect::<Vec<_>>();
+                            list.sort();
+
+                            box move |ce| {
+                                ce.title(locale.get(Msg::ShowingTemplates))
+                                    .color(SHOW_COLOR)
+                                    .description(format!("{} templates", list.len()))
+                                    .fields(list)
+                            }
+                        },
+                    };
+                    drop(guard);
+
+                    Ok(smallvec![view])
+                },
+
+                ContentMod::Get(ContentGetCmd {
+                    content_id,
+                    reveal: true,
+                    ..
+                }) => {
+                    const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+
+                    let content_id = self.resolve_content_id(content_id).await?;
+                    let content = self.return_content_contr.get(content_id).await?;
+
+                    let view: Box<View> = box move |ce| {
+                        ce.title(locale.get(Msg::RevealedPoster))
+                            .color(COLOR)
+                            .description(format!("{} ({})", content.id, content.short_id))
+                            .fields([
+                                ("posted", content.posted.to_string(), true),
+                                ("anonymous", content.anonymous.to_string(), true),
+                            ])
+                    };
+
+                    Ok(smallvec![view])
+                },
+
+                ContentMod::Get(ContentGetCmd {
+                    content_id,
+                    raw: true,
+                    reveal: false,
+                }) => {
+                    let content_id = self.resolve_content_id(content_id).await?;
+                    let content = self.return_content_contr.get(content_id).await?;
+
+                    return Ok(Response::Text(content.content));
+                },
+
+                ContentMod::Get(ContentGetCmd {
+                    content_id,
+                    raw: false,
+                    reveal: false,
+                }) => {
+                    let input = content::get::Input {
+                        content_id: self.resolve_content_id(content_id).await?,
+                        requester: Some(ex_user_id),
+                    };
+
+                    match json {
+                        true => {
+                            let output = self.return_content_contr.get_output(input).await?;
+                            return Ok(Response::Json(content_to_json(output)));
+                        },
+                        false => self.content.get(input).await.map(|v| smallvec![v]),
+                    }
+                },
+
+                ContentMod::History(ContentHistoryCmd { content_id }) => self
                     .content
-                    .get(content::get::Input {
-                        content_id: content_id.let_(ContentId),
+                    .history(content::history::Input {
+                        content_id: self.resolve_content_id(content_id).await?,
                     })
                     .await
                     .map(|v| smallvec![v]),
 
-                ContentMod::Gets(ContentGetsCmd { page, query }) => self
+                ContentMod::Gets(ContentGetsCmd {
+                    page,
+                    query,
+                    resolve_authors,
+                    include_archived,
+                    save,
+                    load,
+                    after,
+                    before,
+                }) => {
+                    let mut query = match (load, query) {
+                        (Some(name), _) => {
+                            let guard = self.saved_queries.read().await;
+                            let saved = guard
+                                .get(&ex_user_id)
+                                .and_then(|m| m.get(&name))
+                                .ok_or_else(|| anyhow!("cannot find saved query: {}", name))?;
+                            serde_json::from_str::<ContentQuery>(saved)?
+                        },
+                        (None, Some(q)) => q,
+                        (None, None) => self.default_query(ex_guild_id).await?,
+                    };
+                    if let Some(name) = save {
+                        let mut guard = self.saved_queries.write().await;
+                        let saved = guard.entry(ex_user_id).or_default();
+                        if !saved.contains_key(&name) && saved.len() >= MAX_SAVED_QUERIES {
+                            bail!(DomainError::Invalid(format!(
+                                "too many saved queries. (max: {})",
+                                MAX_SAVED_QUERIES
+                            )));
+                        }
+                        saved.insert(name, serde_json::to_string(&query)?);
+                    }
+                    query.include_archived = include_archived;
+                    let resolver = match resolve_authors {
+                        true => Some(Arc::new(HttpAuthorResolver {
+                            http,
+                            guild_id: ex_guild_id,
+                        })
+                            as Arc<dyn AuthorResolver + Sync + Send>),
+                        false => None,
+                    };
+
+                    let cursor = match (after, before) {
+                        (Some(id), _) => Some(ContentCursor::After(id.into())),
+                        (None, Some(id)) => Some(ContentCursor::Before(id.into())),
+                        (None, None) => None,
+                    };
+
+                    let input = content::gets::Input {
+                        query,
+                        page,
+                        cursor,
+                        resolver,
+                    };
+
+                    match json {
+                        true => {
+                            let output = self.return_content_contr.gets(input).await?;
+                            return Ok(Response::Json(contents_to_json(output)));
+                        },
+                        false => self
+                            .content
+                            .gets(input)
+                            .await
+                            .map(|mut v| v.drain(..).collect()),
+                    }
+                },
+
+                ContentMod::Edit(ContentEditCmd {
+                    content_id,
+                    mutation:
+                        PartialContentMutation {
+                            content: Some(ContentContentMutation::Sed { capture, replace }),
+                            ..
+                        },
+                    dry_run: true,
+                }) => self
                     .content
-                    .gets(content::gets::Input { query, page })
+                    .preview_sed(content::preview_sed::Input {
+                        content_id: content_id.let_(ContentId),
+                        capture,
+                        replace,
+                    })
                     .await
-                    .map(|mut v| v.drain(..).collect()),
+                    .map(|v| smallvec![v]),
+
+                ContentMod::Edit(ContentEditCmd { dry_run: true, .. }) =>
+                    bail!(DomainError::Invalid(
+                        "dry-run is only available with sed content mutation.".to_string()
+                    )),
 
                 ContentMod::Edit(ContentEditCmd {
                     content_id,
                     mutation: p,
+                    dry_run: false,
                 }) => {
-                    let PartialContentMutation { author, content } = p;
+                    let PartialContentMutation {
+                        author,
+                        content,
+                        title,
+                    } = p;
                     let author = match author {
                         Some(PartialAuthor::Virtual(s)) => Some(Author::Virtual(s)),
-                        Some(PartialAuthor::User(i)) => {
-                            let user = http
-                                .http()
-                                .get_user(i.0)
-                                .await
-                                .map_err(|e| anyhow!("cannot get author: {}", e))?;
-
-                            let nick = ex_guild_id
-                                .map(|i| user.nick_in(http, i))
-                                .transpose()
-                                .await
-                                .flatten();
-                            let id = user.id.let_(|i| i.0).let_(UserId);
-                            let name = user.name;
-
-                            Some(Author::User { id, name, nick })
+                        Some(PartialAuthor::VirtualFromUser { id }) =>
+                            Some(Author::virtual_from_user(id)),
+                        Some(PartialAuthor::User(i)) => match self
+                            .resolve_user_author(i.0, ex_guild_id, http)
+                            .await
+                        {
+                            Ok(a) => Some(a),
+                            Err(e) if self.virtual_author_fallback => {
+                                tracing::info!("fallback to virtual author - id {} | err {}", i, e);
+
+                                Some(Author::virtual_from_user(i))
+                            },
+                            Err(e) => return Err(e),
                         },
                         None => None,
                     };
                     let mutation = ContentMutation {
                         author,
                         content,
-                        edited: **ex_timestamp,
+                        title,
+                        edited: *ex_timestamp,
                     };
 
                     self.content
@@ -285,10 +1078,85 @@ impl SerenityReturnController {
                         .map(|v| smallvec![v])
                 },
 
+                ContentMod::RenameVirtual(ContentRenameVirtualCmd { from, to }) => self
+                    .content
+                    .rename_virtual(content::rename_virtual::Input { from, to })
+                    .await
+                    .map(|v| smallvec![v]),
+
+                ContentMod::ReassignPosted(ContentReassignPostedCmd {
+                    content_id,
+                    user_id,
+                }) => self
+                    .content
+                    .reassign_posted(content::reassign_posted::Input {
+                        content_id: content_id.let_(ContentId),
+                        new_user_id: user_id.let_(UserId),
+                        resolver: Arc::new(HttpAuthorResolver {
+                            http,
+                            guild_id: ex_guild_id,
+                        }),
+                    })
+                    .await
+                    .map(|v| smallvec![v]),
+
+                ContentMod::PreviewSed(ContentPreviewSedCmd {
+                    content_id,
+                    capture,
+                    replace,
+                }) => self
+                    .content
+                    .preview_sed(content::preview_sed::Input {
+                        content_id: content_id.let_(ContentId),
+                        capture,
+                        replace,
+                    })
+                    .await
+                    .map(|v| smallvec![v]),
+
                 ContentMod::Withdraw(ContentWithdrawCmd { content_id }) => self
                     .content
                     .withdraw(content::withdraw::Input {
                         content_id: content_id.let_(ContentId),
+                        archived_at: *ex_timestamp,
+                    })
+                    .await
+                    .map(|v| smallvec![v]),
+
+                ContentMod::Merge(ContentMergeCmd { from, into }) => self
+                    .content
+                    .merge(content::merge::Input {
+                        from: from.let_(ContentId),
+                        into: into.let_(ContentId),
+                        archived_at: *ex_timestamp,
+                    })
+                    .await
+                    .map(|v| smallvec![v]),
+
+                ContentMod::Tag(ContentTagCmd { op }) => match op {
+                    ContentTagOp::Add { content_id, tag } => self
+                        .content
+                        .tag(content::tag::Input {
+                            content_id: content_id.let_(ContentId),
+                            tag,
+                        })
+                        .await
+                        .map(|v| smallvec![v]),
+
+                    ContentTagOp::Remove { content_id, tag } => self
+                        .content
+                        .untag(content::untag::Input {
+                            content_id: content_id.let_(ContentId),
+                            tag,
+                        })
+                        .await
+                        .map(|v| smallvec![v]),
+                },
+
+                ContentMod::Restore(ContentRestoreCmd { content_id }) => self
+                    .content
+                    .restore(content::restore::Input {
+                        content_id: content_id.let_(ContentId),
                     })
                     .await
                     .map(|v| smallvec![v]),
@@ -297,7 +1165,7 @@ impl SerenityReturnController {
                     ContentLikeOp::Do { content_id } => self
                         .content
                         .like(content::like::Input {
-                            content_id: content_id.let_(ContentId),
+                            content_id: self.resolve_content_id(content_id).await?,
                             user_id: ex_user_id,
                         })
                         .await
@@ -306,17 +1174,48 @@ impl SerenityReturnController {
                     ContentLikeOp::Undo { content_id } => self
                         .content
                         .unlike(content::unlike::Input {
-                            content_id: content_id.let_(ContentId),
+                            content_id: self.resolve_content_id(content_id).await?,
                             user_id: ex_user_id,
                         })
                         .await
                         .map(|v| smallvec![v]),
 
-                    ContentLikeOp::Show { page, content_id } =>
+                    ContentLikeOp::DoMany { content_ids } => self
+                        .content
+                        .like_many(content::like_many::Input {
+                            content_ids: content_ids.into_iter().map(ContentId).collect(),
+                            user_id: ex_user_id,
+                        })
+                        .await
+                        .map(|v| smallvec![v]),
+
+                    ContentLikeOp::UndoMany { content_ids } => self
+                        .content
+                        .unlike_many(content::unlike_many::Input {
+                            content_ids: content_ids.into_iter().map(ContentId).collect(),
+                            user_id: ex_user_id,
+                        })
+                        .await
+                        .map(|v| smallvec![v]),
+
+                    ContentLikeOp::Count { content_id } => self
+                        .content
+                        .count_like(content::count_like::Input {
+                            content_id: self.resolve_content_id(content_id).await?,
+                        })
+                        .await
+                        .map(|v| smallvec![v]),
+
+                    ContentLikeOp::Show {
+                        page,
+                        content_id,
+                        detailed,
+                    } =>
                         self.content
                             .get_like(content::get_like::Input {
-                                content_id: content_id.let_(ContentId),
+                                content_id: self.resolve_content_id(content_id).await?,
                                 page,
+                                detailed,
                             })
                             .await,
                 },
@@ -325,7 +1224,7 @@ impl SerenityReturnController {
                     ContentPinOp::Do { content_id } => self
                         .content
                         .pin(content::pin::Input {
-                            content_id: content_id.let_(ContentId),
+                            content_id: self.resolve_content_id(content_id).await?,
                             user_id: ex_user_id,
                         })
                         .await
@@ -334,21 +1233,295 @@ impl SerenityReturnController {
                     ContentPinOp::Undo { content_id } => self
                         .content
                         .unpin(content::unpin::Input {
-                            content_id: content_id.let_(ContentId),
+                            content_id: self.resolve_content_id(content_id).await?,
                             user_id: ex_user_id,
                         })
                         .await
                         .map(|v| smallvec![v]),
 
+                    ContentPinOp::Count { content_id } => self
+                        .content
+                        .count_pin(content::count_pin::Input {
+                            content_id: self.resolve_content_id(content_id).await?,
+                        })
+                        .await
+                        .map(|v| smallvec![v]),
+
                     ContentPinOp::Show { page, content_id } =>
                         self.content
                             .get_pin(content::get_pin::Input {
-                                content_id: content_id.let_(ContentId),
+                                content_id: self.resolve_content_id(content_id).await?,
                                 page,
                             })
                             .await,
                 },
             },
+            RootMod::Guild { cmd } => match cmd {
+                GuildMod::DefaultQuery(GuildDefaultQueryCmd { query }) => {
+                    const COLOR: (u8, u8, u8) = (0xb8, 0xbb, 0x26);
+
+                    let guild_id = match ex_guild_id {
+                        Some(i) => i,
+                        None => bail!("this command is only available in guild."),
+                    };
+
+                    let mut guard = self.guild_default_queries.write().await;
+                    let description = match query {
+                        Some(q) => guard.insert(guild_id, q.clone()).let_(|_| q),
+                        None => guard.remove(&guild_id).let_(|_| "(cleared)".to_string()),
+                    };
+                    drop(guard);
+
+                    let view: Box<View> = box move |ce| {
+                        ce.title(locale.get(Msg::UpdatedDefaultQuery))
+                            .color(COLOR)
+                            .description(format!("```\n{}\n```", description))
+                    };
+
+                    Ok(smallvec![view])
+                },
+
+                GuildMod::Sticky(GuildStickyCmd { content_id }) => {
+                    const COLOR: (u8, u8, u8) = (0xb8, 0xbb, 0x26);
+
+                    if ex_guild_id.is_none() {
+                        bail!("this command is only available in guild.");
+                    }
+                    let channel_id = ex.channel_id;
+
+                    let description = match content_id {
+                        Some(r) => {
+                            let content_id = self.resolve_content_id(r).await?;
+                            // ensure existence.
+                            self.return_content_contr.get(content_id).await?;
+
+                            self.stickies.lock().await.insert(channel_id, Sticky {
+                                content_id,
+                                last: None,
+                                deferred: false,
+                            });
+
+                            content_id.to_string()
+                        },
+                        None => {
+                            self.stickies.lock().await.remove(&channel_id);
+
+                            "(cleared)".to_string()
+                        },
+                    };
+
+                    let view: Box<View> = box move |ce| {
+                        ce.title(locale.get(Msg::UpdatedStickyContent))
+                            .color(COLOR)
+                            .description(description)
+                    };
+
+                    Ok(smallvec![view])
+                },
+            },
+
+            RootMod::Stats => self
+                .content
+                .stats(content::stats::Input { top: STATS_TOP })
+                .await
+                .map(|v| smallvec![v]),
+
+            RootMod::Ping => {
+                const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+
+                let description = match self
+                    .health
+                    .ping()
+                    .await
+                    .map_err(|e| DomainError::Internal(anyhow!(e)))?
+                {
+                    Some(d) => format!("mongo, OK ({}ms)", d.as_millis()),
+                    None => "in-memory, OK".to_string(),
+                };
+
+                let view: Box<View> = box move |ce| {
+                    ce.title(locale.get(Msg::Pong))
+                        .color(COLOR)
+                        .description(description)
+                };
+
+                Ok(smallvec![view])
+            },
+
+            RootMod::Help { subcommand } => {
+                const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+
+                let (help, short_forms) = render_help(&self.prefix, &subcommand)?;
+                let title = match subcommand.is_empty() {
+                    true => locale.get(Msg::ShowingHelp).to_string(),
+                    false => format!("{}: {}", locale.get(Msg::ShowingHelp), subcommand.join(" ")),
+                };
+
+                let view: Box<View> = box move |ce| {
+                    ce.title(title)
+                        .color(COLOR)
+                        .description(format!("```\n{}\n```", help));
+
+                    if let Some(s) = short_forms {
+                        ce.field("short forms", s, false);
+                    }
+
+                    ce
+                };
+
+                Ok(smallvec![view])
+            },
+        };
+
+        match paged {
+            true => res.map(Response::Paged),
+            false => res.map(Response::Embeds),
+        }
+    }
+
+    /// decides whether sticky content in the channel should be re-posted.
+    /// `deferred` is for re-posting scheduled by `StickyPost::Later`, which is
+    /// skipped if posted meanwhile.
+    pub async fn sticky(&self, channel_id: u64, deferred: bool) -> Option<StickyPost> {
+        // not held while fetching, since this runs on every message.
+        let (content_id, prev) = {
+            let mut guard = self.stickies.lock().await;
+            let sticky = guard.get_mut(&channel_id)?;
+
+            if deferred && !sticky.deferred {
+                return None;
+            }
+
+            if let Some((_, at)) = sticky.last {
+                let elapsed = at.elapsed();
+                if elapsed < STICKY_INTERVAL {
+                    // only one is scheduled at a time.
+                    if sticky.deferred && !deferred {
+                        return None;
+                    }
+                    sticky.deferred = true;
+
+                    return Some(StickyPost::Later(STICKY_INTERVAL - elapsed));
+                }
+            }
+
+            let prev = sticky.last.and_then(|(i, _)| i);
+            // reserve, or concurrent messages re-post it twice.
+            sticky.last = Some((prev, Instant::now()));
+            sticky.deferred = false;
+
+            (sticky.content_id, prev)
+        };
+
+        let data = usecases::content::get::Input {
+            content_id,
+            requester: None,
+        };
+
+        match self.content.get(data).await {
+            Ok(v) => Some(StickyPost::Now(v, prev)),
+            Err(e) => {
+                // others may be transient, so retried at next time.
+                if let Some(DomainError::NotFound(_)) = e.downcast_ref::<DomainError>() {
+                    let mut guard = self.stickies.lock().await;
+                    // may be replaced meanwhile.
+                    if guard.get(&channel_id).map(|s| s.content_id) == Some(content_id) {
+                        guard.remove(&channel_id);
+                        tracing::warn!("removed sticky - channel_id {} | err {}", channel_id, e);
+                    }
+                } else {
+                    tracing::warn!("getting sticky err - channel_id {} | err {}", channel_id, e);
+                }
+
+                None
+            },
+        }
+    }
+
+    pub async fn sticky_posted(&self, channel_id: u64, message_id: u64) {
+        if let Some(s) = self.stickies.lock().await.get_mut(&channel_id) {
+            s.last = Some((Some(message_id), Instant::now()));
+        }
+    }
+
+    async fn resolve_author(
+        &self,
+        user_id: Option<u64>,
+        virt: Option<String>,
+        ex_guild_id: Option<u64>,
+        http: impl CacheHttp + Clone,
+    ) -> Result<Author> {
+        let author = match (user_id, virt) {
+            (Some(i), None) => self.resolve_user_author(i, ex_guild_id, http).await?,
+            (None, Some(s)) => Author::Virtual(s),
+            _ => bail!("internal processing error"),
+        };
+
+        Ok(author)
+    }
+
+    async fn resolve_user_author(
+        &self,
+        user_id: u64,
+        ex_guild_id: Option<u64>,
+        http: impl CacheHttp + Clone,
+    ) -> Result<Author> {
+        let user = match http.http().get_user(user_id).await {
+            Ok(u) => u,
+            Err(e) if is_unknown_user(&e) => {
+                tracing::info!("unknown author - id {}", user_id);
+
+                if !self.allow_unknown_author {
+                    bail!(DomainError::NotFound(format!(
+                        "user doesn't exist on discord: {}",
+                        user_id
+                    )));
+                }
+
+                return Ok(Author::User {
+                    id: UserId(user_id),
+                    name: UNKNOWN_AUTHOR_NAME.to_string(),
+                    nick: None,
+                });
+            },
+            Err(e) => {
+                tracing::warn!("cannot get author - id {} | err {:?}", user_id, e);
+
+                bail!(DomainError::Internal(anyhow!(
+                    "cannot get user from discord: {}. retry later.",
+                    user_id
+                )));
+            },
+        };
+
+        let nick = ex_guild_id
+            .map(|i| user.nick_in(http, i))
+            .transpose()
+            .await
+            .flatten();
+        let id = user.id.let_(|i| i.0).let_(UserId);
+        let name = user.name;
+
+        Ok(Author::User { id, name, nick })
+    }
+
+    async fn default_query(&self, ex_guild_id: Option<u64>) -> Result<ContentQuery> {
+        let raw = match ex_guild_id {
+            Some(i) => self.guild_default_queries.read().await.get(&i).cloned(),
+            None => None,
+        };
+
+        match raw {
+            // already validated on set.
+            Some(s) => parse_content_query(s.as_str()).map_err(|e| anyhow!(e)),
+            None => Ok(ContentQuery::default()),
+        }
+    }
+
+    async fn resolve_content_id(&self, content_ref: ContentRef) -> Result<ContentId> {
+        match content_ref {
+            ContentRef::Id(i) => Ok(ContentId(i)),
+            ContentRef::Short(s) => self.return_content_contr.resolve(s).await,
         }
     }
 
@@ -357,28 +1530,257 @@ impl SerenityReturnController {
 
         let res = match &cmd.cmd {
             RootMod::User { cmd } => match cmd {
-                UserMod::Edit(_) | UserMod::Unregister(_) => ex_user_res?.admin,
+                UserMod::RegisterFor(_) | UserMod::Edit(_) | UserMod::Unregister(_) =>
+                    ex_user_res?.admin,
                 _ => true,
             },
+            RootMod::Guild { .. } => ex_user_res?.admin,
+            RootMod::Stats | RootMod::Ping | RootMod::Help { .. } => true,
             RootMod::Content { cmd } => match cmd {
+                ContentMod::Get(ContentGetCmd { reveal: true, .. })
+                | ContentMod::Gets(ContentGetsCmd {
+                    include_archived: true,
+                    ..
+                })
+                | ContentMod::RenameVirtual(_)
+                | ContentMod::Restore(_)
+                | ContentMod::Merge(_) => ex_user_res?.admin,
+                ContentMod::ReassignPosted(_) => ex_user_res?.let_(|u| u.admin || u.sub_admin),
                 ContentMod::Edit(ContentEditCmd { content_id, .. })
-                | ContentMod::Withdraw(ContentWithdrawCmd { content_id, .. }) => {
-                    let ex_user = ex_user_res?;
-
-                    let content = self
-                        .return_content_contr
-                        .get((*content_id).let_(ContentId))
-                        .await?;
-
-                    content.posted.id == ex_user_id || ex_user.admin || ex_user.sub_admin
-                },
+                | ContentMod::PreviewSed(ContentPreviewSedCmd { content_id, .. })
+                | ContentMod::Withdraw(ContentWithdrawCmd { content_id, .. })
+                | ContentMod::Tag(ContentTagCmd {
+                    op: ContentTagOp::Add { content_id, .. },
+                })
+                | ContentMod::Tag(ContentTagCmd {
+                    op: ContentTagOp::Remove { content_id, .. },
+                }) =>
+                    self.is_content_operable((*content_id).let_(ContentId), ex_user_id)
+                        .await?,
                 _ => true,
             },
         };
 
         match res {
             true => Ok(cmd),
-            false => Err(anyhow!("not permitted operation")),
+            false => Err(DomainError::Forbidden("not permitted operation".to_string()).into()),
+        }
+    }
+
+    async fn is_content_operable(&self, content_id: ContentId, ex_user_id: UserId) -> Result<bool> {
+        let ex_user = self.return_user_contr.get(ex_user_id).await?;
+        let content = self.return_content_contr.get(content_id).await?;
+
+        Ok(content.posted.id == ex_user_id || ex_user.admin || ex_user.sub_admin)
+    }
+}
+
+/// fills `{key}` placeholders in `template`.
+/// every placeholder must be given, and every arg must be used.
+fn fill_template(template: &str, args: Vec<(String, String)>) -> Result<String> {
+    let re = Regex::new(r"\{(\w+)\}").unwrap();
+
+    let args = args.into_iter().collect::<HashMap<_, _>>();
+    let placeholders = re
+        .captures_iter(template)
+        .map(|c| c[1].to_string())
+        .collect::<BTreeSet<_>>();
+
+    let missing = placeholders
+        .iter()
+        .filter(|k| !args.contains_key(*k))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        bail!("missing placeholders: {}", missing.join(", "));
+    }
+
+    let mut unknown = args
+        .keys()
+        .filter(|k| !placeholders.contains(*k))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        unknown.sort();
+        bail!("unknown placeholders: {}", unknown.join(", "));
+    }
+
+    re.replace_all(template, |c: &Captures| args[&c[1]].clone())
+        .to_string()
+        .let_(Ok)
+}
+
+struct HttpAuthorResolver<H> {
+    http: H,
+    guild_id: Option<u64>,
+}
+impl<H> ::core::fmt::Debug for HttpAuthorResolver<H> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.debug_struct("HttpAuthorResolver")
+            .field("guild_id", &self.guild_id)
+            .finish_non_exhaustive()
+    }
+}
+#[async_trait]
+impl<H: CacheHttp + Clone> AuthorResolver for HttpAuthorResolver<H> {
+    async fn resolve(&self, id: UserId) -> Option<(String, Option<String>)> {
+        // to_user looks up cache first.
+        let user = match ::serenity::model::id::UserId(id.0)
+            .to_user(&self.http)
+            .await
+        {
+            Ok(u) => u,
+            Err(e) => {
+                tracing::warn!("cannot resolve author - id {} | err {}", id, e);
+                return None;
+            },
+        };
+
+        let nick = match self.guild_id {
+            Some(i) => user.nick_in(&self.http, i).await,
+            None => None,
+        };
+
+        Some((user.name, nick))
+    }
+}
+
+/// whether the error means that the user doesn't exist. (e.g. deleted)
+fn is_unknown_user(e: &::serenity::Error) -> bool {
+    match e {
+        ::serenity::Error::Http(e) => matches!(
+            &**e,
+            ::serenity::http::HttpError::UnsuccessfulRequest(r) if r.status_code.as_u16() == 404
+        ),
+        _ => false,
+    }
+}
+
+/// suggests closest subcommand for first unrecognized one in `args`
+/// (instead of whole help).
+fn suggest_subcommand(args: &[::std::ffi::OsString], max_distance: usize) -> Option<String> {
+    use clap::CommandFactory;
+
+    if max_distance == 0 {
+        return None;
+    }
+
+    let root = Cmd::command();
+    let mut current = &root;
+    let mut path = vec![args.get(0)?.to_str()?.to_string()];
+
+    for arg in args.iter().skip(1) {
+        let arg = arg.to_str()?;
+
+        if current.get_subcommands().next().is_none() {
+            return None;
+        }
+
+        let found = current.get_subcommands().find(|c| {
+            c.get_name() == arg
+                || c.get_short_flag()
+                    .map(|f| arg == format!("-{}", f))
+                    .unwrap_or(false)
+        });
+
+        match found {
+            Some(c) => {
+                path.push(c.get_name().to_string());
+                current = c;
+            },
+            // options (and help) are left to clap.
+            None if arg.starts_with('-') => return None,
+            None => {
+                let (name, distance) = current
+                    .get_subcommands()
+                    .map(|c| c.get_name())
+                    .map(|n| (n, edit_distance(arg, n)))
+                    .min_by_key(|(_, d)| *d)?;
+
+                if distance > max_distance {
+                    return None;
+                }
+
+                return format!(
+                    "unrecognized subcommand '{}'. did you mean `{} {}`?",
+                    arg,
+                    path.join(" "),
+                    name
+                )
+                .let_(Some);
+            },
         }
     }
+
+    None
+}
+
+/// renders long help of subcommand at `path` (by names or short flags),
+/// and short forms of its subcommands if any.
+fn render_help(prefix: &str, path: &[String]) -> Result<(String, Option<String>)> {
+    use clap::{CommandFactory, ErrorKind, Parser};
+
+    // discord rejects embed description longer than 4096, including fences.
+    const HELP_LIMIT: usize = 4000;
+
+    let args = ::core::iter::once(prefix)
+        .chain(path.iter().map(String::as_str))
+        .chain(::core::iter::once("--help"));
+
+    let help = match Cmd::try_parse_from(args) {
+        Err(e) if e.kind() == ErrorKind::DisplayHelp => e.to_string(),
+        Err(e) => bail!(DomainError::Invalid(e.to_string())),
+        Ok(_) => bail!(DomainError::Invalid(format!(
+            "not a subcommand: {}",
+            path.join(" ")
+        ))),
+    };
+    let help = match help.char_indices().nth(HELP_LIMIT) {
+        Some((at, _)) => format!("{}...", &help[..at]),
+        None => help,
+    };
+
+    let root = Cmd::command();
+    let mut current = &root;
+    for arg in path {
+        current = match current.get_subcommands().find(|c| {
+            c.get_name() == arg
+                || c.get_short_flag()
+                    .map(|f| *arg == format!("-{}", f))
+                    .unwrap_or(false)
+        }) {
+            Some(c) => c,
+            None => break,
+        };
+    }
+
+    let short_forms = current
+        .get_subcommands()
+        .filter_map(|c| {
+            c.get_short_flag()
+                .map(|f| format!("`-{}` {}", f, c.get_name()))
+        })
+        .collect::<Vec<_>>();
+    let short_forms = match short_forms.is_empty() {
+        true => None,
+        false => Some(short_forms.join("\n")),
+    };
+
+    Ok((help, short_forms))
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+
+    prev[b.len()]
 }
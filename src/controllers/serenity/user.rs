@@ -5,9 +5,10 @@ use async_recursion::async_recursion;
 use smallvec::SmallVec;
 use tokio::sync::{mpsc, Mutex};
 
-use crate::presenters::impls::serenity::View;
+use crate::presenters::impls::serenity::{Attachment, View};
 use crate::usecases::user::{
-    bookmark, edit, get, get_bookmark, gets, register, unbookmark, unregister,
+    bookmark, count_bookmark, edit, export_bookmark, get, get_bookmark, get_bookmark_contents,
+    gets, register, similar, status, toggle_bookmark, unbookmark, unregister,
 };
 
 pub struct SerenityUserController {
@@ -15,6 +16,10 @@ pub struct SerenityUserController {
     pub register_lock: Mutex<()>,
     pub register_ret: Mutex<mpsc::Receiver<Box<View>>>,
 
+    pub status: Arc<dyn status::Usecase + Sync + Send>,
+    pub status_lock: Mutex<()>,
+    pub status_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
     pub get: Arc<dyn get::Usecase + Sync + Send>,
     pub get_lock: Mutex<()>,
     pub get_ret: Mutex<mpsc::Receiver<Box<View>>>,
@@ -23,6 +28,10 @@ pub struct SerenityUserController {
     pub gets_lock: Mutex<()>,
     pub gets_ret: Mutex<mpsc::Receiver<SmallVec<[Box<View>; 5]>>>,
 
+    pub similar: Arc<dyn similar::Usecase + Sync + Send>,
+    pub similar_lock: Mutex<()>,
+    pub similar_ret: Mutex<mpsc::Receiver<SmallVec<[Box<View>; 5]>>>,
+
     pub edit: Arc<dyn edit::Usecase + Sync + Send>,
     pub edit_lock: Mutex<()>,
     pub edit_ret: Mutex<mpsc::Receiver<Box<View>>>,
@@ -35,6 +44,18 @@ pub struct SerenityUserController {
     pub get_bookmark_lock: Mutex<()>,
     pub get_bookmark_ret: Mutex<mpsc::Receiver<SmallVec<[Box<View>; 20]>>>,
 
+    pub get_bookmark_contents: Arc<dyn get_bookmark_contents::Usecase + Sync + Send>,
+    pub get_bookmark_contents_lock: Mutex<()>,
+    pub get_bookmark_contents_ret: Mutex<mpsc::Receiver<SmallVec<[Box<View>; 20]>>>,
+
+    pub count_bookmark: Arc<dyn count_bookmark::Usecase + Sync + Send>,
+    pub count_bookmark_lock: Mutex<()>,
+    pub count_bookmark_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
+    pub export_bookmark: Arc<dyn export_bookmark::Usecase + Sync + Send>,
+    pub export_bookmark_lock: Mutex<()>,
+    pub export_bookmark_ret: Mutex<mpsc::Receiver<Attachment>>,
+
     pub bookmark: Arc<dyn bookmark::Usecase + Sync + Send>,
     pub bookmark_lock: Mutex<()>,
     pub bookmark_ret: Mutex<mpsc::Receiver<Box<View>>>,
@@ -42,6 +63,10 @@ pub struct SerenityUserController {
     pub unbookmark: Arc<dyn unbookmark::Usecase + Sync + Send>,
     pub unbookmark_lock: Mutex<()>,
     pub unbookmark_ret: Mutex<mpsc::Receiver<Box<View>>>,
+
+    pub toggle_bookmark: Arc<dyn toggle_bookmark::Usecase + Sync + Send>,
+    pub toggle_bookmark_lock: Mutex<()>,
+    pub toggle_bookmark_ret: Mutex<mpsc::Receiver<Box<View>>>,
 }
 impl SerenityUserController {
     #[async_recursion]
@@ -54,6 +79,16 @@ impl SerenityUserController {
         )
     }
 
+    #[async_recursion]
+    pub async fn status(&self, data: status::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use status,
+            lock status_lock,
+            ret status_ret,
+            data data
+        )
+    }
+
     #[async_recursion]
     pub async fn get(&self, data: get::Input) -> Result<Box<View>> {
         return_inner!(self =>
@@ -74,6 +109,16 @@ impl SerenityUserController {
         )
     }
 
+    #[async_recursion]
+    pub async fn similar(&self, data: similar::Input) -> Result<SmallVec<[Box<View>; 5]>> {
+        return_inner!(self =>
+            use similar,
+            lock similar_lock,
+            ret similar_ret,
+            data data
+        )
+    }
+
     #[async_recursion]
     pub async fn edit(&self, data: edit::Input) -> Result<Box<View>> {
         return_inner!(self =>
@@ -107,6 +152,39 @@ impl SerenityUserController {
         )
     }
 
+    #[async_recursion]
+    pub async fn get_bookmark_contents(
+        &self,
+        data: get_bookmark_contents::Input,
+    ) -> Result<SmallVec<[Box<View>; 20]>> {
+        return_inner!(self =>
+            use get_bookmark_contents,
+            lock get_bookmark_contents_lock,
+            ret get_bookmark_contents_ret,
+            data data
+        )
+    }
+
+    #[async_recursion]
+    pub async fn count_bookmark(&self, data: count_bookmark::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use count_bookmark,
+            lock count_bookmark_lock,
+            ret count_bookmark_ret,
+            data data
+        )
+    }
+
+    #[async_recursion]
+    pub async fn export_bookmark(&self, data: export_bookmark::Input) -> Result<Attachment> {
+        return_inner!(self =>
+            use export_bookmark,
+            lock export_bookmark_lock,
+            ret export_bookmark_ret,
+            data data
+        )
+    }
+
     #[async_recursion]
     pub async fn bookmark(&self, data: bookmark::Input) -> Result<Box<View>> {
         return_inner!(self =>
@@ -126,4 +204,14 @@ impl SerenityUserController {
             data data
         )
     }
+
+    #[async_recursion]
+    pub async fn toggle_bookmark(&self, data: toggle_bookmark::Input) -> Result<Box<View>> {
+        return_inner!(self =>
+            use toggle_bookmark,
+            lock toggle_bookmark_lock,
+            ret toggle_bookmark_ret,
+            data data
+        )
+    }
 }
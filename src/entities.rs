@@ -14,12 +14,19 @@ use std::collections::HashSet;
 )]
 pub struct UserId(pub u64);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, ::serde::Serialize)]
 pub struct User {
     pub id: UserId,
     pub admin: bool,
     pub sub_admin: bool,
     pub bookmark: HashSet<ContentId>,
+    /// number of posted contents. derived at read time (not stored), so `None`
+    /// unless counted.
+    pub posted_count: Option<u64>,
+    /// who made the user admin (or sub_admin) last, and when. `None` if never
+    /// granted since recorded.
+    pub granted_by: Option<UserId>,
+    pub granted_at: Option<Date>,
 }
 
 #[derive(
@@ -36,7 +43,7 @@ pub struct User {
 )]
 pub struct ContentId(pub ::uuid::Uuid);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, ::serde::Serialize)]
 pub struct Content {
     pub id: ContentId,
     pub author: Author,
@@ -46,16 +53,33 @@ pub struct Content {
     pub pinned: HashSet<UserId>,
     pub created: Date,
     pub edited: Vec<Date>,
+    pub short_id: String,
+    pub title: Option<String>,
+    /// where content came from. always http(s) with host.
+    pub source_url: Option<String>,
+    /// hides `posted` from others. (still recorded for moderation)
+    pub anonymous: bool,
+    /// detected language (ISO 639-3). `None` if undetermined.
+    pub lang: Option<String>,
+    /// replaced contents, with time of replacement. oldest first.
+    pub edit_history: Vec<(Date, String)>,
+    /// incremented on each update, to detect concurrent updates.
+    pub version: u64,
+    /// withdrawn, but kept for moderation. hidden unless asked.
+    pub archived: bool,
+    pub archived_at: Option<Date>,
+    /// free-form labels, given by poster (or admins).
+    pub tags: HashSet<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, ::serde::Serialize)]
 pub struct Posted {
     pub id: UserId,
     pub name: String,
     pub nick: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, ::serde::Serialize)]
 pub enum Author {
     User {
         id: UserId,
@@ -71,6 +95,16 @@ pub type Date = ::chrono::DateTime<::chrono::Utc>;
 pub enum PartialAuthor {
     User(UserId),
     Virtual(String),
+    /// placeholder of the user, stored as `Author::Virtual` without looking
+    /// up the user.
+    VirtualFromUser {
+        id: UserId,
+    },
+}
+
+impl Author {
+    /// placeholder of the user (e.g. whose account is gone).
+    pub fn virtual_from_user(id: UserId) -> Self { Author::Virtual(format!("user:{}", id)) }
 }
 
 impl ::core::fmt::Display for UserId {
@@ -0,0 +1,28 @@
+/// errors caused by operations on domain, distinguished by whose fault it is.
+///
+/// carried through `anyhow::Error`, so downcast to inspect.
+#[derive(Debug)]
+pub enum DomainError {
+    NotFound(String),
+    AlreadyExists(String),
+    Forbidden(String),
+    /// conflicted with concurrent operation. retrying may succeed.
+    Conflict(String),
+    /// invalid input (e.g. empty content, out of range page).
+    Invalid(String),
+    Internal(anyhow::Error),
+}
+
+impl ::core::fmt::Display for DomainError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match self {
+            DomainError::NotFound(s)
+            | DomainError::AlreadyExists(s)
+            | DomainError::Forbidden(s)
+            | DomainError::Conflict(s)
+            | DomainError::Invalid(s) => write!(f, "{}", s),
+            DomainError::Internal(e) => write!(f, "{}", e),
+        }
+    }
+}
+impl ::std::error::Error for DomainError {}
@@ -1,26 +1,103 @@
 use alloc::sync::Arc;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::{bail, Result};
 use async_trait::async_trait;
 use smallvec::SmallVec;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
 
 use super::*;
-use crate::entities::{Author, Content};
+use crate::entities::{Author, Content, ContentId, Posted, UserId};
+use crate::notifications::NotificationSink;
 use crate::presenters::content::{
-    ContentEditPresenter, ContentGetPresenter, ContentGetsPresenter, ContentLikeGetPresenter,
-    ContentLikePresenter, ContentPinGetPresenter, ContentPinPresenter, ContentPostPresenter,
-    ContentUnlikePresenter, ContentUnpinPresenter, ContentWithdrawPresenter,
+    ContentEditPresenter, ContentGetPresenter, ContentGetsPresenter, ContentHistoryPresenter,
+    ContentLikeCountPresenter, ContentLikeGetPresenter, ContentLikeManyPresenter,
+    ContentLikePresenter, ContentMergePresenter, ContentPinCountPresenter, ContentPinGetPresenter,
+    ContentPinPresenter, ContentPostPresenter, ContentPreviewSedPresenter,
+    ContentReassignPostedPresenter, ContentRenameVirtualPresenter, ContentResolvePresenter,
+    ContentRestorePresenter, ContentStatsPresenter, ContentTagPresenter,
+    ContentUnlikeManyPresenter, ContentUnlikePresenter, ContentUnpinPresenter,
+    ContentUntagPresenter, ContentWithdrawPresenter,
 };
 use crate::repositories::{ContentRepository, UserRepository};
 use crate::usecases::content::{
-    edit, get, get_like, get_pin, gets, like, pin, post, unlike, unpin, withdraw,
+    count_like, count_pin, edit, get, get_like, get_pin, gets, history, like, like_many, merge,
+    pin, post, preview_sed, reassign_posted, rename_virtual, resolve, restore, stats, tag, unlike,
+    unlike_many, unpin, untag, withdraw, AuthorResolver, ContentContentMutation, ContentCursor,
+    ContentQuery, ContentSort, PostedQuery, SortKey,
 };
-use crate::utils::{AlsoChain, LetChain};
+use crate::usecases::user::UserQuery;
+use crate::utils::{detect_lang, normalize_content, AlsoChain, LetChain};
+
+// ids (and short ids) are regenerated on collision up to this.
+const MAX_INSERT_ATTEMPTS: usize = 5;
+
+// collecting bookmarked ids scans all users, so reuse it for a while.
+const BOOKMARKED_CACHE_TTL: Duration = Duration::from_secs(30);
+
+// embed title is limited to 256 chars, and post prefixes it.
+const MAX_TITLE_LEN: usize = 200;
+
+const MAX_TAG_LEN: usize = 32;
+
+const SHORT_ID_LEN: usize = 6;
+// no confusing characters (0/o, 1/l).
+const SHORT_ID_CHARS: &[u8] = b"abcdefghijkmnpqrstuvwxyz23456789";
+
+fn gen_short_id() -> String {
+    ::uuid::Uuid::new_v4()
+        .as_bytes()
+        .iter()
+        .take(SHORT_ID_LEN)
+        .map(|b| SHORT_ID_CHARS[*b as usize % SHORT_ID_CHARS.len()] as char)
+        .collect()
+}
+
+fn validate_title(title: Option<&str>) -> Result<()> {
+    match title {
+        Some(t) if t.is_empty() =>
+            bail!(DomainError::Invalid("title cannot be empty.".to_string())),
+        Some(t) if t.chars().count() > MAX_TITLE_LEN => bail!(DomainError::Invalid(format!(
+            "title is too long. (max: {} chars)",
+            MAX_TITLE_LEN
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn validate_content(content: &str, max_len: usize) -> Result<()> {
+    match content.chars().count() {
+        0 => bail!(DomainError::Invalid("content cannot be empty.".to_string())),
+        n if n > max_len => bail!(DomainError::Invalid(format!(
+            "content is too long. (max: {} chars, actual: {} chars)",
+            max_len, n
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn validate_tag(tag: &str) -> Result<()> {
+    match tag {
+        t if t.is_empty() => bail!(DomainError::Invalid("tag cannot be empty.".to_string())),
+        t if t.contains(char::is_whitespace) => bail!(DomainError::Invalid(
+            "tag cannot contain whitespaces.".to_string()
+        )),
+        t if t.chars().count() > MAX_TAG_LEN => bail!(DomainError::Invalid(format!(
+            "tag is too long. (max: {} chars)",
+            MAX_TAG_LEN
+        ))),
+        _ => Ok(()),
+    }
+}
 
 pub struct ContentPostInteractor {
     pub user_repository: Arc<dyn UserRepository + Sync + Send>,
     pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub max_contents_per_user: Option<usize>,
+    pub max_content_len: usize,
+    pub post_cooldown: Option<Duration>,
+    pub notifier: Arc<dyn NotificationSink + Sync + Send>,
     pub pres: Arc<dyn ContentPostPresenter + Sync + Send>,
 }
 #[async_trait]
@@ -31,10 +108,15 @@ impl post::Usecase for ContentPostInteractor {
 
         let post::Input {
             content,
+            title,
+            source_url,
+            anonymous,
             posted,
             author,
             created,
+            tags,
         } = data;
+        let content = normalize_content(content.as_str());
 
         let user_is_exists = self
             .user_repository
@@ -43,20 +125,84 @@ impl post::Usecase for ContentPostInteractor {
             .map_err(user_err_fmt)?;
 
         if !user_is_exists {
-            bail!("cannot find user. not registered?");
+            bail!(DomainError::NotFound(
+                "cannot find user. not registered?".to_string()
+            ));
+        }
+
+        if let Some(max) = self.max_contents_per_user {
+            let user = self
+                .user_repository
+                .find(posted.id)
+                .await
+                .map_err(user_err_fmt)?;
+
+            if !user.admin {
+                let posted_num = self
+                    .content_repository
+                    .count(ContentQuery {
+                        posted: Some(PostedQuery::UserId(posted.id)),
+                        ..Default::default()
+                    })
+                    .await
+                    .map_err(content_err_fmt)?;
+
+                if posted_num >= max as u64 {
+                    bail!(DomainError::Forbidden(format!(
+                        "post limit reached. ({} contents)",
+                        max
+                    )));
+                }
+            }
+        }
+
+        if let Some(cooldown) = self.post_cooldown {
+            let query = ContentQuery {
+                posted: Some(PostedQuery::UserId(posted.id)),
+                sort: Some(ContentSort {
+                    key: SortKey::Created,
+                    desc: true,
+                }),
+                ..Default::default()
+            };
+            let last = self
+                .content_repository
+                .finds_paged(query, 0, 1)
+                .await
+                .map_err(content_err_fmt)?
+                .0
+                .first()
+                .map(|c| c.created);
+
+            if let Some(last) = last {
+                // negative if clock went back, then treated as just posted.
+                let elapsed = (created - last).to_std().unwrap_or(Duration::ZERO);
+
+                if elapsed < cooldown {
+                    let wait = cooldown - elapsed;
+                    bail!(DomainError::Invalid(format!(
+                        "wait {}s before posting again.",
+                        wait.as_secs() + (wait.subsec_nanos() > 0) as u64
+                    )));
+                }
+            }
         }
 
         if let Author::Virtual(s) = &author {
             if s.is_empty() {
-                bail!("(virtual)username cannot be empty.");
+                bail!(DomainError::Invalid(
+                    "(virtual)username cannot be empty.".to_string()
+                ));
             }
         }
 
-        if content.is_empty() {
-            bail!("content cannot be empty.");
-        }
+        validate_content(content.as_str(), self.max_content_len)?;
+        validate_title(title.as_deref())?;
+        tags.iter().try_for_each(|t| validate_tag(t))?;
+
+        let lang = detect_lang(content.as_str());
 
-        let new_content = Content {
+        let mut new_content = Content {
             id: ::uuid::Uuid::new_v4().into(),
             content,
             author,
@@ -65,18 +211,57 @@ impl post::Usecase for ContentPostInteractor {
             pinned: HashSet::new(),
             created,
             edited: vec![],
+            short_id: gen_short_id(),
+            title,
+            source_url,
+            anonymous,
+            lang,
+            edit_history: vec![],
+            version: 0,
+            archived: false,
+            archived_at: None,
+            tags,
         };
 
-        let content_can_insert = self
-            .content_repository
-            .insert(new_content.clone())
-            .await
-            .map_err(content_err_fmt)?;
+        let mut content_can_insert = false;
+        for _ in 0..MAX_INSERT_ATTEMPTS {
+            content_can_insert = self
+                .content_repository
+                .insert(new_content.clone())
+                .await
+                .map_err(|e| match e {
+                    // the same body by the same user. (if repository rejects it)
+                    RepositoryError::NoUnique { .. } => DomainError::AlreadyExists(
+                        "same content is already posted by you.".to_string(),
+                    )
+                    .into(),
+                    e => content_err_fmt(e),
+                })?;
+
+            if content_can_insert {
+                break;
+            }
+
+            tracing::warn!(
+                "collided - id {} | short_id {}",
+                new_content.id,
+                new_content.short_id
+            );
+
+            new_content.id = ::uuid::Uuid::new_v4().into();
+            new_content.short_id = gen_short_id();
+        }
 
+        // practically unreachable, but not worth taking down the handler.
         if !content_can_insert {
-            panic!("content_id duplicated!");
+            bail!(DomainError::Internal(anyhow::anyhow!(
+                "cannot generate unique id in {} attempts",
+                MAX_INSERT_ATTEMPTS
+            )));
         }
 
+        self.notifier.content_posted(&new_content);
+
         post::Output {
             content: new_content,
         }
@@ -90,6 +275,7 @@ impl post::Usecase for ContentPostInteractor {
 }
 
 pub struct ContentGetInteractor {
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
     pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
     pub pres: Arc<dyn ContentGetPresenter + Sync + Send>,
 }
@@ -99,13 +285,60 @@ impl get::Usecase for ContentGetInteractor {
     async fn handle(&self, data: get::Input) -> anyhow::Result<()> {
         tracing::trace!("input - {:?}", data);
 
-        let get::Input { content_id } = data;
+        let get::Input {
+            content_id,
+            requester,
+        } = data;
+
+        let content = self
+            .content_repository
+            .find(content_id)
+            .await
+            .map_err(content_err_fmt)?;
+
+        let bookmarked_by_me = match requester {
+            Some(id) => match self.user_repository.is_bookmark(id, content_id).await {
+                Ok(b) => Some(b),
+                // not registered, so bookmarks nothing.
+                Err(RepositoryError::NotFound) => Some(false),
+                Err(e) => return Err(user_err_fmt(e)),
+            },
+            None => None,
+        };
+
+        // like and pin are already loaded with content.
+        get::Output {
+            liked_by_me: requester.map(|i| content.liked.contains(&i)),
+            pinned_by_me: requester.map(|i| content.pinned.contains(&i)),
+            bookmarked_by_me,
+            content,
+        }
+        .also_(|o| tracing::trace!("output - {:?}", o))
+        .let_(|r| self.pres.complete(r))
+        .await
+        .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ContentHistoryInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentHistoryPresenter + Sync + Send>,
+}
+#[async_trait]
+impl history::Usecase for ContentHistoryInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: history::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let history::Input { content_id } = data;
 
         self.content_repository
             .find(content_id)
             .await
             .map_err(content_err_fmt)?
-            .let_(|content| get::Output { content })
+            .let_(|content| history::Output { content })
             .also_(|o| tracing::trace!("output - {:?}", o))
             .let_(|r| self.pres.complete(r))
             .await
@@ -115,31 +348,23 @@ impl get::Usecase for ContentGetInteractor {
     }
 }
 
-pub struct ContentGetsInteractor {
+pub struct ContentResolveInteractor {
     pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
-    pub pres: Arc<dyn ContentGetsPresenter + Sync + Send>,
+    pub pres: Arc<dyn ContentResolvePresenter + Sync + Send>,
 }
 #[async_trait]
-impl gets::Usecase for ContentGetsInteractor {
+impl resolve::Usecase for ContentResolveInteractor {
     #[tracing::instrument(skip(self))]
-    async fn handle(&self, data: gets::Input) -> anyhow::Result<()> {
+    async fn handle(&self, data: resolve::Input) -> anyhow::Result<()> {
         tracing::trace!("input - {:?}", data);
 
-        let gets::Input { query, page } = data;
+        let resolve::Input { short_id } = data;
 
         self.content_repository
-            .finds(query)
+            .find_by_short_id(short_id)
             .await
             .map_err(content_err_fmt)?
-            .let_(|mut v| {
-                calc_paging(0..v.len(), 5, page as usize).map(move |lim| {
-                    v.drain(lim)
-                        .enumerate()
-                        .map(|(i, c)| (i as u32, c))
-                        .collect::<SmallVec<[_; 5]>>()
-                })
-            })?
-            .let_(|contents| gets::Output { contents, page })
+            .let_(|c| resolve::Output { content_id: c.id })
             .also_(|o| tracing::trace!("output - {:?}", o))
             .let_(|r| self.pres.complete(r))
             .await
@@ -149,8 +374,351 @@ impl gets::Usecase for ContentGetsInteractor {
     }
 }
 
+pub struct ContentGetsInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub bookmarked_cache: Mutex<Option<(Instant, HashSet<ContentId>)>>,
+    /// max number of ids in `liked` / `pinned` of query (including `any_of`).
+    pub max_query_ids: usize,
+    pub page_size: usize,
+    pub pres: Arc<dyn ContentGetsPresenter + Sync + Send>,
+}
+impl ContentGetsInteractor {
+    async fn bookmarked_ids(&self) -> anyhow::Result<HashSet<ContentId>> {
+        let mut guard = self.bookmarked_cache.lock().await;
+
+        if let Some((at, ids)) = guard.as_ref() {
+            if at.elapsed() < BOOKMARKED_CACHE_TTL {
+                return Ok(ids.clone());
+            }
+        }
+
+        let ids = self
+            .user_repository
+            .bookmarked_ids()
+            .await
+            .map_err(user_err_fmt)?;
+        *guard = Some((Instant::now(), ids.clone()));
+
+        Ok(ids)
+    }
+
+    /// checks each distinct author (of `Author::User`) only once.
+    async fn registered_authors(&self, contents: &[Content]) -> anyhow::Result<HashSet<UserId>> {
+        let ids = contents
+            .iter()
+            .filter_map(|c| match &c.author {
+                Author::User { id, .. } => Some(*id),
+                Author::Virtual(_) => None,
+            })
+            .collect::<HashSet<_>>();
+
+        let mut registered = HashSet::new();
+        for id in ids {
+            if self
+                .user_repository
+                .is_exists(id)
+                .await
+                .map_err(user_err_fmt)?
+            {
+                registered.insert(id);
+            }
+        }
+
+        Ok(registered)
+    }
+
+    /// filters contents with what repository cannot evaluate.
+    async fn retain_matched(
+        &self,
+        contents: &mut Vec<Content>,
+        bookmarked: Option<&(bool, HashSet<ContentId>)>,
+        author_registered: Option<bool>,
+        hide_anonymous: bool,
+    ) -> anyhow::Result<()> {
+        if let Some(b) = author_registered {
+            let registered = self.registered_authors(contents).await?;
+            contents.retain(|c| match &c.author {
+                Author::User { id, .. } => registered.contains(id) == b,
+                Author::Virtual(_) => !b,
+            });
+        }
+
+        if let Some((b, ids)) = bookmarked {
+            contents.retain(|c| ids.contains(&c.id) == *b);
+        }
+        if hide_anonymous {
+            contents.retain(|c| !c.anonymous);
+        }
+
+        Ok(())
+    }
+
+    /// fetches all matched contents and filters them with users, then pages.
+    /// returns the page with number of filtered contents.
+    async fn filtered_page(
+        &self,
+        query: ContentQuery,
+        page: u32,
+        bookmarked: Option<(bool, HashSet<ContentId>)>,
+        author_registered: Option<bool>,
+        hide_anonymous: bool,
+    ) -> anyhow::Result<(SmallVec<[(u32, Content); 5]>, usize)> {
+        let mut candidates = self
+            .content_repository
+            .finds(query)
+            .await
+            .map_err(content_err_fmt)?;
+
+        self.retain_matched(
+            &mut candidates,
+            bookmarked.as_ref(),
+            author_registered,
+            hide_anonymous,
+        )
+        .await?;
+
+        // nothing matched is not an error.
+        if candidates.is_empty() {
+            return Ok((SmallVec::new(), 0));
+        }
+
+        Paginator::new(self.page_size, page)
+            .paginate(candidates)
+            .map(|p| {
+                let paged = p
+                    .items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, c)| (i as u32, c))
+                    .collect::<SmallVec<[_; 5]>>();
+
+                (paged, p.total_items)
+            })
+    }
+
+    /// fetches contents next to the cursor (moving it) until a page is filled
+    /// with filtered ones, or nothing is left. in ascending order.
+    async fn cursor_page(
+        &self,
+        query: ContentQuery,
+        mut cursor: ContentCursor,
+        bookmarked: Option<(bool, HashSet<ContentId>)>,
+        author_registered: Option<bool>,
+        hide_anonymous: bool,
+    ) -> anyhow::Result<Vec<Content>> {
+        let limit = self.page_size;
+        let mut res = vec![];
+
+        loop {
+            let mut fetched = match cursor {
+                ContentCursor::After(id) =>
+                    self.content_repository
+                        .finds_after(query.clone(), id, limit as u64)
+                        .await,
+                ContentCursor::Before(id) =>
+                    self.content_repository
+                        .finds_before(query.clone(), id, limit as u64)
+                        .await,
+            }
+            .map_err(content_err_fmt)?;
+            let exhausted = fetched.len() < limit;
+
+            cursor = match cursor {
+                ContentCursor::After(_) => fetched.last().map(|c| ContentCursor::After(c.id)),
+                ContentCursor::Before(_) => fetched.first().map(|c| ContentCursor::Before(c.id)),
+            }
+            .unwrap_or(cursor);
+
+            self.retain_matched(
+                &mut fetched,
+                bookmarked.as_ref(),
+                author_registered,
+                hide_anonymous,
+            )
+            .await?;
+
+            match cursor {
+                ContentCursor::After(_) => res.append(&mut fetched),
+                ContentCursor::Before(_) => {
+                    fetched.append(&mut res);
+                    res = fetched;
+                },
+            }
+
+            if exhausted || res.len() >= limit {
+                break;
+            }
+        }
+
+        match cursor {
+            ContentCursor::After(_) => res.truncate(limit),
+            ContentCursor::Before(_) => drop(res.drain(..res.len().saturating_sub(limit))),
+        }
+
+        Ok(res)
+    }
+}
+#[async_trait]
+impl gets::Usecase for ContentGetsInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: gets::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let gets::Input {
+            query,
+            page,
+            cursor,
+            resolver,
+        } = data;
+
+        query
+            .check_ids(self.max_query_ids)
+            .map_err(DomainError::Invalid)?;
+
+        if cursor.is_some() {
+            if query.sort.is_some() {
+                bail!(DomainError::Invalid(
+                    "cannot use cursor with sort. (ordered by created date)".to_string()
+                ));
+            }
+            if page != 1 {
+                bail!(DomainError::Invalid(
+                    "cannot use cursor with page.".to_string()
+                ));
+            }
+        }
+
+        for id in pinned_not_liked_by_users(&query) {
+            let exists = self
+                .user_repository
+                .is_exists(id)
+                .await
+                .map_err(user_err_fmt)?;
+
+            if !exists {
+                bail!(DomainError::NotFound(format!("cannot find user: {}", id)));
+            }
+        }
+
+        // don't reveal posters of anonymous contents through the query.
+        let hide_anonymous = uses_posted(&query);
+
+        let bookmarked = match query.bookmarked {
+            Some(b) => Some((b, self.bookmarked_ids().await?)),
+            None => None,
+        };
+
+        let author_registered = query.author_registered;
+
+        let unfiltered = bookmarked.is_none() && author_registered.is_none() && !hide_anonymous;
+
+        let (mut contents, total) = match cursor {
+            Some(c) => {
+                let v = self
+                    .cursor_page(query, c, bookmarked, author_registered, hide_anonymous)
+                    .await?
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, c)| (i as u32, c))
+                    .collect::<SmallVec<[_; 5]>>();
+                let n = v.len();
+
+                (v, n)
+            },
+            // nothing is filtered out here, so let the repository page.
+            None if unfiltered => {
+                let paginator = Paginator::new(self.page_size, page);
+                let (v, total) = self
+                    .content_repository
+                    .finds_paged(query, paginator.offset() as u64, self.page_size as u64)
+                    .await
+                    .map_err(content_err_fmt)?;
+                // only for checking range of page. nothing matched is not an error.
+                if total > 0 {
+                    paginator.range(total as usize)?;
+                }
+
+                let v = v
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, c)| (i as u32, c))
+                    .collect::<SmallVec<[_; 5]>>();
+
+                (v, total as usize)
+            },
+            None =>
+                self.filtered_page(query, page, bookmarked, author_registered, hide_anonymous)
+                    .await?,
+        };
+
+        if let Some(r) = resolver {
+            resolve_authors(&mut contents, r.as_ref()).await;
+        }
+
+        gets::Output {
+            contents,
+            page,
+            total_items: total,
+            total_pages: Paginator::new(self.page_size, page).total_pages(total) as u32,
+            cursor,
+        }
+        .also_(|o| tracing::trace!("output - {:?}", o))
+        .let_(|r| self.pres.complete(r))
+        .await
+        .unwrap();
+
+        Ok(())
+    }
+}
+
+fn pinned_not_liked_by_users(query: &ContentQuery) -> HashSet<UserId> {
+    query
+        .any_of
+        .iter()
+        .flatten()
+        .flat_map(pinned_not_liked_by_users)
+        .chain(query.pinned_not_liked_by)
+        .collect()
+}
+
+fn uses_posted(query: &ContentQuery) -> bool {
+    query.posted.is_some()
+        || query
+            .any_of
+            .as_ref()
+            .map(|v| v.iter().any(uses_posted))
+            .unwrap_or(false)
+}
+
+/// refreshes names of authors, looking up each user only once.
+/// keeps stored names if cannot resolve.
+async fn resolve_authors(
+    contents: &mut SmallVec<[(u32, Content); 5]>,
+    resolver: &(dyn AuthorResolver + Sync + Send),
+) {
+    let mut resolved = HashMap::new();
+
+    for (_, Content { author, .. }) in contents.iter_mut() {
+        let (id, name, nick) = match author {
+            Author::User { id, name, nick } => (*id, name, nick),
+            Author::Virtual(_) => continue,
+        };
+
+        if !resolved.contains_key(&id) {
+            resolved.insert(id, resolver.resolve(id).await);
+        }
+
+        if let Some((n, k)) = &resolved[&id] {
+            *name = n.clone();
+            *nick = k.clone();
+        }
+    }
+}
+
 pub struct ContentEditInteractor {
     pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub max_content_len: usize,
     pub pres: Arc<dyn ContentEditPresenter + Sync + Send>,
 }
 #[async_trait]
@@ -164,6 +732,29 @@ impl edit::Usecase for ContentEditInteractor {
             mutation,
         } = data;
 
+        if let Some(t) = &mutation.title {
+            validate_title(t.as_deref())?;
+        }
+
+        // results of others are known only after applying to current one.
+        match &mutation.content {
+            Some(ContentContentMutation::Complete(s)) =>
+                validate_content(normalize_content(s).as_str(), self.max_content_len)?,
+            Some(m) => {
+                let current = self
+                    .content_repository
+                    .find(content_id)
+                    .await
+                    .map_err(content_err_fmt)?;
+
+                validate_content(
+                    m.clone().apply(current.content.as_str()).as_str(),
+                    self.max_content_len,
+                )?;
+            },
+            None => (),
+        }
+
         self.content_repository
             .update(content_id, mutation)
             .await
@@ -178,6 +769,140 @@ impl edit::Usecase for ContentEditInteractor {
     }
 }
 
+pub struct ContentRenameVirtualInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentRenameVirtualPresenter + Sync + Send>,
+}
+#[async_trait]
+impl rename_virtual::Usecase for ContentRenameVirtualInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: rename_virtual::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let rename_virtual::Input { from, to } = data;
+
+        if to.is_empty() {
+            bail!(DomainError::Invalid(
+                "(virtual)username cannot be empty.".to_string()
+            ));
+        }
+
+        self.content_repository
+            .rename_virtual(from.clone(), to.clone())
+            .await
+            .map_err(content_err_fmt)?
+            .let_(|count| rename_virtual::Output { from, to, count })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ContentReassignPostedInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentReassignPostedPresenter + Sync + Send>,
+}
+#[async_trait]
+impl reassign_posted::Usecase for ContentReassignPostedInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: reassign_posted::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let reassign_posted::Input {
+            content_id,
+            new_user_id,
+            resolver,
+        } = data;
+
+        let user_is_exists = self
+            .user_repository
+            .is_exists(new_user_id)
+            .await
+            .map_err(user_err_fmt)?;
+
+        if !user_is_exists {
+            bail!(DomainError::NotFound(format!(
+                "cannot find user: {}",
+                new_user_id
+            )));
+        }
+
+        let (name, nick) = match resolver.resolve(new_user_id).await {
+            Some(r) => r,
+            None => bail!(DomainError::NotFound(format!(
+                "cannot find discord user: {}",
+                new_user_id
+            ))),
+        };
+
+        let Content { posted: old, .. } = self
+            .content_repository
+            .find(content_id)
+            .await
+            .map_err(content_err_fmt)?;
+
+        let posted = Posted {
+            id: new_user_id,
+            name,
+            nick,
+        };
+
+        self.content_repository
+            .reassign_posted(content_id, posted)
+            .await
+            .map_err(content_err_fmt)?
+            .let_(|content| reassign_posted::Output { content, old })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ContentPreviewSedInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentPreviewSedPresenter + Sync + Send>,
+}
+#[async_trait]
+impl preview_sed::Usecase for ContentPreviewSedInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: preview_sed::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let preview_sed::Input {
+            content_id,
+            capture,
+            replace,
+        } = data;
+
+        let content = self
+            .content_repository
+            .find(content_id)
+            .await
+            .map_err(content_err_fmt)?;
+
+        if !capture.is_match(content.content.as_str()) {
+            bail!(DomainError::Invalid("capture matched nothing.".to_string()));
+        }
+
+        ContentContentMutation::Sed { capture, replace }
+            .apply(content.content.as_str())
+            .let_(|previewed| preview_sed::Output { content, previewed })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
 pub struct ContentWithdrawInteractor {
     pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
     pub pres: Arc<dyn ContentWithdrawPresenter + Sync + Send>,
@@ -188,10 +913,13 @@ impl withdraw::Usecase for ContentWithdrawInteractor {
     async fn handle(&self, data: withdraw::Input) -> anyhow::Result<()> {
         tracing::trace!("input - {:?}", data);
 
-        let withdraw::Input { content_id } = data;
+        let withdraw::Input {
+            content_id,
+            archived_at,
+        } = data;
 
         self.content_repository
-            .delete(content_id)
+            .archive(content_id, archived_at)
             .await
             .map_err(content_err_fmt)?
             .let_(|content| withdraw::Output { content })
@@ -204,8 +932,101 @@ impl withdraw::Usecase for ContentWithdrawInteractor {
     }
 }
 
+/// not atomic across repositories. `from` is archived last, so that it is
+/// kept on failure and can be merged again.
+pub struct ContentMergeInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentMergePresenter + Sync + Send>,
+}
+#[async_trait]
+impl merge::Usecase for ContentMergeInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: merge::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let merge::Input {
+            from,
+            into,
+            archived_at,
+        } = data;
+
+        if from == into {
+            bail!(DomainError::Invalid(
+                "cannot merge content into itself.".to_string()
+            ));
+        }
+
+        // withdrawn ones are not found too.
+        for id in [from, into] {
+            self.content_repository
+                .find(id)
+                .await
+                .map_err(content_err_fmt)?;
+        }
+
+        let content = self
+            .content_repository
+            .merge_reactions(from, into)
+            .await
+            .map_err(content_err_fmt)?;
+
+        let bookmarks = self
+            .user_repository
+            .replace_bookmark(from, into)
+            .await
+            .map_err(user_err_fmt)?;
+
+        let from = self
+            .content_repository
+            .archive(from, archived_at)
+            .await
+            .map_err(content_err_fmt)?;
+
+        merge::Output {
+            content,
+            from,
+            bookmarks,
+        }
+        .also_(|o| tracing::trace!("output - {:?}", o))
+        .let_(|r| self.pres.complete(r))
+        .await
+        .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ContentRestoreInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentRestorePresenter + Sync + Send>,
+}
+#[async_trait]
+impl restore::Usecase for ContentRestoreInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: restore::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let restore::Input { content_id } = data;
+
+        self.content_repository
+            .restore(content_id)
+            .await
+            .map_err(content_err_fmt)?
+            .let_(|content| restore::Output { content })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
 pub struct ContentLikeGetInteractor {
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
     pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub page_size: usize,
     pub pres: Arc<dyn ContentLikeGetPresenter + Sync + Send>,
 }
 #[async_trait]
@@ -214,23 +1035,71 @@ impl get_like::Usecase for ContentLikeGetInteractor {
     async fn handle(&self, data: get_like::Input) -> anyhow::Result<()> {
         tracing::trace!("input - {:?}", data);
 
-        let get_like::Input { content_id, page } = data;
+        let get_like::Input {
+            content_id,
+            page,
+            detailed,
+        } = data;
+
+        let paginator = Paginator::new(self.page_size, page);
+        let (v, total) = self
+            .content_repository
+            .liked_page(content_id, paginator.offset() as u64, self.page_size as u64)
+            .await
+            .map_err(content_err_fmt)?;
+        paginator.range(total as usize)?;
+
+        // resolves the page at once, instead of one by one.
+        let users = match detailed {
+            true => self
+                .user_repository
+                .find_many(v.as_slice())
+                .await
+                .map_err(user_err_fmt)?
+                .drain(..)
+                .map(|u| (u.id, u))
+                .collect::<HashMap<_, _>>()
+                .let_(Some),
+            false => None,
+        };
+
+        v.into_iter()
+            .enumerate()
+            .map(|(idx, id)| (idx as u32, id))
+            .collect::<SmallVec<[_; 20]>>()
+            .let_(|like| get_like::Output {
+                like,
+                users,
+                page,
+                total_items: total as usize,
+                total_pages: paginator.total_pages(total as usize) as u32,
+            })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ContentLikeCountInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentLikeCountPresenter + Sync + Send>,
+}
+#[async_trait]
+impl count_like::Usecase for ContentLikeCountInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: count_like::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let count_like::Input { content_id } = data;
 
         self.content_repository
-            .get_liked(content_id)
+            .count_liked(content_id)
             .await
             .map_err(content_err_fmt)?
-            .drain()
-            .collect::<Vec<_>>()
-            .let_(|mut v| {
-                calc_paging(0..v.len(), 20, page as usize).map(|lim| {
-                    v.drain(lim)
-                        .enumerate()
-                        .map(|(idx, id)| (idx as u32, id))
-                        .collect::<SmallVec<[_; 20]>>()
-                })
-            })?
-            .let_(|like| get_like::Output { like, page })
+            .let_(|count| count_like::Output { content_id, count })
             .also_(|o| tracing::trace!("output - {:?}", o))
             .let_(|r| self.pres.complete(r))
             .await
@@ -262,7 +1131,7 @@ impl like::Usecase for ContentLikeInteractor {
             .map_err(content_err_fmt)?;
 
         if !can_insert {
-            bail!("already liked.");
+            bail!(DomainError::AlreadyExists("already liked.".to_string()));
         }
 
         self.content_repository
@@ -304,7 +1173,7 @@ impl unlike::Usecase for ContentUnlikeInteractor {
             .map_err(content_err_fmt)?;
 
         if !can_insert {
-            bail!("didn't liked.")
+            bail!(DomainError::NotFound("didn't liked.".to_string()))
         }
 
         self.content_repository
@@ -324,8 +1193,101 @@ impl unlike::Usecase for ContentUnlikeInteractor {
     }
 }
 
+pub struct ContentLikeManyInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentLikeManyPresenter + Sync + Send>,
+}
+#[async_trait]
+impl like_many::Usecase for ContentLikeManyInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: like_many::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let like_many::Input {
+            content_ids,
+            user_id,
+        } = data;
+
+        let res = self
+            .content_repository
+            .insert_liked_many(content_ids.clone(), user_id)
+            .await
+            .map_err(content_err_fmt)?;
+
+        let mut liked = vec![];
+        let mut already = vec![];
+        let mut not_found = vec![];
+        for (id, r) in content_ids.into_iter().zip(res) {
+            match r {
+                Some(true) => liked.push(id),
+                Some(false) => already.push(id),
+                None => not_found.push(id),
+            }
+        }
+
+        like_many::Output {
+            liked,
+            already,
+            not_found,
+        }
+        .also_(|o| tracing::trace!("output - {:?}", o))
+        .let_(|r| self.pres.complete(r))
+        .await
+        .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ContentUnlikeManyInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentUnlikeManyPresenter + Sync + Send>,
+}
+#[async_trait]
+impl unlike_many::Usecase for ContentUnlikeManyInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: unlike_many::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let unlike_many::Input {
+            content_ids,
+            user_id,
+        } = data;
+
+        let res = self
+            .content_repository
+            .delete_liked_many(content_ids.clone(), user_id)
+            .await
+            .map_err(content_err_fmt)?;
+
+        let mut unliked = vec![];
+        let mut not_liked = vec![];
+        let mut not_found = vec![];
+        for (id, r) in content_ids.into_iter().zip(res) {
+            match r {
+                Some(true) => unliked.push(id),
+                Some(false) => not_liked.push(id),
+                None => not_found.push(id),
+            }
+        }
+
+        unlike_many::Output {
+            unliked,
+            not_liked,
+            not_found,
+        }
+        .also_(|o| tracing::trace!("output - {:?}", o))
+        .let_(|r| self.pres.complete(r))
+        .await
+        .unwrap();
+
+        Ok(())
+    }
+}
+
 pub struct ContentPinGetInteractor {
     pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub page_size: usize,
     pub pres: Arc<dyn ContentPinGetPresenter + Sync + Send>,
 }
 #[async_trait]
@@ -336,21 +1298,50 @@ impl get_pin::Usecase for ContentPinGetInteractor {
 
         let get_pin::Input { content_id, page } = data;
 
+        let paginator = Paginator::new(self.page_size, page);
+        let (v, total) = self
+            .content_repository
+            .pinned_page(content_id, paginator.offset() as u64, self.page_size as u64)
+            .await
+            .map_err(content_err_fmt)?;
+        paginator.range(total as usize)?;
+
+        v.into_iter()
+            .enumerate()
+            .map(|(idx, id)| (idx as u32, id))
+            .collect::<SmallVec<[_; 20]>>()
+            .let_(|pin| get_pin::Output {
+                pin,
+                page,
+                total_items: total as usize,
+                total_pages: paginator.total_pages(total as usize) as u32,
+            })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ContentPinCountInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentPinCountPresenter + Sync + Send>,
+}
+#[async_trait]
+impl count_pin::Usecase for ContentPinCountInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: count_pin::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let count_pin::Input { content_id } = data;
+
         self.content_repository
-            .get_pinned(content_id)
+            .count_pinned(content_id)
             .await
             .map_err(content_err_fmt)?
-            .drain()
-            .collect::<Vec<_>>()
-            .let_(|mut v| {
-                calc_paging(0..v.len(), 20, page as usize).map(move |lim| {
-                    v.drain(lim)
-                        .enumerate()
-                        .map(|(idx, id)| (idx as u32, id))
-                        .collect::<SmallVec<[_; 20]>>()
-                })
-            })?
-            .let_(|pin| get_pin::Output { pin, page })
+            .let_(|count| count_pin::Output { content_id, count })
             .also_(|o| tracing::trace!("output - {:?}", o))
             .let_(|r| self.pres.complete(r))
             .await
@@ -382,7 +1373,7 @@ impl pin::Usecase for ContentPinInteractor {
             .map_err(content_err_fmt)?;
 
         if !can_insert {
-            bail!("already pinned.");
+            bail!(DomainError::AlreadyExists("already pinned.".to_string()));
         }
 
         self.content_repository
@@ -424,7 +1415,7 @@ impl unpin::Usecase for ContentUnpinInteractor {
             .map_err(content_err_fmt)?;
 
         if !can_insert {
-            bail!("didn't pinned.");
+            bail!(DomainError::NotFound("didn't pinned.".to_string()));
         }
 
         self.content_repository
@@ -443,3 +1434,126 @@ impl unpin::Usecase for ContentUnpinInteractor {
         Ok(())
     }
 }
+
+pub struct ContentTagInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentTagPresenter + Sync + Send>,
+}
+#[async_trait]
+impl tag::Usecase for ContentTagInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: tag::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let tag::Input { content_id, tag } = data;
+
+        validate_tag(tag.as_str())?;
+
+        let can_insert = self
+            .content_repository
+            .insert_tag(content_id, tag.clone())
+            .await
+            .map_err(content_err_fmt)?;
+
+        if !can_insert {
+            bail!(DomainError::AlreadyExists("already tagged.".to_string()));
+        }
+
+        self.content_repository
+            .find(content_id)
+            .await
+            .map_err(content_err_fmt)?
+            .let_(|content| tag::Output { content, tag })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ContentUntagInteractor {
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentUntagPresenter + Sync + Send>,
+}
+#[async_trait]
+impl untag::Usecase for ContentUntagInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: untag::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let untag::Input { content_id, tag } = data;
+
+        let can_delete = self
+            .content_repository
+            .delete_tag(content_id, tag.clone())
+            .await
+            .map_err(content_err_fmt)?;
+
+        if !can_delete {
+            bail!(DomainError::NotFound("didn't tagged.".to_string()));
+        }
+
+        self.content_repository
+            .find(content_id)
+            .await
+            .map_err(content_err_fmt)?
+            .let_(|content| untag::Output { content, tag })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ContentStatsInteractor {
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn ContentStatsPresenter + Sync + Send>,
+}
+#[async_trait]
+impl stats::Usecase for ContentStatsInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: stats::Input) -> anyhow::Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let stats::Input { top } = data;
+
+        let contents = self
+            .content_repository
+            .count(ContentQuery::default())
+            .await
+            .map_err(content_err_fmt)?;
+        let users = self
+            .user_repository
+            .count(UserQuery::default())
+            .await
+            .map_err(user_err_fmt)?;
+        let top_liked = self
+            .content_repository
+            .top_liked(top)
+            .await
+            .map_err(content_err_fmt)?;
+        let top_pinned = self
+            .content_repository
+            .top_pinned(top)
+            .await
+            .map_err(content_err_fmt)?;
+
+        stats::Output {
+            contents,
+            users,
+            top_liked,
+            top_pinned,
+        }
+        .also_(|o| tracing::trace!("output - {:?}", o))
+        .let_(|r| self.pres.complete(r))
+        .await
+        .unwrap();
+
+        Ok(())
+    }
+}
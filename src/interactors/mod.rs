@@ -3,47 +3,143 @@ pub mod user;
 
 use anyhow::{anyhow, bail, Error, Result};
 
+use crate::errors::DomainError;
 use crate::repositories::RepositoryError;
-use crate::utils::{convert_range_display, ConvertRange};
 
 fn user_err_fmt(e: RepositoryError) -> Error {
     match e {
-        RepositoryError::NotFound => anyhow!("cannot find user. not registered?"),
-        e => anyhow!("repository error: {}", e),
+        RepositoryError::NotFound =>
+            DomainError::NotFound("cannot find user. not registered?".to_string()).into(),
+        e => DomainError::Internal(anyhow!("repository error: {}", e)).into(),
     }
 }
 
 fn content_err_fmt(e: RepositoryError) -> Error {
     match e {
-        RepositoryError::NotFound => anyhow!("cannot find content."),
-        e => anyhow!("repository error: {}", e),
+        RepositoryError::NotFound =>
+            DomainError::NotFound("cannot find content.".to_string()).into(),
+        RepositoryError::Conflict =>
+            DomainError::Conflict("content was modified, please retry.".to_string()).into(),
+        e => DomainError::Internal(anyhow!("repository error: {}", e)).into(),
     }
 }
 
-fn calc_paging(
-    full: impl ConvertRange<usize> + Clone,
-    items: usize,
-    page: usize,
-) -> Result<impl ConvertRange<usize>> {
-    let lim = (items * (page - 1))..(items + items * (page - 1));
-
-    if !full.contains(&lim.start) {
-        bail!(
-            "out of range ({} !< {})",
-            convert_range_display(full),
-            convert_range_display(lim)
-        );
+/// splits items into pages. `page` starts from 1.
+#[derive(Debug, Clone, Copy)]
+struct Paginator {
+    items_per_page: usize,
+    page: u32,
+}
+
+#[derive(Debug)]
+struct Page<T> {
+    items: Vec<T>,
+    page: u32,
+    total_pages: usize,
+    total_items: usize,
+}
+
+impl Paginator {
+    /// `items_per_page` of 0 is treated as 1.
+    fn new(items_per_page: usize, page: u32) -> Self {
+        Self {
+            items_per_page: items_per_page.max(1),
+            page,
+        }
+    }
+
+    /// number of items before the page.
+    fn offset(self) -> usize { self.items_per_page * (self.page as usize).saturating_sub(1) }
+
+    fn total_pages(self, total_items: usize) -> usize {
+        (total_items + self.items_per_page - 1) / self.items_per_page
     }
 
-    let r: (::core::ops::Bound<usize>, ::core::ops::Bound<usize>) = if !full.contains(&lim.end) {
-        let (start_bo, _) = full.to_turple();
-        match start_bo {
-            ::core::ops::Bound::Included(n) | ::core::ops::Bound::Excluded(n) => (n..).to_turple(),
-            ::core::ops::Bound::Unbounded => (..).to_turple(),
+    /// range of items in the page. the last page may be partial, and pages
+    /// after it (or any page of no items) are out of range.
+    fn range(self, total_items: usize) -> Result<::core::ops::Range<usize>> {
+        let total_pages = self.total_pages(total_items);
+
+        // tells the last page, so that it can be retried with valid one.
+        if self.page == 0 || self.page as usize > total_pages {
+            bail!(DomainError::Invalid(match total_pages {
+                0 => format!("page {} out of range (no items)", self.page),
+                n => format!("page {} out of range (max {})", self.page, n),
+            }));
         }
-    } else {
-        lim.to_turple()
-    };
 
-    Ok(r)
+        let start = self.offset();
+        Ok(start..(start + self.items_per_page).min(total_items))
+    }
+
+    fn paginate<T>(self, mut items: Vec<T>) -> Result<Page<T>> {
+        let total_items = items.len();
+        let range = self.range(total_items)?;
+
+        let page = Page {
+            items: items.drain(range).collect(),
+            page: self.page,
+            total_pages: self.total_pages(total_items),
+            total_items,
+        };
+        tracing::trace!(
+            "paged - {} of {} ({} items)",
+            page.page,
+            page.total_pages,
+            page.total_items
+        );
+
+        Ok(page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_pages_rounds_up() {
+        assert_eq!(Paginator::new(5, 1).total_pages(0), 0);
+        assert_eq!(Paginator::new(5, 1).total_pages(1), 1);
+        assert_eq!(Paginator::new(5, 1).total_pages(5), 1);
+        assert_eq!(Paginator::new(5, 1).total_pages(6), 2);
+    }
+
+    #[test]
+    fn range_of_exact_and_partial_pages() {
+        assert_eq!(Paginator::new(5, 1).range(10).unwrap(), 0..5);
+        assert_eq!(Paginator::new(5, 2).range(10).unwrap(), 5..10);
+        assert_eq!(Paginator::new(5, 2).range(7).unwrap(), 5..7);
+    }
+
+    #[test]
+    fn range_out_of_range() {
+        // page 0, after the last one, and no items.
+        assert!(Paginator::new(5, 0).range(10).is_err());
+        assert!(Paginator::new(5, 3).range(10).is_err());
+        assert!(Paginator::new(5, 1).range(0).is_err());
+    }
+
+    #[test]
+    fn paginate_partial_last_page() {
+        let page = Paginator::new(2, 3).paginate((0..5).collect()).unwrap();
+
+        assert_eq!(page.items, vec![4]);
+        assert_eq!(page.page, 3);
+        assert_eq!(page.total_pages, 3);
+        assert_eq!(page.total_items, 5);
+    }
+
+    #[test]
+    fn paginate_no_items() {
+        assert!(Paginator::new(2, 1).paginate(Vec::<u8>::new()).is_err());
+    }
+
+    #[test]
+    fn zero_items_per_page() {
+        let paginator = Paginator::new(0, 2);
+
+        assert_eq!(paginator.total_pages(3), 3);
+        assert_eq!(paginator.range(3).unwrap(), 1..2);
+    }
 }
@@ -1,19 +1,23 @@
 use alloc::sync::Arc;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::{bail, Result};
 use async_trait::async_trait;
 use smallvec::SmallVec;
 
 use super::*;
-use crate::entities::User;
+use crate::entities::{Posted, User};
 use crate::presenters::user::{
-    UserBookmarkGetPresenter, UserBookmarkPresenter, UserEditPresenter, UserGetPresenter,
-    UserGetsPresenter, UserRegisterPresenter, UserUnbookmarkPresenter, UserUnregisterPresenter,
+    UserBookmarkContentsPresenter, UserBookmarkCountPresenter, UserBookmarkExportPresenter,
+    UserBookmarkGetPresenter, UserBookmarkPresenter, UserBookmarkTogglePresenter,
+    UserEditPresenter, UserGetPresenter, UserGetsPresenter, UserRegisterPresenter,
+    UserSimilarPresenter, UserStatusPresenter, UserUnbookmarkPresenter, UserUnregisterPresenter,
 };
-use crate::repositories::UserRepository;
+use crate::repositories::{ContentRepository, UserRepository};
+use crate::usecases::content::{ContentQuery, PostedQuery};
 use crate::usecases::user::{
-    bookmark, edit, get, get_bookmark, gets, register, unbookmark, unregister,
+    bookmark, count_bookmark, edit, export_bookmark, get, get_bookmark, get_bookmark_contents,
+    gets, register, similar, status, toggle_bookmark, unbookmark, unregister, PostedContents,
 };
 use crate::utils::{AlsoChain, LetChain};
 
@@ -34,12 +38,17 @@ impl register::Usecase for UserRegisterInteractor {
             admin: false,
             sub_admin: false,
             bookmark: HashSet::new(),
+            posted_count: None,
+            granted_by: None,
+            granted_at: None,
         };
 
         let can_insert = self.user_repository.insert(new_user.clone()).await?;
 
         if !can_insert {
-            bail!("already registered.");
+            bail!(DomainError::AlreadyExists(
+                "already registered.".to_string()
+            ));
         }
 
         register::Output { user: new_user }
@@ -52,8 +61,38 @@ impl register::Usecase for UserRegisterInteractor {
     }
 }
 
+pub struct UserStatusInteractor {
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub pres: Arc<dyn UserStatusPresenter + Sync + Send>,
+}
+#[async_trait]
+impl status::Usecase for UserStatusInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: status::Input) -> Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let status::Input { user_id } = data;
+
+        self.user_repository
+            .is_exists(user_id)
+            .await
+            .map_err(user_err_fmt)?
+            .let_(|registered| status::Output {
+                user_id,
+                registered,
+            })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
 pub struct UserGetInteractor {
     pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
     pub pres: Arc<dyn UserGetPresenter + Sync + Send>,
 }
 #[async_trait]
@@ -62,13 +101,45 @@ impl get::Usecase for UserGetInteractor {
     async fn handle(&self, data: get::Input) -> Result<()> {
         tracing::trace!("input - {:?}", data);
 
-        let get::Input { user_id } = data;
+        let get::Input { user_id, requester } = data;
 
-        self.user_repository
+        let mut user = self
+            .user_repository
             .find(user_id)
             .await
-            .map_err(user_err_fmt)?
-            .let_(|user| get::Output { user })
+            .map_err(user_err_fmt)?;
+
+        // don't reveal posters of anonymous contents to others.
+        let reveal_anonymous = requester == user_id
+            || self
+                .user_repository
+                .find(requester)
+                .await
+                .map(|u| u.admin)
+                .unwrap_or(false);
+
+        let query = ContentQuery {
+            posted: Some(PostedQuery::UserId(user_id)),
+            ..Default::default()
+        };
+        user.posted_count = match reveal_anonymous {
+            true => self
+                .content_repository
+                .count(query)
+                .await
+                .map_err(content_err_fmt)?,
+            false => self
+                .content_repository
+                .finds(query)
+                .await
+                .map_err(content_err_fmt)?
+                .iter()
+                .filter(|c| !c.anonymous)
+                .count() as u64,
+        }
+        .let_(Some);
+
+        get::Output { user }
             .also_(|o| tracing::trace!("output - {:?}", o))
             .let_(|r| self.pres.complete(r))
             .await
@@ -80,6 +151,9 @@ impl get::Usecase for UserGetInteractor {
 
 pub struct UserGetsInteractor {
     pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    /// max number of ids in `bookmark` of query.
+    pub max_query_ids: usize,
+    pub page_size: usize,
     pub pres: Arc<dyn UserGetsPresenter + Sync + Send>,
 }
 #[async_trait]
@@ -90,19 +164,71 @@ impl gets::Usecase for UserGetsInteractor {
 
         let gets::Input { query, page } = data;
 
-        self.user_repository
+        query
+            .check_ids(self.max_query_ids)
+            .map_err(DomainError::Invalid)?;
+
+        let users = self
+            .user_repository
             .finds(query)
             .await
+            .map_err(user_err_fmt)?;
+
+        // nothing matched is not an error, so shown as empty page.
+        let p = match users.is_empty() {
+            true => Page {
+                items: vec![],
+                page,
+                total_pages: 0,
+                total_items: 0,
+            },
+            false => Paginator::new(self.page_size, page).paginate(users)?,
+        };
+
+        gets::Output {
+            users: p
+                .items
+                .into_iter()
+                .enumerate()
+                .map(|(i, u)| (i as u32, u))
+                .collect(),
+            page,
+            total_items: p.total_items,
+            total_pages: p.total_pages as u32,
+        }
+        .also_(|o| tracing::trace!("output - {:?}", o))
+        .let_(|r| self.pres.complete(r))
+        .await
+        .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct UserSimilarInteractor {
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub page_size: usize,
+    pub pres: Arc<dyn UserSimilarPresenter + Sync + Send>,
+}
+#[async_trait]
+impl similar::Usecase for UserSimilarInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: similar::Input) -> Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let similar::Input { user_id, page } = data;
+
+        self.user_repository
+            .similar(user_id)
+            .await
             .map_err(user_err_fmt)?
-            .let_(|mut v| {
-                calc_paging(0..v.len(), 5, page as usize).map(move |lim| {
-                    v.drain(lim)
-                        .enumerate()
-                        .map(|(i, u)| (i as u32, u))
-                        .collect::<SmallVec<[_; 5]>>()
-                })
-            })?
-            .let_(|users| gets::Output { users, page })
+            .let_(|v| Paginator::new(self.page_size, page).paginate(v))?
+            .items
+            .into_iter()
+            .enumerate()
+            .map(|(i, (u, n))| (i as u32, u, n))
+            .collect::<SmallVec<[_; 5]>>()
+            .let_(|users| similar::Output { users, page })
             .also_(|o| tracing::trace!("output - {:?}", o))
             .let_(|r| self.pres.complete(r))
             .await
@@ -122,7 +248,26 @@ impl edit::Usecase for UserEditInteractor {
     async fn handle(&self, data: edit::Input) -> Result<()> {
         tracing::trace!("input - {:?}", data);
 
-        let edit::Input { user_id, mutation } = data;
+        let edit::Input {
+            user_id,
+            mut mutation,
+            executor_id,
+            executed_at,
+        } = data;
+
+        let current = self
+            .user_repository
+            .find(user_id)
+            .await
+            .map_err(user_err_fmt)?;
+
+        // records only escalation, not revocation or no-op.
+        let granted = (mutation.admin == Some(true) && !current.admin)
+            || (mutation.sub_admin == Some(true) && !current.sub_admin);
+        if granted {
+            mutation.granted_by = Some(executor_id);
+            mutation.granted_at = Some(executed_at);
+        }
 
         self.user_repository
             .update(user_id, mutation)
@@ -138,8 +283,11 @@ impl edit::Usecase for UserEditInteractor {
     }
 }
 
+/// contents posted by the user are changed first (at once), then the user is
+/// deleted. so if deleting fails, they stay changed; retrying is harmless.
 pub struct UserUnregisterInteractor {
     pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
     pub pres: Arc<dyn UserUnregisterPresenter + Sync + Send>,
 }
 #[async_trait]
@@ -148,13 +296,66 @@ impl unregister::Usecase for UserUnregisterInteractor {
     async fn handle(&self, data: unregister::Input) -> Result<()> {
         tracing::trace!("input - {:?}", data);
 
-        let unregister::Input { user_id } = data;
+        let unregister::Input { user_id, posted } = data;
+
+        if !self
+            .user_repository
+            .is_exists(user_id)
+            .await
+            .map_err(user_err_fmt)?
+        {
+            bail!(DomainError::NotFound(format!(
+                "cannot find user: {}",
+                user_id
+            )));
+        }
+
+        let posted_changed = match posted {
+            PostedContents::Keep => 0,
+            PostedContents::Reassign { to, resolver } => {
+                if to == user_id {
+                    bail!(DomainError::Invalid(
+                        "cannot reassign to unregistering user.".to_string()
+                    ));
+                }
+
+                let user_is_exists = self
+                    .user_repository
+                    .is_exists(to)
+                    .await
+                    .map_err(user_err_fmt)?;
+                if !user_is_exists {
+                    bail!(DomainError::NotFound(format!("cannot find user: {}", to)));
+                }
+
+                let (name, nick) = match resolver.resolve(to).await {
+                    Some(r) => r,
+                    None => bail!(DomainError::NotFound(format!(
+                        "cannot find discord user: {}",
+                        to
+                    ))),
+                };
+
+                self.content_repository
+                    .reassign_posted_by(user_id, Posted { id: to, name, nick })
+                    .await
+                    .map_err(content_err_fmt)?
+            },
+            PostedContents::Withdraw { at } => self
+                .content_repository
+                .archive_posted_by(user_id, at)
+                .await
+                .map_err(content_err_fmt)?,
+        };
 
         self.user_repository
             .delete(user_id)
             .await
-            .map_err(content_err_fmt)?
-            .let_(|user| unregister::Output { user })
+            .map_err(user_err_fmt)?
+            .let_(|user| unregister::Output {
+                user,
+                posted_changed,
+            })
             .also_(|o| tracing::trace!("output - {:?}", o))
             .let_(|r| self.pres.complete(r))
             .await
@@ -166,6 +367,7 @@ impl unregister::Usecase for UserUnregisterInteractor {
 
 pub struct UserBookmarkGetInteractor {
     pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub page_size: usize,
     pub pres: Arc<dyn UserBookmarkGetPresenter + Sync + Send>,
 }
 #[async_trait]
@@ -180,17 +382,132 @@ impl get_bookmark::Usecase for UserBookmarkGetInteractor {
             .get_bookmark(user_id)
             .await
             .map_err(content_err_fmt)?
-            .drain()
-            .collect::<Vec<_>>()
-            .let_(|mut v| {
-                calc_paging(0..v.len(), 20, page as usize).map(move |lim| {
-                    v.drain(lim)
-                        .enumerate()
-                        .map(|(i, d)| (i as u32, d))
-                        .collect::<SmallVec<[_; 20]>>()
-                })
-            })?
-            .let_(|bookmark| get_bookmark::Output { bookmark, page })
+            .let_(|v| Paginator::new(self.page_size, page).paginate(v))?
+            .let_(|p| get_bookmark::Output {
+                bookmark: p
+                    .items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, d)| (i as u32, d))
+                    .collect(),
+                page,
+                total_items: p.total_items,
+                total_pages: p.total_pages as u32,
+            })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct UserBookmarkContentsInteractor {
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub page_size: usize,
+    pub pres: Arc<dyn UserBookmarkContentsPresenter + Sync + Send>,
+}
+#[async_trait]
+impl get_bookmark_contents::Usecase for UserBookmarkContentsInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: get_bookmark_contents::Input) -> Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let get_bookmark_contents::Input { user_id, page } = data;
+
+        let ids = self
+            .user_repository
+            .get_bookmark(user_id)
+            .await
+            .map_err(user_err_fmt)?;
+
+        let ids = Paginator::new(self.page_size, page).paginate(ids)?.items;
+
+        let mut found = self
+            .content_repository
+            .find_many(ids.as_slice())
+            .await
+            .map_err(content_err_fmt)?
+            .drain(..)
+            .map(|c| (c.id, c))
+            .collect::<HashMap<_, _>>();
+
+        ids.iter()
+            .enumerate()
+            .map(|(i, id)| (i as u32, *id, found.remove(id)))
+            .collect::<SmallVec<[_; 20]>>()
+            .let_(|contents| get_bookmark_contents::Output { contents, page })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct UserBookmarkCountInteractor {
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub pres: Arc<dyn UserBookmarkCountPresenter + Sync + Send>,
+}
+#[async_trait]
+impl count_bookmark::Usecase for UserBookmarkCountInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: count_bookmark::Input) -> Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let count_bookmark::Input { user_id } = data;
+
+        self.user_repository
+            .count_bookmark(user_id)
+            .await
+            .map_err(user_err_fmt)?
+            .let_(|count| count_bookmark::Output { user_id, count })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct UserBookmarkExportInteractor {
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub content_repository: Arc<dyn ContentRepository + Sync + Send>,
+    pub pres: Arc<dyn UserBookmarkExportPresenter + Sync + Send>,
+}
+#[async_trait]
+impl export_bookmark::Usecase for UserBookmarkExportInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: export_bookmark::Input) -> Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let export_bookmark::Input { user_id } = data;
+
+        let mut ids = self
+            .user_repository
+            .get_bookmark(user_id)
+            .await
+            .map_err(user_err_fmt)?;
+
+        let mut found = self
+            .content_repository
+            .find_many(ids.as_slice())
+            .await
+            .map_err(content_err_fmt)?
+            .drain(..)
+            .map(|c| (c.id, c))
+            .collect::<HashMap<_, _>>();
+
+        let contents = ids
+            .drain(..)
+            .map(|id| (id, found.remove(&id)))
+            .collect::<Vec<_>>();
+
+        export_bookmark::Output { user_id, contents }
             .also_(|o| tracing::trace!("output - {:?}", o))
             .let_(|r| self.pres.complete(r))
             .await
@@ -222,7 +539,9 @@ impl bookmark::Usecase for UserBookmarkInteractor {
             .map_err(user_err_fmt)?;
 
         if !can_insert {
-            bail!("already bookmarked.");
+            bail!(DomainError::AlreadyExists(
+                "already bookmarked.".to_string()
+            ));
         }
 
         self.user_repository
@@ -264,7 +583,7 @@ impl unbookmark::Usecase for UserUnbookmarkInteractor {
             .map_err(user_err_fmt)?;
 
         if !can_insert {
-            bail!("didn't bookmarked.");
+            bail!(DomainError::NotFound("didn't bookmarked.".to_string()));
         }
 
         self.user_repository
@@ -283,3 +602,42 @@ impl unbookmark::Usecase for UserUnbookmarkInteractor {
         Ok(())
     }
 }
+
+pub struct UserBookmarkToggleInteractor {
+    pub user_repository: Arc<dyn UserRepository + Sync + Send>,
+    pub pres: Arc<dyn UserBookmarkTogglePresenter + Sync + Send>,
+}
+#[async_trait]
+impl toggle_bookmark::Usecase for UserBookmarkToggleInteractor {
+    #[tracing::instrument(skip(self))]
+    async fn handle(&self, data: toggle_bookmark::Input) -> Result<()> {
+        tracing::trace!("input - {:?}", data);
+
+        let toggle_bookmark::Input {
+            user_id,
+            content_id,
+        } = data;
+
+        let bookmarked = self
+            .user_repository
+            .toggle_bookmark(user_id, content_id)
+            .await
+            .map_err(user_err_fmt)?;
+
+        self.user_repository
+            .find(user_id)
+            .await
+            .map_err(user_err_fmt)?
+            .let_(|user| toggle_bookmark::Output {
+                user,
+                id: content_id,
+                bookmarked,
+            })
+            .also_(|o| tracing::trace!("output - {:?}", o))
+            .let_(|r| self.pres.complete(r))
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
@@ -12,10 +12,16 @@ pub(crate) mod conductors;
 mod constructors;
 pub(crate) mod controllers;
 pub(crate) mod entities;
+pub(crate) mod errors;
 pub(crate) mod interactors;
+pub(crate) mod messages;
+pub(crate) mod notifications;
 pub(crate) mod presenters;
 pub(crate) mod repositories;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub(crate) mod usecases;
 pub(crate) mod utils;
 
 pub use constructors::*;
+pub use messages::Locale;
@@ -1,56 +1,103 @@
 use tracing_subscriber::EnvFilter;
 
-async fn async_main() {
-    let AppValues { token, flag } = get_values();
+async fn async_main(
+    AppValues {
+        token,
+        flag,
+        log_format,
+        worker_threads: _,
+        config,
+        export,
+    }: AppValues,
+) {
+    // before connecting, so that logs on it (e.g. migrations) are not lost.
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_thread_ids(true)
+        .with_thread_names(true);
+
+    match log_format {
+        LogFormat::Pretty => builder.pretty().init(),
+        LogFormat::Json => builder.json().init(),
+    }
+
+    if let Some(path) = export {
+        let res = match flag {
+            Flag::InMemory => ::icey_pudding::export_in_memory(path.as_str()).await,
+            Flag::Mongo { uri, name } =>
+                ::icey_pudding::export_mongo(uri, name, path.as_str()).await,
+        };
+
+        return match res {
+            Ok(count) => println!("exported {} contents to {}", count, path),
+            Err(e) => {
+                eprintln!("export error: {:?}", e);
+                ::std::process::exit(1)
+            },
+        };
+    }
+
+    let token = token.expect("error on: DISCORD_BOT_TOKEN");
 
     use serenity::model::gateway::GatewayIntents;
     let cb = ::serenity::client::ClientBuilder::new(
         token,
-        GatewayIntents::GUILD_MESSAGES | GatewayIntents::DIRECT_MESSAGES,
+        GatewayIntents::GUILD_MESSAGES
+            | GatewayIntents::DIRECT_MESSAGES
+            | GatewayIntents::GUILD_MESSAGE_REACTIONS
+            | GatewayIntents::DIRECT_MESSAGE_REACTIONS,
     );
 
     let cb = match flag {
-        Flag::InMemory => cb.event_handler(::icey_pudding::in_memory()),
-        Flag::Mongo { uri, name } =>
-            cb.event_handler(::icey_pudding::mongo(uri, name).await.expect("eh error")),
+        Flag::InMemory => cb.event_handler(::icey_pudding::in_memory(config)),
+        Flag::Mongo { uri, name } => cb.event_handler(
+            ::icey_pudding::mongo(uri, name, config)
+                .await
+                .expect("eh error"),
+        ),
     };
 
     let mut c = cb.await.expect("cannot build serenity client.");
 
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .with_thread_ids(true)
-        .with_thread_names(true)
-        .pretty()
-        .init();
-
     c.start_autosharded()
         .await
         .expect("serenity client returned.");
 }
 
 fn main() {
-    let rt = match tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .thread_name_fn(|| {
-            let num = unsafe { NUM };
-            unsafe { NUM += 1 }
-            format!("icey_pudding-worker-{}", num)
-        })
-        .build()
-    {
+    let values = get_values();
+
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    builder.enable_all().thread_name_fn(|| {
+        let num = unsafe { NUM };
+        unsafe { NUM += 1 }
+        format!("icey_pudding-worker-{}", num)
+    });
+
+    if let Some(n) = values.worker_threads {
+        builder.worker_threads(n);
+    }
+
+    let rt = match builder.build() {
         Ok(r) => r,
         Err(e) => return eprintln!("{}", e),
     };
 
-    rt.block_on(async_main())
+    rt.block_on(async_main(values))
 }
 
 static mut NUM: u32 = 0;
 
 struct AppValues {
-    token: String,
+    /// not required on export.
+    token: Option<String>,
     flag: Flag,
+    log_format: LogFormat,
+    worker_threads: Option<usize>,
+    config: ::icey_pudding::Config,
+    /// exports contents to the path (as NDJSON) and exits, instead of running
+    /// bot.
+    export: Option<String>,
 }
 
 enum Flag {
@@ -58,10 +105,20 @@ enum Flag {
     Mongo { uri: String, name: String },
 }
 
+enum LogFormat {
+    Pretty,
+    Json,
+}
+
 fn get_values() -> AppValues {
     use std::env::var;
 
-    let token = var("DISCORD_BOT_TOKEN").expect("error on: DISCORD_BOT_TOKEN");
+    let export = var("EXPORT_CONTENTS").ok();
+
+    let token = match export {
+        Some(_) => var("DISCORD_BOT_TOKEN").ok(),
+        None => Some(var("DISCORD_BOT_TOKEN").expect("error on: DISCORD_BOT_TOKEN")),
+    };
 
     let flag = match var("FLAG").expect("error on: FLAG").as_str() {
         "InMemory" => Flag::InMemory,
@@ -74,5 +131,190 @@ fn get_values() -> AppValues {
         v => panic!("unexpected value: {}", v),
     };
 
-    AppValues { token, flag }
+    let log_format = match var("LOG_FORMAT").as_deref() {
+        Ok("pretty") | Err(_) => LogFormat::Pretty,
+        Ok("json") => LogFormat::Json,
+        Ok(v) => panic!("unexpected value: {}", v),
+    };
+
+    // unset means auto-detect (= number of cpu cores).
+    let worker_threads =
+        var("WORKER_THREADS")
+            .ok()
+            .map(|v| match v.parse::<::core::num::NonZeroUsize>() {
+                Ok(n) => n.get(),
+                Err(_) => panic!("unexpected value: {}", v),
+            });
+
+    let mut config = ::icey_pudding::Config::default();
+
+    if let Ok(v) = var("LIST_CAP") {
+        config.list_cap = match v.parse::<::core::num::NonZeroUsize>() {
+            Ok(n) => n.get(),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    // unset means unlimited.
+    if let Ok(v) = var("MAX_CONTENTS_PER_USER") {
+        config.max_contents_per_user = match v.parse::<::core::num::NonZeroUsize>() {
+            Ok(n) => Some(n.get()),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    if let Ok(v) = var("MAX_CONTENT_LEN") {
+        config.max_content_len = match v.parse::<::core::num::NonZeroUsize>() {
+            Ok(n) => n.get(),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    // unset means no cooldown.
+    if let Ok(v) = var("POST_COOLDOWN_SECS") {
+        config.post_cooldown = match v.parse::<::core::num::NonZeroU64>() {
+            Ok(n) => Some(::core::time::Duration::from_secs(n.get())),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    // 0 executes destructive commands without confirmation.
+    if let Ok(v) = var("CONFIRM_TIMEOUT_SECS") {
+        config.confirm_timeout = match v.parse::<u64>() {
+            Ok(0) => None,
+            Ok(n) => Some(::core::time::Duration::from_secs(n)),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    // 0 disables warnings of slow queries.
+    if let Ok(v) = var("SLOW_QUERY_MS") {
+        config.slow_query = match v.parse::<u64>() {
+            Ok(0) => None,
+            Ok(n) => Some(::core::time::Duration::from_millis(n)),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    // 0 disables suggestion.
+    if let Ok(v) = var("SUGGEST_DISTANCE") {
+        config.suggest_distance = match v.parse::<usize>() {
+            Ok(n) => n,
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    if let Ok(v) = var("ICEY_PREFIX") {
+        if v.is_empty() || v.contains(char::is_whitespace) {
+            panic!("unexpected value: {}", v);
+        }
+
+        config.prefix = v;
+    }
+
+    // unset means unlimited.
+    if let Ok(v) = var("RATE_LIMIT_REQUESTS") {
+        let n = match v.parse::<::core::num::NonZeroU32>() {
+            Ok(n) => n.get(),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+        let window = match var("RATE_LIMIT_WINDOW_SECS").as_deref() {
+            Err(_) => 60,
+            Ok(v) => match v.parse::<::core::num::NonZeroU64>() {
+                Ok(n) => n.get(),
+                Err(_) => panic!("unexpected value: {}", v),
+            },
+        };
+
+        config.rate_limit = Some((n, ::core::time::Duration::from_secs(window)));
+    }
+
+    // unset means rejecting.
+    if let Ok(v) = var("ALLOW_UNKNOWN_AUTHOR") {
+        config.allow_unknown_author = match v.parse::<bool>() {
+            Ok(b) => b,
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    // unset means no notification.
+    if let Ok(v) = var("NOTIFY_URL") {
+        config.notify_url = Some(v);
+    }
+
+    // unset means allowing.
+    if let Ok(v) = var("REJECT_DUPLICATE_BODY") {
+        config.reject_duplicate_body = match v.parse::<bool>() {
+            Ok(b) => b,
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    // unset means rejecting.
+    if let Ok(v) = var("VIRTUAL_AUTHOR_FALLBACK") {
+        config.virtual_author_fallback = match v.parse::<bool>() {
+            Ok(b) => b,
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    if let Ok(v) = var("MONGO_CONNECT_ATTEMPTS") {
+        config.mongo_retry.0 = match v.parse::<::core::num::NonZeroU32>() {
+            Ok(n) => n.get(),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    if let Ok(v) = var("MONGO_CONNECT_DELAY_MS") {
+        config.mongo_retry.1 = match v.parse::<u64>() {
+            Ok(n) => ::core::time::Duration::from_millis(n),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    // "en" | "ja". unset means english.
+    if let Ok(v) = var("ICEY_LOCALE") {
+        config.locale = match v.parse() {
+            Ok(l) => l,
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    if let Ok(v) = var("ICEY_GETS_PAGE_SIZE") {
+        config.gets_page_size = match v.parse::<usize>() {
+            Ok(n @ 1..=::icey_pudding::MAX_PAGE_SIZE) => n,
+            _ => panic!("unexpected value: {}", v),
+        };
+    }
+
+    if let Ok(v) = var("ICEY_LIST_PAGE_SIZE") {
+        config.list_page_size = match v.parse::<usize>() {
+            Ok(n @ 1..=::icey_pudding::MAX_PAGE_SIZE) => n,
+            _ => panic!("unexpected value: {}", v),
+        };
+    }
+
+    if let Ok(v) = var("MAX_QUERY_IDS") {
+        config.max_query_ids = match v.parse::<::core::num::NonZeroUsize>() {
+            Ok(n) => n.get(),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    // unset means no caching.
+    if let Ok(v) = var("CONTENT_CACHE_SIZE") {
+        config.content_cache = match v.parse::<::core::num::NonZeroUsize>() {
+            Ok(n) => Some(n.get()),
+            Err(_) => panic!("unexpected value: {}", v),
+        };
+    }
+
+    AppValues {
+        token,
+        flag,
+        log_format,
+        worker_threads,
+        config,
+        export,
+    }
 }
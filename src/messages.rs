@@ -0,0 +1,195 @@
+//! user-facing strings, looked up by key. every locale has to cover all
+//! keys (matches are exhaustive), so adding a key needs each table updated.
+
+use core::str::FromStr;
+
+use anyhow::bail;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+impl FromStr for Locale {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let l = match s {
+            "en" => Self::En,
+            "ja" => Self::Ja,
+            s => bail!("unknown locale: {}. (en | ja)", s),
+        };
+
+        Ok(l)
+    }
+}
+
+impl Locale {
+    pub(crate) fn get(self, key: Msg) -> &'static str {
+        match self {
+            Self::En => en(key),
+            Self::Ja => ja(key),
+        }
+    }
+}
+
+/// keys of messages, mostly titles of embeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Msg {
+    ErrorOccurred,
+    Pong,
+    ShowingStats,
+    ShowingHelp,
+
+    RegisteredUser,
+    ShowingStatus,
+    ShowingUser,
+    ShowingUsers,
+    ShowingSimilarUsers,
+    UpdatedUser,
+    DeletedUser,
+    ShowingBookmark,
+    ShowingBookmarkedContent,
+    CountedBookmark,
+    Bookmarked,
+    Unbookmarked,
+
+    PostedContent,
+    ShowingContent,
+    ShowingContents,
+    EditHistory,
+    UpdatedContent,
+    RenamedVirtualAuthor,
+    ReassignedPosted,
+    PreviewOfEdit,
+    ArchivedContent,
+    RestoredContent,
+    MergedContent,
+    ShowingLike,
+    CountedLike,
+    Like,
+    Unlike,
+    ShowingPin,
+    CountedPin,
+    Pin,
+    Unpin,
+    Tag,
+    Untag,
+
+    SavedTemplate,
+    DeletedTemplate,
+    ShowingTemplates,
+    RevealedPoster,
+    UpdatedDefaultQuery,
+    UpdatedStickyContent,
+    ConfirmCommand,
+    CancelledCommand,
+}
+
+fn en(key: Msg) -> &'static str {
+    match key {
+        Msg::ErrorOccurred => "error occurred.\nplease send this message to administrator.",
+        Msg::Pong => "pong",
+        Msg::ShowingStats => "showing stats",
+        Msg::ShowingHelp => "help",
+
+        Msg::RegisteredUser => "registered user",
+        Msg::ShowingStatus => "showing status",
+        Msg::ShowingUser => "showing user",
+        Msg::ShowingUsers => "showing users",
+        Msg::ShowingSimilarUsers => "showing similar users",
+        Msg::UpdatedUser => "updated user",
+        Msg::DeletedUser => "deleted user",
+        Msg::ShowingBookmark => "showing bookmark",
+        Msg::ShowingBookmarkedContent => "showing bookmarked content",
+        Msg::CountedBookmark => "counted bookmark",
+        Msg::Bookmarked => "bookmarked",
+        Msg::Unbookmarked => "unbookmarked",
+
+        Msg::PostedContent => "posted content",
+        Msg::ShowingContent => "showing content",
+        Msg::ShowingContents => "showing contents.",
+        Msg::EditHistory => "edit history",
+        Msg::UpdatedContent => "updated content.",
+        Msg::RenamedVirtualAuthor => "renamed virtual author",
+        Msg::ReassignedPosted => "reassigned posted",
+        Msg::PreviewOfEdit => "preview of edit. (not saved)",
+        Msg::ArchivedContent => "archived content.",
+        Msg::RestoredContent => "restored content.",
+        Msg::MergedContent => "merged content.",
+        Msg::ShowingLike => "showing like",
+        Msg::CountedLike => "counted like",
+        Msg::Like => "like",
+        Msg::Unlike => "unlike",
+        Msg::ShowingPin => "showing pin",
+        Msg::CountedPin => "counted pin",
+        Msg::Pin => "pin",
+        Msg::Unpin => "unpin",
+        Msg::Tag => "tag",
+        Msg::Untag => "untag",
+
+        Msg::SavedTemplate => "saved template",
+        Msg::DeletedTemplate => "deleted template",
+        Msg::ShowingTemplates => "showing templates",
+        Msg::RevealedPoster => "revealed poster",
+        Msg::UpdatedDefaultQuery => "updated default query",
+        Msg::UpdatedStickyContent => "updated sticky content",
+        Msg::ConfirmCommand => "confirm command",
+        Msg::CancelledCommand => "cancelled command",
+    }
+}
+
+fn ja(key: Msg) -> &'static str {
+    match key {
+        Msg::ErrorOccurred => "エラーが発生しました。\nこのメッセージを管理者に送ってください。",
+        Msg::Pong => "pong",
+        Msg::ShowingStats => "統計",
+        Msg::ShowingHelp => "ヘルプ",
+
+        Msg::RegisteredUser => "ユーザーを登録しました",
+        Msg::ShowingStatus => "ステータス",
+        Msg::ShowingUser => "ユーザー",
+        Msg::ShowingUsers => "ユーザー一覧",
+        Msg::ShowingSimilarUsers => "似ているユーザー",
+        Msg::UpdatedUser => "ユーザーを更新しました",
+        Msg::DeletedUser => "ユーザーを削除しました",
+        Msg::ShowingBookmark => "ブックマーク一覧",
+        Msg::ShowingBookmarkedContent => "ブックマークしたコンテンツ",
+        Msg::CountedBookmark => "ブックマーク数",
+        Msg::Bookmarked => "ブックマークしました",
+        Msg::Unbookmarked => "ブックマークを解除しました",
+
+        Msg::PostedContent => "投稿されたコンテンツ",
+        Msg::ShowingContent => "コンテンツ",
+        Msg::ShowingContents => "コンテンツ一覧",
+        Msg::EditHistory => "編集履歴",
+        Msg::UpdatedContent => "コンテンツを更新しました",
+        Msg::RenamedVirtualAuthor => "仮想作者の名前を変更しました",
+        Msg::ReassignedPosted => "投稿者を変更しました",
+        Msg::PreviewOfEdit => "編集のプレビュー (未保存)",
+        Msg::ArchivedContent => "コンテンツをアーカイブしました",
+        Msg::RestoredContent => "コンテンツを復元しました",
+        Msg::MergedContent => "コンテンツを統合しました",
+        Msg::ShowingLike => "いいね一覧",
+        Msg::CountedLike => "いいね数",
+        Msg::Like => "いいね",
+        Msg::Unlike => "いいね取り消し",
+        Msg::ShowingPin => "ピン一覧",
+        Msg::CountedPin => "ピン数",
+        Msg::Pin => "ピン",
+        Msg::Unpin => "ピン解除",
+        Msg::Tag => "タグ追加",
+        Msg::Untag => "タグ削除",
+
+        Msg::SavedTemplate => "テンプレートを保存しました",
+        Msg::DeletedTemplate => "テンプレートを削除しました",
+        Msg::ShowingTemplates => "テンプレート一覧",
+        Msg::RevealedPoster => "投稿者を開示しました",
+        Msg::UpdatedDefaultQuery => "デフォルトクエリを更新しました",
+        Msg::UpdatedStickyContent => "固定コンテンツを更新しました",
+        Msg::ConfirmCommand => "コマンドの確認",
+        Msg::CancelledCommand => "コマンドを取り消しました",
+    }
+}
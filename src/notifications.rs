@@ -0,0 +1,86 @@
+//! outbound notifications of events, for mirroring them elsewhere.
+
+use alloc::sync::Arc;
+
+use serde_json::{json, Value};
+use tracing::Instrument;
+
+use crate::entities::{Author, Content};
+use crate::utils::date_to_string;
+
+pub trait NotificationSink {
+    /// called after content is posted (stored). must not block, so slow
+    /// deliveries should be done in background.
+    fn content_posted(&self, _content: &Content) {}
+}
+
+/// notifies nothing.
+pub struct NoopSink;
+impl NotificationSink for NoopSink {}
+
+/// posts events as json to `url`, without waiting for (or retrying on)
+/// response. failures are only logged.
+pub struct HttpSink {
+    client: ::reqwest::Client,
+    url: Arc<str>,
+}
+
+impl HttpSink {
+    pub fn new(url: impl Into<Arc<str>>) -> Self {
+        Self {
+            client: ::reqwest::Client::new(),
+            url: url.into(),
+        }
+    }
+
+    fn send(&self, body: Value) {
+        let req = self.client.post(&*self.url).json(&body);
+        let url = self.url.clone();
+
+        ::tokio::spawn(
+            async move {
+                match req.send().await.and_then(|r| r.error_for_status()) {
+                    Ok(_) => tracing::debug!("notified - url {}", url),
+                    Err(e) => tracing::warn!("notifying err - url {} | {:?}", url, e),
+                }
+            }
+            .instrument(tracing::trace_span!("notify")),
+        );
+    }
+}
+
+impl NotificationSink for HttpSink {
+    fn content_posted(&self, content: &Content) {
+        self.send(json!({
+            "event": "content_posted",
+            "content": content_to_json(content),
+        }));
+    }
+}
+
+// poster is omitted if anonymous, same as shown on discord.
+fn content_to_json(c: &Content) -> Value {
+    let author = match &c.author {
+        Author::User { id, name, nick } => json!({ "id": id, "name": name, "nick": nick }),
+        Author::Virtual(name) => json!({ "virtual": name }),
+    };
+    let posted = match c.anonymous {
+        true => Value::Null,
+        false => json!({ "id": c.posted.id, "name": c.posted.name, "nick": c.posted.nick }),
+    };
+
+    let mut tags = c.tags.iter().collect::<Vec<_>>();
+    tags.sort();
+
+    json!({
+        "id": c.id,
+        "short_id": c.short_id,
+        "title": c.title,
+        "source_url": c.source_url,
+        "content": c.content,
+        "author": author,
+        "posted": posted,
+        "tags": tags,
+        "created": date_to_string(c.created),
+    })
+}
@@ -2,7 +2,9 @@ use anyhow::Result;
 use async_trait::async_trait;
 
 use crate::usecases::content::{
-    edit, get, get_like, get_pin, gets, like, pin, post, unlike, unpin, withdraw,
+    count_like, count_pin, edit, get, get_like, get_pin, gets, history, like, like_many, merge,
+    pin, post, preview_sed, reassign_posted, rename_virtual, resolve, restore, stats, tag, unlike,
+    unlike_many, unpin, untag, withdraw,
 };
 
 #[async_trait]
@@ -15,6 +17,16 @@ pub trait ContentGetPresenter {
     async fn complete(&self, data: get::Output) -> Result<()>;
 }
 
+#[async_trait]
+pub trait ContentHistoryPresenter {
+    async fn complete(&self, data: history::Output) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ContentResolvePresenter {
+    async fn complete(&self, data: resolve::Output) -> Result<()>;
+}
+
 #[async_trait]
 pub trait ContentGetsPresenter {
     async fn complete(&self, data: gets::Output) -> Result<()>;
@@ -25,16 +37,61 @@ pub trait ContentEditPresenter {
     async fn complete(&self, data: edit::Output) -> Result<()>;
 }
 
+#[async_trait]
+pub trait ContentRenameVirtualPresenter {
+    async fn complete(&self, data: rename_virtual::Output) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ContentReassignPostedPresenter {
+    async fn complete(&self, data: reassign_posted::Output) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ContentPreviewSedPresenter {
+    async fn complete(&self, data: preview_sed::Output) -> Result<()>;
+}
+
 #[async_trait]
 pub trait ContentWithdrawPresenter {
     async fn complete(&self, data: withdraw::Output) -> Result<()>;
 }
 
+#[async_trait]
+pub trait ContentMergePresenter {
+    async fn complete(&self, data: merge::Output) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ContentRestorePresenter {
+    async fn complete(&self, data: restore::Output) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ContentTagPresenter {
+    async fn complete(&self, data: tag::Output) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ContentUntagPresenter {
+    async fn complete(&self, data: untag::Output) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ContentStatsPresenter {
+    async fn complete(&self, data: stats::Output) -> Result<()>;
+}
+
 #[async_trait]
 pub trait ContentLikeGetPresenter {
     async fn complete(&self, data: get_like::Output) -> Result<()>;
 }
 
+#[async_trait]
+pub trait ContentLikeCountPresenter {
+    async fn complete(&self, data: count_like::Output) -> Result<()>;
+}
+
 #[async_trait]
 pub trait ContentLikePresenter {
     async fn complete(&self, data: like::Output) -> Result<()>;
@@ -45,11 +102,26 @@ pub trait ContentUnlikePresenter {
     async fn complete(&self, data: unlike::Output) -> Result<()>;
 }
 
+#[async_trait]
+pub trait ContentLikeManyPresenter {
+    async fn complete(&self, data: like_many::Output) -> Result<()>;
+}
+
+#[async_trait]
+pub trait ContentUnlikeManyPresenter {
+    async fn complete(&self, data: unlike_many::Output) -> Result<()>;
+}
+
 #[async_trait]
 pub trait ContentPinGetPresenter {
     async fn complete(&self, data: get_pin::Output) -> Result<()>;
 }
 
+#[async_trait]
+pub trait ContentPinCountPresenter {
+    async fn complete(&self, data: count_pin::Output) -> Result<()>;
+}
+
 #[async_trait]
 pub trait ContentPinPresenter {
     async fn complete(&self, data: pin::Output) -> Result<()>;
@@ -3,17 +3,49 @@ use async_trait::async_trait;
 use tokio::sync::mpsc;
 
 use super::super::super::content;
-use crate::entities::Content;
-use crate::usecases::content::get;
+use crate::entities::ContentId;
+use crate::usecases::content::{get, gets, resolve};
 
 pub struct ReturnContentGetPresenter {
-    pub ret: mpsc::Sender<Content>,
+    pub ret: mpsc::Sender<get::Output>,
 }
 #[async_trait]
 impl content::ContentGetPresenter for ReturnContentGetPresenter {
-    async fn complete(&self, get::Output { content }: get::Output) -> Result<()> {
+    async fn complete(&self, output: get::Output) -> Result<()> {
         self.ret
-            .send(content)
+            .send(output)
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ReturnContentResolvePresenter {
+    pub ret: mpsc::Sender<ContentId>,
+}
+#[async_trait]
+impl content::ContentResolvePresenter for ReturnContentResolvePresenter {
+    async fn complete(&self, resolve::Output { content_id }: resolve::Output) -> Result<()> {
+        self.ret
+            .send(content_id)
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct ReturnContentGetsPresenter {
+    pub ret: mpsc::Sender<gets::Output>,
+}
+#[async_trait]
+impl content::ContentGetsPresenter for ReturnContentGetsPresenter {
+    async fn complete(&self, output: gets::Output) -> Result<()> {
+        self.ret
+            .send(output)
             .await
             .map_err(|e| e.to_string())
             .unwrap();
@@ -4,7 +4,7 @@ use tokio::sync::mpsc;
 
 use super::super::super::user;
 use crate::entities::User;
-use crate::usecases::user::get;
+use crate::usecases::user::{get, gets};
 
 pub struct ReturnUserGetPresenter {
     pub ret: mpsc::Sender<User>,
@@ -21,3 +21,19 @@ impl user::UserGetPresenter for ReturnUserGetPresenter {
         Ok(())
     }
 }
+
+pub struct ReturnUserGetsPresenter {
+    pub ret: mpsc::Sender<gets::Output>,
+}
+#[async_trait]
+impl user::UserGetsPresenter for ReturnUserGetsPresenter {
+    async fn complete(&self, output: gets::Output) -> Result<()> {
+        self.ret
+            .send(output)
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
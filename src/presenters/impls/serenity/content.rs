@@ -1,18 +1,68 @@
+use std::collections::HashSet;
+
 use anyhow::Result;
 use async_trait::async_trait;
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
 use tokio::sync::mpsc;
 
 use super::super::super::content;
-use super::{View, EMPTY_FIELD};
-use crate::entities::Content;
+use super::{capped_join, page_footer, truncate, truncate_field, View, EMPTY_FIELD};
+use crate::entities::{Content, ContentId, Date, Posted};
+use crate::messages::{Locale, Msg};
 use crate::usecases::content::{
-    edit, get, get_like, get_pin, gets, like, pin, post, unlike, unpin, withdraw,
+    count_like, count_pin, edit, get, get_like, get_pin, gets, history, like, like_many, merge,
+    pin, post, preview_sed, reassign_posted, rename_virtual, restore, stats, tag, unlike,
+    unlike_many, unpin, untag, withdraw,
 };
 use crate::utils::date_to_string;
 
+fn posted_to_string(posted: &Posted, anonymous: bool) -> String {
+    match anonymous {
+        true => "anonymous".to_string(),
+        false => posted.to_string(),
+    }
+}
+
+// discord rejects embeds with more fields than 25, and one is for current.
+const MAX_REVISIONS: usize = 24;
+
+// only preview is needed in lists. full one is shown by `get`.
+const PREVIEW_LEN: usize = 200;
+
+/// marks title of archived content.
+fn archived_title(title: impl ToString, archived: bool, archived_at: Option<Date>) -> String {
+    match (archived, archived_at) {
+        (false, _) => title.to_string(),
+        (true, Some(d)) => format!(
+            "{} (archived since {})",
+            title.to_string(),
+            date_to_string(d)
+        ),
+        (true, None) => format!("{} (archived)", title.to_string()),
+    }
+}
+
+fn tags_to_string(tags: HashSet<String>, cap: usize) -> String {
+    let mut tags = tags.into_iter().collect::<Vec<_>>();
+    tags.sort();
+
+    match tags.is_empty() {
+        true => "None".to_string(),
+        false => capped_join(tags.into_iter(), cap, "tags", None),
+    }
+}
+
+fn ids_to_string(mut ids: Vec<ContentId>, cap: usize) -> String {
+    match ids.is_empty() {
+        true => "None".to_string(),
+        false => capped_join(ids.drain(..).map(|i| i.to_string()), cap, "contents", None),
+    }
+}
+
 pub struct SerenityContentPostPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub list_cap: usize,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentPostPresenter for SerenityContentPostPresenter {
@@ -29,23 +79,47 @@ impl content::ContentPostPresenter for SerenityContentPostPresenter {
                     pinned: _,
                     created,
                     edited: _,
+                    short_id,
+                    title,
+                    source_url,
+                    anonymous,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags,
                 },
         }: post::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xfb, 0xf1, 0xc7);
+        let locale = self.locale;
+        let cap = self.list_cap;
+
+        let title = match title {
+            Some(t) => format!("{}: {}", locale.get(Msg::PostedContent), t),
+            None => locale.get(Msg::PostedContent).to_string(),
+        };
 
         self.out
             .send(box move |ce| {
-                ce.title("posted content")
+                ce.title(archived_title(title, archived, archived_at))
                     .color(COLOR)
-                    .description(id)
+                    .description(format!("{} ({})", id, short_id))
                     .fields([
                         ("author", author.to_string(), true),
-                        ("posted", posted.to_string(), true),
+                        ("posted", posted_to_string(&posted, anonymous), true),
                         (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
                         ("created", created.to_string(), false),
+                        ("tags", tags_to_string(tags, cap), false),
                         ("content", content, false),
-                    ])
+                    ]);
+
+                if let Some(u) = source_url {
+                    ce.url(&u).field("source", u, false);
+                }
+
+                ce
             })
             .await
             .map_err(|e| e.to_string())
@@ -57,6 +131,8 @@ impl content::ContentPostPresenter for SerenityContentPostPresenter {
 
 pub struct SerenityContentGetPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub list_cap: usize,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentGetPresenter for SerenityContentGetPresenter {
@@ -73,36 +149,162 @@ impl content::ContentGetPresenter for SerenityContentGetPresenter {
                     pinned,
                     created,
                     mut edited,
+                    short_id,
+                    title,
+                    source_url,
+                    anonymous,
+                    lang,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags,
                 },
+            liked_by_me,
+            pinned_by_me,
+            bookmarked_by_me,
         }: get::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xfa, 0xdb, 0x2f);
+        let locale = self.locale;
+        let cap = self.list_cap;
+
+        let by_me = [
+            ("you liked", liked_by_me),
+            ("you pinned", pinned_by_me),
+            ("you bookmarked", bookmarked_by_me),
+        ]
+        .into_iter()
+        .filter_map(|(name, b)| b.map(|b| (name, b.to_string(), true)));
 
         self.out
             .send(box move |ce| {
-                ce.title("showing content")
-                    .color(COLOR)
-                    .description(id)
-                    .fields([
-                        ("author", author.to_string(), true),
-                        ("posted", posted.to_string(), true),
-                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
-                        ("created", created.to_string(), false),
-                        ("edited_times", edited.len().to_string(), true),
-                        (
-                            "last_edited",
-                            edited
-                                .pop()
-                                .map(date_to_string)
-                                .unwrap_or_else(|| "None".to_string()),
-                            true,
-                        ),
-                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
-                        ("like", liked.len().to_string(), true),
-                        ("pin", pinned.len().to_string(), true),
-                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
-                        ("content", content, false),
-                    ])
+                ce.title(archived_title(
+                    title.unwrap_or_else(|| locale.get(Msg::ShowingContent).to_string()),
+                    archived,
+                    archived_at,
+                ))
+                .color(COLOR)
+                .description(format!("{} ({})", id, short_id))
+                .fields([
+                    ("author", author.to_string(), true),
+                    ("posted", posted_to_string(&posted, anonymous), true),
+                    (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
+                    ("created", created.to_string(), false),
+                    ("edited_times", edited.len().to_string(), true),
+                    (
+                        "last_edited",
+                        edited
+                            .pop()
+                            .map(date_to_string)
+                            .unwrap_or_else(|| "None".to_string()),
+                        true,
+                    ),
+                    (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
+                    ("like", liked.len().to_string(), true),
+                    ("pin", pinned.len().to_string(), true),
+                    ("lang", lang.unwrap_or_else(|| "None".to_string()), true),
+                ])
+                .fields(by_me)
+                .fields([
+                    ("tags", tags_to_string(tags, cap), false),
+                    ("content", content, false),
+                ]);
+
+                if let Some(u) = source_url {
+                    ce.url(&u).field("source", u, false);
+                }
+
+                ce
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityContentHistoryPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentHistoryPresenter for SerenityContentHistoryPresenter {
+    async fn complete(
+        &self,
+        history::Output {
+            content:
+                Content {
+                    id,
+                    author: _,
+                    posted: _,
+                    content,
+                    liked: _,
+                    pinned: _,
+                    created,
+                    edited: _,
+                    short_id,
+                    title: _,
+                    source_url: _,
+                    anonymous: _,
+                    lang: _,
+                    edit_history,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags: _,
+                },
+        }: history::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
+
+        let total = edit_history.len();
+        let description = match total > MAX_REVISIONS {
+            true => format!(
+                "{} ({}) | {} revisions, showing latest {}",
+                id, short_id, total, MAX_REVISIONS
+            ),
+            false => format!("{} ({}) | {} revisions", id, short_id, total),
+        };
+
+        // n-th revision was replaced at n-th date, and first one was created at
+        // `created`.
+        let mut since = created;
+        let mut revisions = edit_history
+            .into_iter()
+            .enumerate()
+            .map(|(i, (replaced, old))| {
+                let name = format!(
+                    "rev {} ({} ~ {})",
+                    i + 1,
+                    date_to_string(since),
+                    date_to_string(replaced)
+                );
+                since = replaced;
+
+                (name, truncate_field(old), false)
+            })
+            .collect::<Vec<_>>();
+        let skip = revisions.len().saturating_sub(MAX_REVISIONS);
+        revisions.drain(..skip);
+        revisions.push((
+            format!("current ({} ~)", date_to_string(since)),
+            truncate_field(content),
+            false,
+        ));
+
+        self.out
+            .send(box move |ce| {
+                ce.title(archived_title(
+                    locale.get(Msg::EditHistory),
+                    archived,
+                    archived_at,
+                ))
+                .color(COLOR)
+                .description(description)
+                .fields(revisions)
             })
             .await
             .map_err(|e| e.to_string())
@@ -114,11 +316,50 @@ impl content::ContentGetPresenter for SerenityContentGetPresenter {
 
 pub struct SerenityContentGetsPresenter {
     pub out: mpsc::Sender<SmallVec<[Box<View>; 5]>>,
+    pub list_cap: usize,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentGetsPresenter for SerenityContentGetsPresenter {
-    async fn complete(&self, gets::Output { mut contents, page }: gets::Output) -> Result<()> {
+    async fn complete(
+        &self,
+        gets::Output {
+            mut contents,
+            page,
+            total_items,
+            total_pages,
+            cursor,
+        }: gets::Output,
+    ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xfa, 0xdb, 0x2f);
+        let locale = self.locale;
+        let cap = self.list_cap;
+
+        if contents.is_empty() {
+            let view: Box<View> = box move |ce| {
+                ce.title(locale.get(Msg::ShowingContents))
+                    .color(COLOR)
+                    .description("no contents matched your query.")
+            };
+
+            self.out
+                .send(smallvec![view])
+                .await
+                .map_err(|e| e.to_string())
+                .unwrap();
+
+            return Ok(());
+        }
+
+        // totals are not counted with cursor, so pointing neighbors instead.
+        let footer = match cursor {
+            Some(_) => format!(
+                "--before {} / --after {}",
+                contents.first().unwrap().1.id,
+                contents.last().unwrap().1.id
+            ),
+            None => page_footer(page, total_pages, total_items),
+        };
 
         self.out
             .send(
@@ -136,32 +377,53 @@ impl content::ContentGetsPresenter for SerenityContentGetsPresenter {
                                 pinned,
                                 created,
                                 mut edited,
+                                short_id,
+                                title: _,
+                                source_url: _,
+                                anonymous,
+                                lang: _,
+                                edit_history: _,
+                                version: _,
+                                archived,
+                                archived_at,
+                                tags,
                             },
                         )| {
+                            let footer = footer.clone();
+                            let position = match cursor {
+                                Some(_) => idx.to_string(),
+                                None => format!("{} in {}", idx, page),
+                            };
                             box move |ce| {
-                                ce.title("showing contents.")
-                                    .color(COLOR)
-                                    .description(format!("{} in {} | {}", idx, page, id))
-                                    .fields([
-                                        ("author", author.to_string(), true),
-                                        ("posted", posted.to_string(), true),
-                                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
-                                        ("created", created.to_string(), false),
-                                        ("edited_times", edited.len().to_string(), true),
-                                        (
-                                            "last_edited",
-                                            edited
-                                                .pop()
-                                                .map(date_to_string)
-                                                .unwrap_or_else(|| "None".to_string()),
-                                            true,
-                                        ),
-                                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
-                                        ("like", liked.len().to_string(), true),
-                                        ("pin", pinned.len().to_string(), true),
-                                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
-                                        ("content", content, false),
-                                    ])
+                                ce.title(archived_title(
+                                    locale.get(Msg::ShowingContents),
+                                    archived,
+                                    archived_at,
+                                ))
+                                .color(COLOR)
+                                .description(format!("{} | {} ({})", position, id, short_id))
+                                .footer(|cf| cf.text(footer))
+                                .fields([
+                                    ("author", author.to_string(), true),
+                                    ("posted", posted_to_string(&posted, anonymous), true),
+                                    (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
+                                    ("created", created.to_string(), false),
+                                    ("edited_times", edited.len().to_string(), true),
+                                    (
+                                        "last_edited",
+                                        edited
+                                            .pop()
+                                            .map(date_to_string)
+                                            .unwrap_or_else(|| "None".to_string()),
+                                        true,
+                                    ),
+                                    (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
+                                    ("like", liked.len().to_string(), true),
+                                    ("pin", pinned.len().to_string(), true),
+                                    (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
+                                    ("tags", tags_to_string(tags, cap), false),
+                                    ("content", truncate(content, PREVIEW_LEN), false),
+                                ])
                             }
                         },
                     )
@@ -177,6 +439,7 @@ impl content::ContentGetsPresenter for SerenityContentGetsPresenter {
 
 pub struct SerenityContentEditPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentEditPresenter for SerenityContentEditPresenter {
@@ -193,36 +456,192 @@ impl content::ContentEditPresenter for SerenityContentEditPresenter {
                     pinned,
                     created,
                     mut edited,
+                    short_id,
+                    title: _,
+                    source_url: _,
+                    anonymous,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags: _,
                 },
         }: edit::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0x8e, 0xc0, 0x7c);
+        let locale = self.locale;
 
         self.out
             .send(box move |ce| {
-                ce.title("updated content.")
+                ce.title(archived_title(
+                    locale.get(Msg::UpdatedContent),
+                    archived,
+                    archived_at,
+                ))
+                .color(COLOR)
+                .description(format!("{} ({})", id, short_id))
+                .fields([
+                    ("author", author.to_string(), true),
+                    ("posted", posted_to_string(&posted, anonymous), true),
+                    (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
+                    ("created", created.to_string(), false),
+                    ("edited_times", edited.len().to_string(), true),
+                    (
+                        "last_edited",
+                        edited
+                            .pop()
+                            .map(date_to_string)
+                            .unwrap_or_else(|| "None".to_string()),
+                        true,
+                    ),
+                    (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
+                    ("like", liked.len().to_string(), true),
+                    ("pin", pinned.len().to_string(), true),
+                    (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
+                    ("content", content, false),
+                ])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityContentRenameVirtualPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentRenameVirtualPresenter for SerenityContentRenameVirtualPresenter {
+    async fn complete(
+        &self,
+        rename_virtual::Output { from, to, count }: rename_virtual::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x8e, 0xc0, 0x7c);
+        let locale = self.locale;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(locale.get(Msg::RenamedVirtualAuthor))
                     .color(COLOR)
-                    .description(id)
-                    .fields([
-                        ("author", author.to_string(), true),
-                        ("posted", posted.to_string(), true),
-                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
-                        ("created", created.to_string(), false),
-                        ("edited_times", edited.len().to_string(), true),
-                        (
-                            "last_edited",
-                            edited
-                                .pop()
-                                .map(date_to_string)
-                                .unwrap_or_else(|| "None".to_string()),
-                            true,
-                        ),
-                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
-                        ("like", liked.len().to_string(), true),
-                        ("pin", pinned.len().to_string(), true),
-                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
-                        ("content", content, false),
-                    ])
+                    .description(format!("{} => {}", from, to))
+                    .fields([("changed", count, true)])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityContentReassignPostedPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentReassignPostedPresenter for SerenityContentReassignPostedPresenter {
+    async fn complete(
+        &self,
+        reassign_posted::Output {
+            content:
+                Content {
+                    id,
+                    author: _,
+                    posted,
+                    content: _,
+                    liked: _,
+                    pinned: _,
+                    created: _,
+                    edited: _,
+                    short_id,
+                    title: _,
+                    source_url: _,
+                    anonymous: _,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags: _,
+                },
+            old,
+        }: reassign_posted::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x8e, 0xc0, 0x7c);
+        let locale = self.locale;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(archived_title(
+                    locale.get(Msg::ReassignedPosted),
+                    archived,
+                    archived_at,
+                ))
+                .color(COLOR)
+                .description(format!("{} ({})", id, short_id))
+                .fields([
+                    ("old", old.to_string(), true),
+                    ("new", posted.to_string(), true),
+                ])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityContentPreviewSedPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentPreviewSedPresenter for SerenityContentPreviewSedPresenter {
+    async fn complete(
+        &self,
+        preview_sed::Output {
+            content:
+                Content {
+                    id,
+                    author: _,
+                    posted: _,
+                    content,
+                    liked: _,
+                    pinned: _,
+                    created: _,
+                    edited: _,
+                    short_id,
+                    title: _,
+                    source_url: _,
+                    anonymous: _,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags: _,
+                },
+            previewed,
+        }: preview_sed::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x8e, 0xc0, 0x7c);
+        let locale = self.locale;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(archived_title(
+                    locale.get(Msg::PreviewOfEdit),
+                    archived,
+                    archived_at,
+                ))
+                .color(COLOR)
+                .description(format!("{} ({})", id, short_id))
+                .fields([("before", content, false), ("after", previewed, false)])
             })
             .await
             .map_err(|e| e.to_string())
@@ -234,6 +653,10 @@ impl content::ContentEditPresenter for SerenityContentEditPresenter {
 
 pub struct SerenityContentWithdrawPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub list_cap: usize,
+    /// used in hints.
+    pub prefix: String,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentWithdrawPresenter for SerenityContentWithdrawPresenter {
@@ -250,50 +673,63 @@ impl content::ContentWithdrawPresenter for SerenityContentWithdrawPresenter {
                     mut pinned,
                     created,
                     mut edited,
+                    short_id,
+                    title: _,
+                    source_url: _,
+                    anonymous,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived: _,
+                    archived_at: _,
+                    tags: _,
                 },
         }: withdraw::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0x66, 0x5c, 0x54);
+        let locale = self.locale;
+
+        let cap = self.list_cap;
+        let like_hint = format!("{} content like show {}", self.prefix, id);
+        let pin_hint = format!("{} content pin show {}", self.prefix, id);
 
         self.out
             .send(box move |ce| {
-                ce.title("deleted content.")
+                ce.title(locale.get(Msg::ArchivedContent))
                     .color(COLOR)
-                    .description(id)
+                    .description(format!("{} ({})", id, short_id))
                     .fields([
                         ("author", author.to_string(), true),
-                        ("posted", posted.to_string(), true),
+                        ("posted", posted_to_string(&posted, anonymous), true),
                         (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
                         ("created", created.to_string(), false),
                         ("edited_times", edited.len().to_string(), true),
                         (
                             "edit_history",
-                            edited
-                                .drain(..)
-                                .map(date_to_string)
-                                .collect::<Vec<_>>()
-                                .join(", "),
+                            capped_join(edited.drain(..).map(date_to_string), cap, "edits", None),
                             true,
                         ),
                         (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
                         ("like_times", liked.len().to_string(), false),
                         (
                             "liked",
-                            liked
-                                .drain()
-                                .map(|i| i.to_string())
-                                .collect::<Vec<_>>()
-                                .join(", "),
+                            capped_join(
+                                liked.drain().map(|i| i.to_string()),
+                                cap,
+                                "likers",
+                                Some(like_hint.as_str()),
+                            ),
                             false,
                         ),
                         ("pinned_times", pinned.len().to_string(), false),
                         (
                             "pinned",
-                            pinned
-                                .drain()
-                                .map(|i| i.to_string())
-                                .collect::<Vec<_>>()
-                                .join(", "),
+                            capped_join(
+                                pinned.drain().map(|i| i.to_string()),
+                                cap,
+                                "pinners",
+                                Some(pin_hint.as_str()),
+                            ),
                             false,
                         ),
                         ("content", content, false),
@@ -307,23 +743,144 @@ impl content::ContentWithdrawPresenter for SerenityContentWithdrawPresenter {
     }
 }
 
+pub struct SerenityContentMergePresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentMergePresenter for SerenityContentMergePresenter {
+    async fn complete(
+        &self,
+        merge::Output {
+            content,
+            from,
+            bookmarks,
+        }: merge::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0xb8, 0xbb, 0x26);
+        let locale = self.locale;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(locale.get(Msg::MergedContent))
+                    .color(COLOR)
+                    .description(format!("{} -> {}", from.id, content.id))
+                    .fields([
+                        ("like_times", content.liked.len().to_string(), true),
+                        ("pinned_times", content.pinned.len().to_string(), true),
+                        ("moved_bookmarks", bookmarks.to_string(), true),
+                    ])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityContentRestorePresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentRestorePresenter for SerenityContentRestorePresenter {
+    async fn complete(
+        &self,
+        restore::Output {
+            content:
+                Content {
+                    id,
+                    author,
+                    posted,
+                    content,
+                    liked: _,
+                    pinned: _,
+                    created: _,
+                    edited: _,
+                    short_id,
+                    title: _,
+                    source_url: _,
+                    anonymous,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags: _,
+                },
+        }: restore::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0xb8, 0xbb, 0x26);
+        let locale = self.locale;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(archived_title(
+                    locale.get(Msg::RestoredContent),
+                    archived,
+                    archived_at,
+                ))
+                .color(COLOR)
+                .description(format!("{} ({})", id, short_id))
+                .fields([
+                    ("author", author.to_string(), true),
+                    ("posted", posted_to_string(&posted, anonymous), true),
+                    ("content", content, false),
+                ])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
 pub struct SerenityContentLikeGetPresenter {
     pub out: mpsc::Sender<SmallVec<[Box<View>; 20]>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentLikeGetPresenter for SerenityContentLikeGetPresenter {
-    async fn complete(&self, get_like::Output { mut like, page }: get_like::Output) -> Result<()> {
+    async fn complete(
+        &self,
+        get_like::Output {
+            mut like,
+            mut users,
+            page,
+            total_items,
+            total_pages,
+        }: get_like::Output,
+    ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xd3, 0x86, 0x9b);
+        let locale = self.locale;
 
         self.out
             .send(
                 like.drain(..)
                     .map::<Box<View>, _>(|(idx, id)| {
+                        let footer = page_footer(page, total_pages, total_items);
+                        let detail = users.as_mut().map(|m| match m.remove(&id) {
+                            Some(u) => (u.admin.to_string(), u.sub_admin.to_string()),
+                            None => ("unregistered".to_string(), "unregistered".to_string()),
+                        });
                         box move |ce| {
-                            ce.title("showing like")
+                            ce.title(locale.get(Msg::ShowingLike))
                                 .color(COLOR)
                                 .description(format!("{} in {}", idx, page))
-                                .fields([("id", id, true)])
+                                .footer(|cf| cf.text(footer))
+                                .fields([("id", id.to_string(), true)]);
+
+                            if let Some((admin, sub_admin)) = detail {
+                                ce.fields([
+                                    ("name", format!("<@{}>", id), true),
+                                    ("admin", admin, true),
+                                    ("sub_admin", sub_admin, true),
+                                ]);
+                            }
+
+                            ce
                         }
                     })
                     .collect(),
@@ -336,8 +893,36 @@ impl content::ContentLikeGetPresenter for SerenityContentLikeGetPresenter {
     }
 }
 
+pub struct SerenityContentLikeCountPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentLikeCountPresenter for SerenityContentLikeCountPresenter {
+    async fn complete(
+        &self,
+        count_like::Output { content_id, count }: count_like::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0xd3, 0x86, 0x9b);
+        let locale = self.locale;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(locale.get(Msg::CountedLike))
+                    .color(COLOR)
+                    .description(format!("{} users liked {}", count, content_id))
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
 pub struct SerenityContentLikePresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentLikePresenter for SerenityContentLikePresenter {
@@ -354,15 +939,26 @@ impl content::ContentLikePresenter for SerenityContentLikePresenter {
                     pinned: _,
                     created: _,
                     edited: _,
+                    short_id: _,
+                    title: _,
+                    source_url: _,
+                    anonymous: _,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags: _,
                 },
             id,
         }: like::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xd3, 0x86, 0x9b);
+        let locale = self.locale;
 
         self.out
             .send(box move |ce| {
-                ce.title("like")
+                ce.title(archived_title(locale.get(Msg::Like), archived, archived_at))
                     .color(COLOR)
                     .description(format!("{} => {}", id, content_id))
                     .fields([("like", liked.len(), true)])
@@ -377,6 +973,7 @@ impl content::ContentLikePresenter for SerenityContentLikePresenter {
 
 pub struct SerenityContentUnlikePresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentUnlikePresenter for SerenityContentUnlikePresenter {
@@ -393,18 +990,111 @@ impl content::ContentUnlikePresenter for SerenityContentUnlikePresenter {
                     pinned: _,
                     created: _,
                     edited: _,
+                    short_id: _,
+                    title: _,
+                    source_url: _,
+                    anonymous: _,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags: _,
                 },
             id,
         }: unlike::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xd3, 0x86, 0x9b);
+        let locale = self.locale;
 
         self.out
             .send(box move |ce| {
-                ce.title("unlike")
+                ce.title(archived_title(
+                    locale.get(Msg::Unlike),
+                    archived,
+                    archived_at,
+                ))
+                .color(COLOR)
+                .description(format!("{} =/> {}", id, content_id))
+                .fields([("like", liked.len(), true)])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityContentLikeManyPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub list_cap: usize,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentLikeManyPresenter for SerenityContentLikeManyPresenter {
+    async fn complete(
+        &self,
+        like_many::Output {
+            liked,
+            already,
+            not_found,
+        }: like_many::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0xd3, 0x86, 0x9b);
+        let locale = self.locale;
+
+        let cap = self.list_cap;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(locale.get(Msg::Like))
                     .color(COLOR)
-                    .description(format!("{} =/> {}", id, content_id))
-                    .fields([("like", liked.len(), true)])
+                    .description(format!("{} liked", liked.len()))
+                    .fields([
+                        ("liked", ids_to_string(liked, cap), false),
+                        ("already liked", ids_to_string(already, cap), false),
+                        ("not found", ids_to_string(not_found, cap), false),
+                    ])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityContentUnlikeManyPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub list_cap: usize,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentUnlikeManyPresenter for SerenityContentUnlikeManyPresenter {
+    async fn complete(
+        &self,
+        unlike_many::Output {
+            unliked,
+            not_liked,
+            not_found,
+        }: unlike_many::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0xd3, 0x86, 0x9b);
+        let locale = self.locale;
+
+        let cap = self.list_cap;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(locale.get(Msg::Unlike))
+                    .color(COLOR)
+                    .description(format!("{} unliked", unliked.len()))
+                    .fields([
+                        ("unliked", ids_to_string(unliked, cap), false),
+                        ("didn't liked", ids_to_string(not_liked, cap), false),
+                        ("not found", ids_to_string(not_found, cap), false),
+                    ])
             })
             .await
             .map_err(|e| e.to_string())
@@ -416,20 +1106,32 @@ impl content::ContentUnlikePresenter for SerenityContentUnlikePresenter {
 
 pub struct SerenityContentPinGetPresenter {
     pub out: mpsc::Sender<SmallVec<[Box<View>; 20]>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentPinGetPresenter for SerenityContentPinGetPresenter {
-    async fn complete(&self, get_pin::Output { mut pin, page }: get_pin::Output) -> Result<()> {
+    async fn complete(
+        &self,
+        get_pin::Output {
+            mut pin,
+            page,
+            total_items,
+            total_pages,
+        }: get_pin::Output,
+    ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xfb, 0x49, 0x34);
+        let locale = self.locale;
 
         self.out
             .send(
                 pin.drain(..)
                     .map::<Box<View>, _>(|(idx, id)| {
+                        let footer = page_footer(page, total_pages, total_items);
                         box move |ce| {
-                            ce.title("showing pin")
+                            ce.title(locale.get(Msg::ShowingPin))
                                 .color(COLOR)
                                 .description(format!("{} in {}", idx, page))
+                                .footer(|cf| cf.text(footer))
                                 .fields([("id", id, true)])
                         }
                     })
@@ -443,8 +1145,36 @@ impl content::ContentPinGetPresenter for SerenityContentPinGetPresenter {
     }
 }
 
+pub struct SerenityContentPinCountPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentPinCountPresenter for SerenityContentPinCountPresenter {
+    async fn complete(
+        &self,
+        count_pin::Output { content_id, count }: count_pin::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0xfb, 0x49, 0x34);
+        let locale = self.locale;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(locale.get(Msg::CountedPin))
+                    .color(COLOR)
+                    .description(format!("{} users pinned {}", count, content_id))
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
 pub struct SerenityContentPinPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentPinPresenter for SerenityContentPinPresenter {
@@ -461,15 +1191,26 @@ impl content::ContentPinPresenter for SerenityContentPinPresenter {
                     pinned,
                     created: _,
                     edited: _,
+                    short_id: _,
+                    title: _,
+                    source_url: _,
+                    anonymous: _,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags: _,
                 },
             id,
         }: pin::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xfb, 0x49, 0x34);
+        let locale = self.locale;
 
         self.out
             .send(box move |ce| {
-                ce.title("pin")
+                ce.title(archived_title(locale.get(Msg::Pin), archived, archived_at))
                     .color(COLOR)
                     .description(format!("{} => {}", id, content_id))
                     .fields([("pin", pinned.len(), true)])
@@ -484,6 +1225,7 @@ impl content::ContentPinPresenter for SerenityContentPinPresenter {
 
 pub struct SerenityContentUnpinPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl content::ContentUnpinPresenter for SerenityContentUnpinPresenter {
@@ -500,18 +1242,196 @@ impl content::ContentUnpinPresenter for SerenityContentUnpinPresenter {
                     pinned,
                     created: _,
                     edited: _,
+                    short_id: _,
+                    title: _,
+                    source_url: _,
+                    anonymous: _,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags: _,
                 },
             id,
         }: unpin::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xfb, 0x49, 0x34);
+        let locale = self.locale;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(archived_title(
+                    locale.get(Msg::Unpin),
+                    archived,
+                    archived_at,
+                ))
+                .color(COLOR)
+                .description(format!("{} =/> {}", id, content_id))
+                .fields([("pin", pinned.len(), true)])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityContentTagPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub list_cap: usize,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentTagPresenter for SerenityContentTagPresenter {
+    async fn complete(
+        &self,
+        tag::Output {
+            content:
+                Content {
+                    id: content_id,
+                    author: _,
+                    posted: _,
+                    content: _,
+                    liked: _,
+                    pinned: _,
+                    created: _,
+                    edited: _,
+                    short_id: _,
+                    title: _,
+                    source_url: _,
+                    anonymous: _,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags,
+                },
+            tag,
+        }: tag::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x8e, 0xc0, 0x7c);
+        let locale = self.locale;
+        let cap = self.list_cap;
 
         self.out
             .send(box move |ce| {
-                ce.title("unpin")
+                ce.title(archived_title(locale.get(Msg::Tag), archived, archived_at))
                     .color(COLOR)
-                    .description(format!("{} =/> {}", id, content_id))
-                    .fields([("pin", pinned.len(), true)])
+                    .description(format!("{} => {}", tag, content_id))
+                    .fields([("tags", tags_to_string(tags, cap), true)])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityContentUntagPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub list_cap: usize,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentUntagPresenter for SerenityContentUntagPresenter {
+    async fn complete(
+        &self,
+        untag::Output {
+            content:
+                Content {
+                    id: content_id,
+                    author: _,
+                    posted: _,
+                    content: _,
+                    liked: _,
+                    pinned: _,
+                    created: _,
+                    edited: _,
+                    short_id: _,
+                    title: _,
+                    source_url: _,
+                    anonymous: _,
+                    lang: _,
+                    edit_history: _,
+                    version: _,
+                    archived,
+                    archived_at,
+                    tags,
+                },
+            tag,
+        }: untag::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x8e, 0xc0, 0x7c);
+        let locale = self.locale;
+        let cap = self.list_cap;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(archived_title(
+                    locale.get(Msg::Untag),
+                    archived,
+                    archived_at,
+                ))
+                .color(COLOR)
+                .description(format!("{} =/> {}", tag, content_id))
+                .fields([("tags", tags_to_string(tags, cap), true)])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityContentStatsPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl content::ContentStatsPresenter for SerenityContentStatsPresenter {
+    async fn complete(
+        &self,
+        stats::Output {
+            contents,
+            users,
+            top_liked,
+            top_pinned,
+        }: stats::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0xd3, 0x86, 0x9b);
+        let locale = self.locale;
+
+        fn ranking(contents: Vec<Content>, size: impl Fn(&Content) -> usize) -> String {
+            match contents.is_empty() {
+                true => "None".to_string(),
+                false => contents
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| format!("{}. {} ({})", i + 1, c.id, size(c)))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            }
+        }
+        let top_liked = ranking(top_liked, |c| c.liked.len());
+        let top_pinned = ranking(top_pinned, |c| c.pinned.len());
+
+        self.out
+            .send(box move |ce| {
+                ce.title(locale.get(Msg::ShowingStats))
+                    .color(COLOR)
+                    .fields([
+                        ("contents", contents.to_string(), true),
+                        ("users", users.to_string(), true),
+                    ])
+                    .fields([
+                        ("top liked", top_liked, false),
+                        ("top pinned", top_pinned, false),
+                    ])
             })
             .await
             .map_err(|e| e.to_string())
@@ -1,8 +1,193 @@
 const EMPTY_FIELD: (&str, &str, bool) = ("\u{200b}", "\u{200b}", true);
 
+// discord rejects embed fields with value longer than this.
+const FIELD_VALUE_LIMIT: usize = 1024;
+
+fn truncate_field(s: String) -> String { truncate(s, FIELD_VALUE_LIMIT) }
+
+/// truncates to `max` bytes (including ellipsis) at char boundary.
+fn truncate(mut s: String, max: usize) -> String {
+    const ELLIPSIS: &str = "...";
+
+    if s.len() <= max {
+        return s;
+    }
+
+    let mut at = max - ELLIPSIS.len();
+    while !s.is_char_boundary(at) {
+        at -= 1;
+    }
+    s.truncate(at);
+    s.push_str(ELLIPSIS);
+    s
+}
+
+/// footer of paged lists.
+fn page_footer(page: u32, total_pages: u32, total_items: usize) -> String {
+    format!("page {} of {} — {} total", page, total_pages, total_items)
+}
+
+/// joins items up to `cap` (and within field value limit).
+/// if some items are omitted, summary (and pointer to `hint` if given) is
+/// appended.
+fn capped_join(
+    items: impl ExactSizeIterator<Item = String>,
+    cap: usize,
+    noun: &str,
+    hint: Option<&str>,
+) -> String {
+    let total = items.len();
+    let summary = |shown: usize| {
+        let mut s = format!("\n({} {}, showing {}", total, noun, shown);
+        if let Some(h) = hint {
+            s.push_str(format!(". see `{}`", h).as_str());
+        }
+        s.push(')');
+        s
+    };
+    let reserved = summary(total).len();
+
+    let mut joined = String::new();
+    let mut shown = 0;
+    for item in items.take(cap) {
+        let sep = if shown == 0 { "" } else { ", " };
+
+        if joined.len() + sep.len() + item.len() + reserved > FIELD_VALUE_LIMIT {
+            break;
+        }
+
+        joined.push_str(sep);
+        joined.push_str(item.as_str());
+        shown += 1;
+    }
+
+    match shown == total {
+        true => joined,
+        false => joined + summary(shown).as_str(),
+    }
+}
+
 pub type View = dyn FnOnce(&mut ::serenity::builder::CreateEmbed) -> &mut ::serenity::builder::CreateEmbed
     + Sync
     + Send;
 
+/// file sent as attachment.
+#[derive(Debug)]
+pub struct Attachment {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+pub enum Response {
+    Embeds(::smallvec::SmallVec<[Box<View>; 20]>),
+    /// sent one embed at a time, turned by reactions.
+    Paged(::smallvec::SmallVec<[Box<View>; 20]>),
+    /// sent as code block (split if too long), without embed.
+    Text(String),
+    /// same as `Text`, highlighted as json.
+    Json(String),
+    File(Attachment),
+    /// asks the executor to confirm by reaction, before executing the command.
+    Confirm(Box<View>, crate::controllers::serenity::Pending),
+}
+
+/// user as json for `--json`.
+pub fn user_to_json(user: crate::entities::User) -> String {
+    serde_json::to_string_pretty(&user).unwrap()
+}
+
+/// same as `user_to_json` for each user, with page.
+pub fn users_to_json(
+    crate::usecases::user::gets::Output {
+        users,
+        page,
+        total_items,
+        total_pages,
+    }: crate::usecases::user::gets::Output,
+) -> String {
+    let users = users.into_iter().map(|(_, u)| u).collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&serde_json::json!({
+        "users": users,
+        "page": page,
+        "total_items": total_items,
+        "total_pages": total_pages,
+    }))
+    .unwrap()
+}
+
+/// `posted` of anonymous content is hidden, same as embeds.
+fn content_value(content: crate::entities::Content) -> serde_json::Value {
+    let anonymous = content.anonymous;
+
+    let mut v = serde_json::to_value(content).unwrap();
+    if anonymous {
+        v["posted"] = serde_json::Value::Null;
+    }
+    v
+}
+
+/// content (with own like, pin and bookmark) as json for `--json`.
+pub fn content_to_json(
+    crate::usecases::content::get::Output {
+        content,
+        liked_by_me,
+        pinned_by_me,
+        bookmarked_by_me,
+    }: crate::usecases::content::get::Output,
+) -> String {
+    serde_json::to_string_pretty(&serde_json::json!({
+        "content": content_value(content),
+        "liked_by_me": liked_by_me,
+        "pinned_by_me": pinned_by_me,
+        "bookmarked_by_me": bookmarked_by_me,
+    }))
+    .unwrap()
+}
+
+/// same as `content_to_json` for each content (without own ones), with page.
+/// page and totals are omitted if paged by cursor, as they are not counted.
+pub fn contents_to_json(
+    crate::usecases::content::gets::Output {
+        contents,
+        page,
+        total_items,
+        total_pages,
+        cursor,
+    }: crate::usecases::content::gets::Output,
+) -> String {
+    let contents = contents
+        .into_iter()
+        .map(|(_, c)| content_value(c))
+        .collect::<Vec<_>>();
+
+    let v = match cursor {
+        Some(_) => serde_json::json!({ "contents": contents }),
+        None => serde_json::json!({
+            "contents": contents,
+            "page": page,
+            "total_items": total_items,
+            "total_pages": total_pages,
+        }),
+    };
+
+    serde_json::to_string_pretty(&v).unwrap()
+}
+
+/// color of error embed. red if our fault, yellowish if executor's.
+pub fn error_color(e: &::anyhow::Error) -> (u8, u8, u8) {
+    use crate::errors::DomainError;
+
+    match e.downcast_ref::<DomainError>() {
+        Some(DomainError::Internal(_)) => (0xcc, 0x24, 0x1d),
+        Some(DomainError::NotFound(_)) => (0xd7, 0x99, 0x21),
+        Some(DomainError::AlreadyExists(_)) => (0xfa, 0xbd, 0x2f),
+        Some(DomainError::Forbidden(_)) => (0xd6, 0x5d, 0x0e),
+        Some(DomainError::Conflict(_)) => (0xb1, 0x62, 0x86),
+        // including errors not from domain (e.g. parse error).
+        Some(DomainError::Invalid(_)) | None => (0xfe, 0x80, 0x19),
+    }
+}
+
 pub mod content;
 pub mod user;
@@ -1,17 +1,21 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
 use tokio::sync::mpsc;
 
 use super::super::super::user;
-use super::{View, EMPTY_FIELD};
-use crate::entities::User;
+use super::{capped_join, page_footer, truncate_field, Attachment, View, EMPTY_FIELD};
+use crate::entities::{Content, User};
+use crate::messages::{Locale, Msg};
 use crate::usecases::user::{
-    bookmark, edit, get, get_bookmark, gets, register, unbookmark, unregister,
+    bookmark, count_bookmark, edit, export_bookmark, get, get_bookmark, get_bookmark_contents,
+    gets, register, similar, status, toggle_bookmark, unbookmark, unregister,
 };
+use crate::utils::date_to_string;
 
 pub struct SerenityUserRegisterPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl user::UserRegisterPresenter for SerenityUserRegisterPresenter {
@@ -24,13 +28,21 @@ impl user::UserRegisterPresenter for SerenityUserRegisterPresenter {
                     admin: _,
                     sub_admin: _,
                     bookmark: _,
+                    posted_count: _,
+                    granted_by: _,
+                    granted_at: _,
                 },
         }: register::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xd5, 0xc4, 0xa1);
+        let locale = self.locale;
 
         self.out
-            .send(box move |ce| ce.title("registered user").color(COLOR).description(id))
+            .send(box move |ce| {
+                ce.title(locale.get(Msg::RegisteredUser))
+                    .color(COLOR)
+                    .description(id)
+            })
             .await
             .map_err(|e| e.to_string())
             .unwrap();
@@ -38,8 +50,40 @@ impl user::UserRegisterPresenter for SerenityUserRegisterPresenter {
     }
 }
 
+pub struct SerenityUserStatusPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl user::UserStatusPresenter for SerenityUserStatusPresenter {
+    async fn complete(
+        &self,
+        status::Output {
+            user_id,
+            registered,
+        }: status::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(locale.get(Msg::ShowingStatus))
+                    .color(COLOR)
+                    .description(user_id)
+                    .fields([("registered", registered, true)])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
 pub struct SerenityUserGetPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl user::UserGetPresenter for SerenityUserGetPresenter {
@@ -52,14 +96,18 @@ impl user::UserGetPresenter for SerenityUserGetPresenter {
                     admin,
                     sub_admin,
                     bookmark,
+                    posted_count,
+                    granted_by,
+                    granted_at,
                 },
         }: get::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
 
         self.out
             .send(box move |ce| {
-                ce.title("showing user")
+                ce.title(locale.get(Msg::ShowingUser))
                     .color(COLOR)
                     .description(id)
                     .fields([
@@ -67,6 +115,28 @@ impl user::UserGetPresenter for SerenityUserGetPresenter {
                         ("sub_admin", sub_admin.to_string(), true),
                         (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
                         ("bookmark", bookmark.len().to_string(), true),
+                        (
+                            "posted",
+                            posted_count
+                                .map(|n| n.to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                            true,
+                        ),
+                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
+                        (
+                            "granted_by",
+                            granted_by
+                                .map(|i| i.to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                            true,
+                        ),
+                        (
+                            "granted_at",
+                            granted_at
+                                .map(date_to_string)
+                                .unwrap_or_else(|| "-".to_string()),
+                            true,
+                        ),
                     ])
             })
             .await
@@ -78,11 +148,37 @@ impl user::UserGetPresenter for SerenityUserGetPresenter {
 
 pub struct SerenityUserGetsPresenter {
     pub out: mpsc::Sender<SmallVec<[Box<View>; 5]>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl user::UserGetsPresenter for SerenityUserGetsPresenter {
-    async fn complete(&self, gets::Output { mut users, page }: gets::Output) -> Result<()> {
+    async fn complete(
+        &self,
+        gets::Output {
+            mut users,
+            page,
+            total_items,
+            total_pages,
+        }: gets::Output,
+    ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
+
+        if users.is_empty() {
+            let view: Box<View> = box move |ce| {
+                ce.title(locale.get(Msg::ShowingUsers))
+                    .color(COLOR)
+                    .description("no users matched your query.")
+            };
+
+            self.out
+                .send(smallvec![view])
+                .await
+                .map_err(|e| e.to_string())
+                .unwrap();
+
+            return Ok(());
+        }
 
         self.out
             .send(
@@ -96,12 +192,17 @@ impl user::UserGetsPresenter for SerenityUserGetsPresenter {
                                 admin,
                                 sub_admin,
                                 bookmark,
+                                posted_count: _,
+                                granted_by: _,
+                                granted_at: _,
                             },
                         )| {
+                            let footer = page_footer(page, total_pages, total_items);
                             box move |ce| {
-                                ce.title("showing users")
+                                ce.title(locale.get(Msg::ShowingUsers))
                                     .color(COLOR)
                                     .description(format!("{} in {} | {}", idx, page, id))
+                                    .footer(|cf| cf.text(footer))
                                     .fields([
                                         ("admin", admin.to_string(), true),
                                         ("sub_admin", sub_admin.to_string(), true),
@@ -121,8 +222,44 @@ impl user::UserGetsPresenter for SerenityUserGetsPresenter {
     }
 }
 
+pub struct SerenityUserSimilarPresenter {
+    pub out: mpsc::Sender<SmallVec<[Box<View>; 5]>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl user::UserSimilarPresenter for SerenityUserSimilarPresenter {
+    async fn complete(&self, similar::Output { mut users, page }: similar::Output) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
+
+        self.out
+            .send(
+                users
+                    .drain(..)
+                    .map::<Box<View>, _>(|(idx, User { id, bookmark, .. }, shared)| {
+                        box move |ce| {
+                            ce.title(locale.get(Msg::ShowingSimilarUsers))
+                                .color(COLOR)
+                                .description(format!("{} in {} | {}", idx, page, id))
+                                .fields([
+                                    ("shared_bookmark", shared.to_string(), true),
+                                    ("bookmark", bookmark.len().to_string(), true),
+                                ])
+                        }
+                    })
+                    .collect(),
+            )
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
 pub struct SerenityUserEditPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl user::UserEditPresenter for SerenityUserEditPresenter {
@@ -135,14 +272,18 @@ impl user::UserEditPresenter for SerenityUserEditPresenter {
                     admin,
                     sub_admin,
                     bookmark,
+                    posted_count: _,
+                    granted_by: _,
+                    granted_at: _,
                 },
         }: edit::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0xb8, 0xb2, 0x26);
+        let locale = self.locale;
 
         self.out
             .send(box move |ce| {
-                ce.title("updated user")
+                ce.title(locale.get(Msg::UpdatedUser))
                     .color(COLOR)
                     .description(id)
                     .fields([
@@ -161,6 +302,10 @@ impl user::UserEditPresenter for SerenityUserEditPresenter {
 
 pub struct SerenityUserUnregisterPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub list_cap: usize,
+    /// used in hints.
+    pub prefix: String,
+    pub locale: Locale,
 }
 #[async_trait]
 impl user::UserUnregisterPresenter for SerenityUserUnregisterPresenter {
@@ -173,28 +318,37 @@ impl user::UserUnregisterPresenter for SerenityUserUnregisterPresenter {
                     admin,
                     sub_admin,
                     mut bookmark,
+                    posted_count: _,
+                    granted_by: _,
+                    granted_at: _,
                 },
+            posted_changed,
         }: unregister::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0x1d, 0x20, 0x21);
+        let locale = self.locale;
+
+        let cap = self.list_cap;
+        let hint = format!("{} user bookmark show {}", self.prefix, id);
 
         self.out
             .send(box move |ce| {
-                ce.title("deleted user")
+                ce.title(locale.get(Msg::DeletedUser))
                     .color(COLOR)
                     .description(id)
                     .fields([
                         ("admin", admin.to_string(), true),
                         ("sub_admin", sub_admin.to_string(), true),
-                        (EMPTY_FIELD.0, EMPTY_FIELD.1.into(), EMPTY_FIELD.2),
+                        ("posted_changed", posted_changed.to_string(), true),
                         ("bookmark", bookmark.len().to_string(), false),
                         (
                             "bookmark",
-                            bookmark
-                                .drain()
-                                .map(|i| i.to_string())
-                                .collect::<Vec<_>>()
-                                .join(", "),
+                            capped_join(
+                                bookmark.drain().map(|i| i.to_string()),
+                                cap,
+                                "bookmarks",
+                                Some(hint.as_str()),
+                            ),
                             true,
                         ),
                     ])
@@ -208,24 +362,33 @@ impl user::UserUnregisterPresenter for SerenityUserUnregisterPresenter {
 
 pub struct SerenityUserBookmarkGetPresenter {
     pub out: mpsc::Sender<SmallVec<[Box<View>; 20]>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl user::UserBookmarkGetPresenter for SerenityUserBookmarkGetPresenter {
     async fn complete(
         &self,
-        get_bookmark::Output { mut bookmark, page }: get_bookmark::Output,
+        get_bookmark::Output {
+            mut bookmark,
+            page,
+            total_items,
+            total_pages,
+        }: get_bookmark::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
 
         self.out
             .send(
                 bookmark
                     .drain(..)
                     .map::<Box<View>, _>(|(idx, id)| {
+                        let footer = page_footer(page, total_pages, total_items);
                         box move |ce| {
-                            ce.title("showing bookmark")
+                            ce.title(locale.get(Msg::ShowingBookmark))
                                 .color(COLOR)
                                 .description(format!("{} in {}", idx, page))
+                                .footer(|cf| cf.text(footer))
                                 .fields([("id", id, true)])
                         }
                     })
@@ -238,8 +401,137 @@ impl user::UserBookmarkGetPresenter for SerenityUserBookmarkGetPresenter {
     }
 }
 
+pub struct SerenityUserBookmarkContentsPresenter {
+    pub out: mpsc::Sender<SmallVec<[Box<View>; 20]>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl user::UserBookmarkContentsPresenter for SerenityUserBookmarkContentsPresenter {
+    async fn complete(
+        &self,
+        get_bookmark_contents::Output { mut contents, page }: get_bookmark_contents::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
+
+        self.out
+            .send(
+                contents
+                    .drain(..)
+                    .map::<Box<View>, _>(|(idx, id, content)| {
+                        box move |ce| {
+                            ce.title(locale.get(Msg::ShowingBookmarkedContent))
+                                .color(COLOR)
+                                .description(format!("{} in {} | {}", idx, page, id));
+
+                            match content {
+                                Some(Content {
+                                    author,
+                                    content,
+                                    liked,
+                                    pinned,
+                                    ..
+                                }) => ce.fields([
+                                    ("author", author.to_string(), true),
+                                    ("like", liked.len().to_string(), true),
+                                    ("pin", pinned.len().to_string(), true),
+                                    ("content", truncate_field(content), false),
+                                ]),
+                                None => ce.field("content", "(withdrawn)", false),
+                            }
+                        }
+                    })
+                    .collect(),
+            )
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+        Ok(())
+    }
+}
+
+// chars of content shown in exported bookmarks.
+const SNIPPET_LEN: usize = 50;
+
+pub struct SerenityUserBookmarkCountPresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl user::UserBookmarkCountPresenter for SerenityUserBookmarkCountPresenter {
+    async fn complete(
+        &self,
+        count_bookmark::Output { user_id, count }: count_bookmark::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
+
+        self.out
+            .send(box move |ce| {
+                ce.title(locale.get(Msg::CountedBookmark))
+                    .color(COLOR)
+                    .description(format!("{} bookmarked {} contents", user_id, count))
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+pub struct SerenityUserBookmarkExportPresenter {
+    pub out: mpsc::Sender<Attachment>,
+}
+#[async_trait]
+impl user::UserBookmarkExportPresenter for SerenityUserBookmarkExportPresenter {
+    async fn complete(
+        &self,
+        export_bookmark::Output {
+            user_id,
+            mut contents,
+        }: export_bookmark::Output,
+    ) -> Result<()> {
+        let entries = contents
+            .drain(..)
+            .map(|(id, c)| match c {
+                Some(Content {
+                    author, content, ..
+                }) => {
+                    let mut snippet = content.chars().take(SNIPPET_LEN).collect::<String>();
+                    if content.chars().nth(SNIPPET_LEN).is_some() {
+                        snippet += "...";
+                    }
+
+                    ::serde_json::json!({
+                        "id": id.to_string(),
+                        "author": author.to_string(),
+                        "snippet": snippet,
+                    })
+                },
+                None => ::serde_json::json!({
+                    "id": id.to_string(),
+                    "author": null,
+                    "snippet": null,
+                }),
+            })
+            .collect::<Vec<_>>();
+
+        self.out
+            .send(Attachment {
+                name: format!("bookmark-{}.json", user_id),
+                data: ::serde_json::to_vec_pretty(&entries).unwrap(),
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+        Ok(())
+    }
+}
+
 pub struct SerenityUserBookmarkPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl user::UserBookmarkPresenter for SerenityUserBookmarkPresenter {
@@ -252,15 +544,19 @@ impl user::UserBookmarkPresenter for SerenityUserBookmarkPresenter {
                     admin: _,
                     sub_admin: _,
                     bookmark,
+                    posted_count: _,
+                    granted_by: _,
+                    granted_at: _,
                 },
             id,
         }: bookmark::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
 
         self.out
             .send(box move |ce| {
-                ce.title("bookmarked")
+                ce.title(locale.get(Msg::Bookmarked))
                     .color(COLOR)
                     .description(format!("{} => {}", user_id, id))
                     .fields([("bookmark", bookmark.len(), true)])
@@ -274,6 +570,7 @@ impl user::UserBookmarkPresenter for SerenityUserBookmarkPresenter {
 
 pub struct SerenityUserUnbookmarkPresenter {
     pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
 }
 #[async_trait]
 impl user::UserUnbookmarkPresenter for SerenityUserUnbookmarkPresenter {
@@ -286,15 +583,19 @@ impl user::UserUnbookmarkPresenter for SerenityUserUnbookmarkPresenter {
                     admin: _,
                     sub_admin: _,
                     bookmark,
+                    posted_count: _,
+                    granted_by: _,
+                    granted_at: _,
                 },
             id,
         }: unbookmark::Output,
     ) -> Result<()> {
         const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
 
         self.out
             .send(box move |ce| {
-                ce.title("unbookmarked")
+                ce.title(locale.get(Msg::Unbookmarked))
                     .color(COLOR)
                     .description(format!("{} =/> {}", user_id, id))
                     .fields([("bookmark", bookmark.len(), true)])
@@ -306,3 +607,49 @@ impl user::UserUnbookmarkPresenter for SerenityUserUnbookmarkPresenter {
         Ok(())
     }
 }
+
+pub struct SerenityUserBookmarkTogglePresenter {
+    pub out: mpsc::Sender<Box<View>>,
+    pub locale: Locale,
+}
+#[async_trait]
+impl user::UserBookmarkTogglePresenter for SerenityUserBookmarkTogglePresenter {
+    async fn complete(
+        &self,
+        toggle_bookmark::Output {
+            user:
+                User {
+                    id: user_id,
+                    admin: _,
+                    sub_admin: _,
+                    bookmark,
+                    posted_count: _,
+                    granted_by: _,
+                    granted_at: _,
+                },
+            id,
+            bookmarked,
+        }: toggle_bookmark::Output,
+    ) -> Result<()> {
+        const COLOR: (u8, u8, u8) = (0x83, 0xa5, 0x98);
+        let locale = self.locale;
+
+        let (title, arrow) = match bookmarked {
+            true => (locale.get(Msg::Bookmarked), "=>"),
+            false => (locale.get(Msg::Unbookmarked), "=/>"),
+        };
+
+        self.out
+            .send(box move |ce| {
+                ce.title(title)
+                    .color(COLOR)
+                    .description(format!("{} {} {}", user_id, arrow, id))
+                    .fields([("bookmark", bookmark.len(), true)])
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        Ok(())
+    }
+}
@@ -2,7 +2,8 @@ use anyhow::Result;
 use async_trait::async_trait;
 
 use crate::usecases::user::{
-    bookmark, edit, get, get_bookmark, gets, register, unbookmark, unregister,
+    bookmark, count_bookmark, edit, export_bookmark, get, get_bookmark, get_bookmark_contents,
+    gets, register, similar, status, toggle_bookmark, unbookmark, unregister,
 };
 
 #[async_trait]
@@ -10,6 +11,11 @@ pub trait UserRegisterPresenter {
     async fn complete(&self, data: register::Output) -> Result<()>;
 }
 
+#[async_trait]
+pub trait UserStatusPresenter {
+    async fn complete(&self, data: status::Output) -> Result<()>;
+}
+
 #[async_trait]
 pub trait UserGetPresenter {
     async fn complete(&self, data: get::Output) -> Result<()>;
@@ -20,6 +26,11 @@ pub trait UserGetsPresenter {
     async fn complete(&self, data: gets::Output) -> Result<()>;
 }
 
+#[async_trait]
+pub trait UserSimilarPresenter {
+    async fn complete(&self, data: similar::Output) -> Result<()>;
+}
+
 #[async_trait]
 pub trait UserEditPresenter {
     async fn complete(&self, data: edit::Output) -> Result<()>;
@@ -35,6 +46,21 @@ pub trait UserBookmarkGetPresenter {
     async fn complete(&self, data: get_bookmark::Output) -> Result<()>;
 }
 
+#[async_trait]
+pub trait UserBookmarkContentsPresenter {
+    async fn complete(&self, data: get_bookmark_contents::Output) -> Result<()>;
+}
+
+#[async_trait]
+pub trait UserBookmarkCountPresenter {
+    async fn complete(&self, data: count_bookmark::Output) -> Result<()>;
+}
+
+#[async_trait]
+pub trait UserBookmarkExportPresenter {
+    async fn complete(&self, data: export_bookmark::Output) -> Result<()>;
+}
+
 #[async_trait]
 pub trait UserBookmarkPresenter {
     async fn complete(&self, data: bookmark::Output) -> Result<()>;
@@ -44,3 +70,8 @@ pub trait UserBookmarkPresenter {
 pub trait UserUnbookmarkPresenter {
     async fn complete(&self, data: unbookmark::Output) -> Result<()>;
 }
+
+#[async_trait]
+pub trait UserBookmarkTogglePresenter {
+    async fn complete(&self, data: toggle_bookmark::Output) -> Result<()>;
+}
@@ -0,0 +1,312 @@
+use alloc::sync::Arc;
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use tokio::io::AsyncWrite;
+use tokio::sync::Mutex;
+
+use super::{ContentRepository, Result};
+use crate::entities::{Content, ContentId, Date, Posted, UserId};
+use crate::usecases::content::{ContentMutation, ContentQuery};
+
+/// caches results of `find` in front of other repository, evicting least
+/// recently used ones over `cap`. any mutation through this invalidates
+/// cached contents it may change, so the inner repository must not be
+/// mutated directly.
+pub struct CachingContentRepository {
+    inner: Arc<dyn ContentRepository + Sync + Send>,
+    cache: Mutex<Lru>,
+}
+
+struct Lru {
+    cap: usize,
+    /// content with tick of last use.
+    items: HashMap<ContentId, (Content, u64)>,
+    tick: u64,
+    /// bumped on every invalidation, to not store `find`s raced with it.
+    generation: u64,
+}
+
+impl Lru {
+    fn get(&mut self, id: ContentId) -> Option<Content> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        self.items.get_mut(&id).map(|(c, t)| {
+            *t = tick;
+            c.clone()
+        })
+    }
+
+    fn put(&mut self, content: Content) {
+        if self.items.len() >= self.cap && !self.items.contains_key(&content.id) {
+            let oldest = self
+                .items
+                .iter()
+                .min_by_key(|(_, (_, t))| *t)
+                .map(|(id, _)| *id);
+            if let Some(id) = oldest {
+                self.items.remove(&id);
+            }
+        }
+
+        self.tick += 1;
+        self.items.insert(content.id, (content, self.tick));
+    }
+
+    fn invalidate(&mut self, ids: &[ContentId]) {
+        self.generation += 1;
+        for id in ids {
+            self.items.remove(id);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.generation += 1;
+        self.items.clear();
+    }
+}
+
+impl CachingContentRepository {
+    /// `cap` must be greater than 0.
+    pub fn new(inner: Arc<dyn ContentRepository + Sync + Send>, cap: usize) -> Self {
+        assert!(cap > 0, "cap must be greater than 0");
+
+        Self {
+            inner,
+            cache: Mutex::new(Lru {
+                cap,
+                items: HashMap::new(),
+                tick: 0,
+                generation: 0,
+            }),
+        }
+    }
+
+    async fn invalidate(&self, ids: &[ContentId]) { self.cache.lock().await.invalidate(ids) }
+
+    async fn clear(&self) { self.cache.lock().await.clear() }
+}
+
+#[async_trait]
+impl ContentRepository for CachingContentRepository {
+    async fn insert(&self, item: Content) -> Result<bool> {
+        let id = item.id;
+        let res = self.inner.insert(item).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn is_exists(&self, id: ContentId) -> Result<bool> { self.inner.is_exists(id).await }
+
+    async fn find(&self, id: ContentId) -> Result<Content> {
+        let generation = {
+            let mut guard = self.cache.lock().await;
+            if let Some(c) = guard.get(id) {
+                return Ok(c);
+            }
+            guard.generation
+        };
+
+        let content = self.inner.find(id).await?;
+
+        let mut guard = self.cache.lock().await;
+        if guard.generation == generation {
+            guard.put(content.clone());
+        }
+
+        Ok(content)
+    }
+
+    async fn find_by_short_id(&self, short_id: String) -> Result<Content> {
+        self.inner.find_by_short_id(short_id).await
+    }
+
+    async fn find_many(&self, ids: &[ContentId]) -> Result<Vec<Content>> {
+        self.inner.find_many(ids).await
+    }
+
+    async fn finds(&self, query: ContentQuery) -> Result<Vec<Content>> {
+        self.inner.finds(query).await
+    }
+
+    async fn count(&self, query: ContentQuery) -> Result<u64> { self.inner.count(query).await }
+
+    async fn finds_paged(
+        &self,
+        query: ContentQuery,
+        skip: u64,
+        limit: u64,
+    ) -> Result<(Vec<Content>, u64)> {
+        self.inner.finds_paged(query, skip, limit).await
+    }
+
+    async fn finds_after(
+        &self,
+        query: ContentQuery,
+        cursor: ContentId,
+        limit: u64,
+    ) -> Result<Vec<Content>> {
+        self.inner.finds_after(query, cursor, limit).await
+    }
+
+    async fn finds_before(
+        &self,
+        query: ContentQuery,
+        cursor: ContentId,
+        limit: u64,
+    ) -> Result<Vec<Content>> {
+        self.inner.finds_before(query, cursor, limit).await
+    }
+
+    async fn update(&self, id: ContentId, mutation: ContentMutation) -> Result<Content> {
+        let res = self.inner.update(id, mutation).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn rename_virtual(&self, from: String, to: String) -> Result<u64> {
+        let res = self.inner.rename_virtual(from, to).await;
+        self.clear().await;
+        res
+    }
+
+    async fn reassign_posted(&self, id: ContentId, posted: Posted) -> Result<Content> {
+        let res = self.inner.reassign_posted(id, posted).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn reassign_posted_by(&self, from: UserId, posted: Posted) -> Result<u64> {
+        let res = self.inner.reassign_posted_by(from, posted).await;
+        self.clear().await;
+        res
+    }
+
+    async fn get_liked(&self, id: ContentId) -> Result<Vec<UserId>> {
+        self.inner.get_liked(id).await
+    }
+
+    async fn count_liked(&self, id: ContentId) -> Result<u32> { self.inner.count_liked(id).await }
+
+    async fn liked_page(&self, id: ContentId, skip: u64, limit: u64) -> Result<(Vec<UserId>, u64)> {
+        self.inner.liked_page(id, skip, limit).await
+    }
+
+    async fn is_liked(&self, id: ContentId, user_id: UserId) -> Result<bool> {
+        self.inner.is_liked(id, user_id).await
+    }
+
+    async fn insert_liked(&self, id: ContentId, user_id: UserId) -> Result<bool> {
+        let res = self.inner.insert_liked(id, user_id).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn delete_liked(&self, id: ContentId, user_id: UserId) -> Result<bool> {
+        let res = self.inner.delete_liked(id, user_id).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn insert_liked_many(
+        &self,
+        ids: Vec<ContentId>,
+        user_id: UserId,
+    ) -> Result<Vec<Option<bool>>> {
+        let res = self.inner.insert_liked_many(ids.clone(), user_id).await;
+        self.invalidate(ids.as_slice()).await;
+        res
+    }
+
+    async fn delete_liked_many(
+        &self,
+        ids: Vec<ContentId>,
+        user_id: UserId,
+    ) -> Result<Vec<Option<bool>>> {
+        let res = self.inner.delete_liked_many(ids.clone(), user_id).await;
+        self.invalidate(ids.as_slice()).await;
+        res
+    }
+
+    async fn get_pinned(&self, id: ContentId) -> Result<Vec<UserId>> {
+        self.inner.get_pinned(id).await
+    }
+
+    async fn count_pinned(&self, id: ContentId) -> Result<u32> { self.inner.count_pinned(id).await }
+
+    async fn pinned_page(
+        &self,
+        id: ContentId,
+        skip: u64,
+        limit: u64,
+    ) -> Result<(Vec<UserId>, u64)> {
+        self.inner.pinned_page(id, skip, limit).await
+    }
+
+    async fn is_pinned(&self, id: ContentId, user_id: UserId) -> Result<bool> {
+        self.inner.is_pinned(id, user_id).await
+    }
+
+    async fn insert_pinned(&self, id: ContentId, user_id: UserId) -> Result<bool> {
+        let res = self.inner.insert_pinned(id, user_id).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn delete_pinned(&self, id: ContentId, user_id: UserId) -> Result<bool> {
+        let res = self.inner.delete_pinned(id, user_id).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn top_liked(&self, n: usize) -> Result<Vec<Content>> { self.inner.top_liked(n).await }
+
+    async fn top_pinned(&self, n: usize) -> Result<Vec<Content>> { self.inner.top_pinned(n).await }
+
+    async fn insert_tag(&self, id: ContentId, tag: String) -> Result<bool> {
+        let res = self.inner.insert_tag(id, tag).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn delete_tag(&self, id: ContentId, tag: String) -> Result<bool> {
+        let res = self.inner.delete_tag(id, tag).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn merge_reactions(&self, from: ContentId, into: ContentId) -> Result<Content> {
+        let res = self.inner.merge_reactions(from, into).await;
+        self.invalidate(&[into]).await;
+        res
+    }
+
+    async fn archive(&self, id: ContentId, at: Date) -> Result<Content> {
+        let res = self.inner.archive(id, at).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn archive_posted_by(&self, from: UserId, at: Date) -> Result<u64> {
+        let res = self.inner.archive_posted_by(from, at).await;
+        self.clear().await;
+        res
+    }
+
+    async fn restore(&self, id: ContentId) -> Result<Content> {
+        let res = self.inner.restore(id).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn delete(&self, id: ContentId) -> Result<Content> {
+        let res = self.inner.delete(id).await;
+        self.invalidate(&[id]).await;
+        res
+    }
+
+    async fn export(&self, out: &mut (dyn AsyncWrite + Unpin + Send)) -> Result<usize> {
+        self.inner.export(out).await
+    }
+}
@@ -0,0 +1,231 @@
+use core::ops::RangeBounds;
+
+use chrono::{Datelike, Timelike};
+
+use crate::entities::{Author, Content, ContentId, Date, Posted};
+use crate::usecases::content::{AuthorQuery, ContentQuery, ContentSort, PostedQuery, SortKey};
+
+pub fn filter_content(
+    ContentQuery {
+        author,
+        posted,
+        content,
+        content_exact,
+        // differs per repository, so evaluated by repository. (see `filter_text`)
+        text: _,
+        liked,
+        liked_num,
+        liked_eq,
+        has_likes,
+        pinned,
+        pinned_num,
+        pinned_eq,
+        has_pins,
+        min_engagement,
+        lang,
+        self_unengaged,
+        created_hour,
+        created_weekday,
+        created_range,
+        edited_after,
+        pinned_not_liked_by,
+        // needs users, so evaluated by interactor.
+        bookmarked: _,
+        author_registered: _,
+        any_of,
+        // not a filter.
+        sort: _,
+        tags,
+        // applies to root query only, so evaluated by repository. (see `filter_archived`)
+        include_archived: _,
+    }: &ContentQuery,
+    c: &Content,
+) -> bool {
+    author
+        .as_ref()
+        .map(|q| filter_author(q, &c.author))
+        .unwrap_or(true)
+        && posted
+            .as_ref()
+            .map(|q| filter_posted(q, &c.posted))
+            .unwrap_or(true)
+        && content
+            .as_ref()
+            .map(|r| r.is_match(c.content.as_str()))
+            .unwrap_or(true)
+        && content_exact
+            .as_ref()
+            .map(|s| c.content == *s)
+            .unwrap_or(true)
+        && liked
+            .as_ref()
+            .map(|s| s.is_subset(&c.liked))
+            .unwrap_or(true)
+        && liked_num
+            .as_ref()
+            .map(|b| b.contains(&(c.liked.len() as u32)))
+            .unwrap_or(true)
+        && liked_eq.map(|n| c.liked.len() as u32 == n).unwrap_or(true)
+        && has_likes.map(|b| b != c.liked.is_empty()).unwrap_or(true)
+        && pinned
+            .as_ref()
+            .map(|s| s.is_subset(&c.pinned))
+            .unwrap_or(true)
+        && pinned_num
+            .as_ref()
+            .map(|b| b.contains(&(c.pinned.len() as u32)))
+            .unwrap_or(true)
+        && pinned_eq
+            .map(|n| c.pinned.len() as u32 == n)
+            .unwrap_or(true)
+        && has_pins.map(|b| b != c.pinned.is_empty()).unwrap_or(true)
+        && min_engagement
+            .map(|n| (c.liked.len() + c.pinned.len()) as u32 >= n)
+            .unwrap_or(true)
+        && lang
+            .as_ref()
+            .map(|l| c.lang.as_ref() == Some(l))
+            .unwrap_or(true)
+        && self_unengaged
+            .map(|b| {
+                let engaged = c.liked.contains(&c.posted.id) || c.pinned.contains(&c.posted.id);
+                b != engaged
+            })
+            .unwrap_or(true)
+        && created_hour
+            .as_ref()
+            .map(|r| r.contains(&(c.created.hour() as u8)))
+            .unwrap_or(true)
+        && created_weekday
+            .as_ref()
+            .map(|s| s.contains(&c.created.weekday()))
+            .unwrap_or(true)
+        && created_range
+            .as_ref()
+            .map(|r| r.contains(&c.created))
+            .unwrap_or(true)
+        && edited_after
+            .map(|d| c.edited.iter().any(|e| *e > d))
+            .unwrap_or(true)
+        && pinned_not_liked_by
+            .map(|u| c.pinned.contains(&u) && !c.liked.contains(&u))
+            .unwrap_or(true)
+        && tags.as_ref().map(|s| s.is_subset(&c.tags)).unwrap_or(true)
+        && any_of
+            .as_ref()
+            .map(|v| v.is_empty() || v.iter().any(|q| filter_content(q, c)))
+            .unwrap_or(true)
+}
+
+/// case-insensitive substring matching, used in place of text index.
+pub fn filter_text(ContentQuery { text, .. }: &ContentQuery, c: &Content) -> bool {
+    text.as_ref()
+        .map(|t| c.content.to_lowercase().contains(&t.to_lowercase()))
+        .unwrap_or(true)
+}
+
+pub fn filter_archived(
+    ContentQuery {
+        include_archived, ..
+    }: &ContentQuery,
+    c: &Content,
+) -> bool {
+    *include_archived || !c.archived
+}
+
+/// stable, so equal ones keep insertion order.
+pub fn sort_contents(contents: &mut [Content], ContentSort { key, desc }: ContentSort) {
+    contents.sort_by(|a, b| {
+        let ord = match key {
+            SortKey::Created => a.created.cmp(&b.created),
+            SortKey::LikedCount => a.liked.len().cmp(&b.liked.len()),
+            SortKey::PinnedCount => a.pinned.len().cmp(&b.pinned.len()),
+            SortKey::Edited => {
+                let last = |c: &Content| c.edited.last().copied().unwrap_or(c.created);
+                last(a).cmp(&last(b))
+            },
+        };
+
+        match desc {
+            true => ord.reverse(),
+            false => ord,
+        }
+    });
+}
+
+/// key of cursor paging. `id` breaks ties of `created`, so the order is total.
+pub fn cursor_key(c: &Content) -> (Date, ContentId) { (c.created, c.id) }
+
+/// keeps `limit` contents closest to `pivot`, strictly after (or before) it.
+/// in ascending order of `cursor_key` anyway.
+pub fn page_by_cursor(
+    mut contents: Vec<Content>,
+    pivot: (Date, ContentId),
+    after: bool,
+    limit: usize,
+) -> Vec<Content> {
+    contents.retain(|c| match after {
+        true => cursor_key(c) > pivot,
+        false => cursor_key(c) < pivot,
+    });
+    contents.sort_by_key(cursor_key);
+
+    match after {
+        true => contents.truncate(limit),
+        false => drop(contents.drain(..contents.len().saturating_sub(limit))),
+    }
+
+    contents
+}
+
+fn filter_author(q: &AuthorQuery, author: &Author) -> bool {
+    match author {
+        Author::User { id, name, nick } => match q {
+            AuthorQuery::UserId(q_id) => q_id == id,
+            AuthorQuery::UserName(q_r) => q_r.is_match(name.as_str()),
+            AuthorQuery::UserNick(q_r) => nick
+                .as_ref()
+                .map(|n| q_r.is_match(n.as_str()))
+                .unwrap_or(false),
+            AuthorQuery::Any(q_r) =>
+                q_r.is_match(name.as_str())
+                    || nick
+                        .as_ref()
+                        .map(|n| q_r.is_match(n.as_str()))
+                        .unwrap_or(false),
+            AuthorQuery::AnyOfUsers(q_ids) => q_ids.contains(id),
+            AuthorQuery::NameContains(q_s) =>
+                name.to_lowercase().contains(q_s.as_str())
+                    || nick
+                        .as_ref()
+                        .map(|n| n.to_lowercase().contains(q_s.as_str()))
+                        .unwrap_or(false),
+            _ => false,
+        },
+        Author::Virtual(name) => match q {
+            AuthorQuery::Virtual(q_r) => q_r.is_match(name.as_str()),
+            AuthorQuery::Any(q_r) => q_r.is_match(name.as_str()),
+            AuthorQuery::NameContains(q_s) => name.to_lowercase().contains(q_s.as_str()),
+            _ => false,
+        },
+    }
+}
+
+fn filter_posted(q: &PostedQuery, posted: &Posted) -> bool {
+    match q {
+        PostedQuery::UserId(q_id) => *q_id == posted.id,
+        PostedQuery::UserName(q_r) => q_r.is_match(posted.name.as_str()),
+        PostedQuery::UserNick(q_r) => posted
+            .nick
+            .as_ref()
+            .map(|n| q_r.is_match(n.as_str()))
+            .unwrap_or(false),
+        PostedQuery::Any(q_r) =>
+            q_r.is_match(posted.name.as_str())
+                || posted
+                    .nick
+                    .as_ref()
+                    .map(|n| q_r.is_match(n.as_str()))
+                    .unwrap_or(false),
+    }
+}
@@ -1,4 +1,4 @@
-use super::{RepositoryError, Result as RepoResult};
+use super::{Content, RepositoryError, Result as RepoResult};
 
 pub fn find_mut<T, P>(v: &mut [T], preficate: P) -> RepoResult<&mut T>
 where
@@ -31,3 +31,11 @@ where
         i => Err(RepositoryError::NoUnique { matched: i as u32 }),
     }
 }
+
+/// at most `n` not archived contents with largest `size`, ties are older first.
+pub fn top_by(v: &[Content], n: usize, size: impl Fn(&Content) -> usize) -> Vec<Content> {
+    let mut res = v.iter().filter(|c| !c.archived).collect::<Vec<_>>();
+    res.sort_by(|a, b| size(b).cmp(&size(a)).then(a.created.cmp(&b.created)));
+
+    res.drain(..).take(n).cloned().collect()
+}
@@ -1,28 +1,56 @@
 use core::ops::RangeBounds;
 
 use async_trait::async_trait;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio::sync::Mutex;
 
-use super::{ContentRepository, RepositoryError, Result, UserRepository};
-use crate::entities::{Author, Content, ContentId, User, UserId};
-use crate::usecases::content::{
-    AuthorQuery, ContentContentMutation, ContentMutation, ContentQuery, PostedQuery,
+use super::filters::{
+    cursor_key, filter_archived, filter_content, filter_text, page_by_cursor, sort_contents,
 };
+use super::mongo::models::MongoContentModel;
+use super::{ContentRepository, HealthRepository, RepositoryError, Result, UserRepository};
+use crate::entities::{Author, Content, ContentId, Date, Posted, User, UserId};
+use crate::usecases::content::{ContentMutation, ContentQuery};
 use crate::usecases::user::{UserMutation, UserQuery};
 
 mod helpers;
 
 use helpers::*;
 
-pub struct InMemoryRepository<T>(Mutex<Vec<T>>);
+/// second is whether to reject contents with the same body as existing
+/// (not archived) one posted by the same user, as `NoUnique`.
+pub struct InMemoryRepository<T>(Mutex<Vec<T>>, bool);
 
 impl<T> InMemoryRepository<T> {
-    pub fn new() -> Self { Self(Mutex::new(vec![])) }
+    pub fn new() -> Self { Self::new_with(false) }
+
+    pub fn new_with(reject_duplicate_body: bool) -> Self {
+        Self(Mutex::new(vec![]), reject_duplicate_body)
+    }
+}
+impl InMemoryRepository<Content> {
+    async fn finds_by_cursor(
+        &self,
+        query: ContentQuery,
+        cursor: ContentId,
+        after: bool,
+        limit: u64,
+    ) -> Result<Vec<Content>> {
+        let pivot = cursor_key(find_ref(&self.0.lock().await, |v| v.id == cursor)?);
+        let all = self.finds(query).await?;
+
+        Ok(page_by_cursor(all, pivot, after, limit as usize))
+    }
 }
 impl<T> Default for InMemoryRepository<T> {
     fn default() -> Self { Self::new() }
 }
 
+#[async_trait]
+impl<T: Send> HealthRepository for InMemoryRepository<T> {
+    async fn ping(&self) -> Result<Option<::core::time::Duration>> { Ok(None) }
+}
+
 #[async_trait]
 impl UserRepository for InMemoryRepository<User> {
     async fn insert(&self, item: User) -> Result<bool> {
@@ -56,12 +84,28 @@ impl UserRepository for InMemoryRepository<User> {
         Ok(find_ref(&guard, |v| v.id == id)?.clone())
     }
 
+    async fn find_many(&self, ids: &[UserId]) -> Result<Vec<User>> {
+        let guard = self.0.lock().await;
+
+        let res = ids
+            .iter()
+            .filter_map(|id| guard.iter().find(|u| u.id == *id))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        tracing::trace!("found - {:?}", res);
+
+        Ok(res)
+    }
+
     #[tracing::instrument(skip(self))]
     async fn finds(
         &self,
         UserQuery {
             bookmark,
             bookmark_num,
+            admin,
+            sub_admin,
         }: UserQuery,
     ) -> Result<Vec<User>> {
         let res = self
@@ -81,6 +125,8 @@ impl UserRepository for InMemoryRepository<User> {
                     .map(|b| b.contains(&(u.bookmark.len() as u32)))
                     .unwrap_or(true)
             })
+            .filter(|u| admin.map(|b| u.admin == b).unwrap_or(true))
+            .filter(|u| sub_admin.map(|b| u.sub_admin == b).unwrap_or(true))
             .cloned()
             .collect();
 
@@ -89,11 +135,20 @@ impl UserRepository for InMemoryRepository<User> {
         Ok(res)
     }
 
+    async fn count(&self, query: UserQuery) -> Result<u64> {
+        Ok(self.finds(query).await?.len() as u64)
+    }
+
     #[tracing::instrument(skip(self))]
     async fn update(
         &self,
         id: UserId,
-        UserMutation { admin, sub_admin }: UserMutation,
+        UserMutation {
+            admin,
+            sub_admin,
+            granted_by,
+            granted_at,
+        }: UserMutation,
     ) -> Result<User> {
         let mut guard = self.0.lock().await;
         let item = find_mut(&mut guard, |v| v.id == id)?;
@@ -106,16 +161,31 @@ impl UserRepository for InMemoryRepository<User> {
         if let Some(val) = sub_admin {
             item.sub_admin = val;
         }
+        if let Some(val) = granted_by {
+            item.granted_by = Some(val);
+        }
+        if let Some(val) = granted_at {
+            item.granted_at = Some(val);
+        }
 
         tracing::trace!("mutated - {:?}", item);
 
         Ok(item.clone())
     }
 
-    async fn get_bookmark(&self, id: UserId) -> Result<std::collections::HashSet<ContentId>> {
+    async fn get_bookmark(&self, id: UserId) -> Result<Vec<ContentId>> {
+        let User { bookmark, .. } = self.find(id).await?;
+
+        let mut res = bookmark.into_iter().collect::<Vec<_>>();
+        res.sort();
+
+        Ok(res)
+    }
+
+    async fn count_bookmark(&self, id: UserId) -> Result<u32> {
         let User { bookmark, .. } = self.find(id).await?;
 
-        Ok(bookmark)
+        Ok(bookmark.len() as u32)
     }
 
     async fn is_bookmark(&self, id: UserId, content_id: ContentId) -> Result<bool> {
@@ -143,6 +213,55 @@ impl UserRepository for InMemoryRepository<User> {
         Ok(item.bookmark.remove(&content_id))
     }
 
+    async fn toggle_bookmark(&self, id: UserId, content_id: ContentId) -> Result<bool> {
+        let mut guard = self.0.lock().await;
+        let item = find_mut(&mut guard, |u| u.id == id)?;
+
+        match item.bookmark.remove(&content_id) {
+            true => Ok(false),
+            false => Ok(item.bookmark.insert(content_id)),
+        }
+    }
+
+    async fn replace_bookmark(&self, from: ContentId, into: ContentId) -> Result<u64> {
+        let mut guard = self.0.lock().await;
+
+        let mut count = 0;
+        for item in guard.iter_mut() {
+            if item.bookmark.remove(&from) {
+                item.bookmark.insert(into);
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    async fn bookmarked_ids(&self) -> Result<std::collections::HashSet<ContentId>> {
+        let guard = self.0.lock().await;
+
+        Ok(guard
+            .iter()
+            .flat_map(|u| u.bookmark.iter().copied())
+            .collect())
+    }
+
+    async fn similar(&self, id: UserId) -> Result<Vec<(User, u32)>> {
+        let guard = self.0.lock().await;
+        let target = find_ref(&guard, |v| v.id == id)?;
+
+        let mut res = guard
+            .iter()
+            .filter(|u| u.id != id)
+            .map(|u| (u, u.bookmark.intersection(&target.bookmark).count() as u32))
+            .filter(|(_, n)| *n > 0)
+            .map(|(u, n)| (u.clone(), n))
+            .collect::<Vec<_>>();
+        res.sort_by(|(a, an), (b, bn)| bn.cmp(an).then(a.id.cmp(&b.id)));
+
+        Ok(res)
+    }
+
     #[tracing::instrument(skip(self))]
     async fn delete(&self, id: UserId) -> Result<User> {
         let mut guard = self.0.lock().await;
@@ -176,6 +295,25 @@ impl ContentRepository for InMemoryRepository<Content> {
             Err(e) => return Err(e),
         }
 
+        if guard.iter().any(|v| v.short_id == item.short_id) {
+            return Ok(false);
+        }
+
+        if self.1 {
+            let matched = guard
+                .iter()
+                .filter(|v| {
+                    !v.archived && v.posted.id == item.posted.id && v.content == item.content
+                })
+                .count();
+
+            if matched > 0 {
+                return Err(RepositoryError::NoUnique {
+                    matched: matched as u32,
+                });
+            }
+        }
+
         tracing::trace!("insert - {:?}", item);
 
         guard.push(item);
@@ -185,7 +323,7 @@ impl ContentRepository for InMemoryRepository<Content> {
     async fn is_exists(&self, id: ContentId) -> Result<bool> {
         let guard = self.0.lock().await;
 
-        match find_ref(&guard, |v| v.id == id) {
+        match find_ref(&guard, |v| v.id == id && !v.archived) {
             Ok(_) => Ok(true),
             Err(RepositoryError::NotFound) => Ok(false),
             Err(e) => Err(e),
@@ -195,114 +333,101 @@ impl ContentRepository for InMemoryRepository<Content> {
     async fn find(&self, id: ContentId) -> Result<Content> {
         let guard = self.0.lock().await;
 
-        Ok(find_ref(&guard, |v| v.id == id)?.clone())
+        Ok(find_ref(&guard, |v| v.id == id && !v.archived)?.clone())
+    }
+
+    async fn find_by_short_id(&self, short_id: String) -> Result<Content> {
+        let guard = self.0.lock().await;
+
+        Ok(find_ref(&guard, |v| v.short_id == short_id && !v.archived)?.clone())
+    }
+
+    async fn find_many(&self, ids: &[ContentId]) -> Result<Vec<Content>> {
+        let guard = self.0.lock().await;
+
+        let res = ids
+            .iter()
+            .filter_map(|id| guard.iter().find(|c| c.id == *id && !c.archived))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        tracing::trace!("found - {:?}", res);
+
+        Ok(res)
     }
 
     #[tracing::instrument(skip(self))]
-    async fn finds(
-        &self,
-        ContentQuery {
-            author,
-            posted,
-            content,
-            liked,
-            liked_num,
-            pinned,
-            pinned_num,
-        }: ContentQuery,
-    ) -> Result<Vec<Content>> {
-        let res = self
-            .00
+    async fn finds(&self, query: ContentQuery) -> Result<Vec<Content>> {
+        let mut res = self
+            .0
             .lock()
             .await
             .iter()
             .filter(|c| {
-                author
-                    .as_ref()
-                    .map(|q| match &c.author {
-                        Author::User { id, name, nick } => match q {
-                            AuthorQuery::UserId(q_id) => q_id == id,
-                            AuthorQuery::UserName(q_r) => q_r.is_match(name.as_str()),
-                            AuthorQuery::UserNick(q_r) => nick
-                                .as_ref()
-                                .map(|n| q_r.is_match(n.as_str()))
-                                .unwrap_or(false),
-                            AuthorQuery::Any(q_r) =>
-                                (q_r.is_match(name.as_str())
-                                    || nick
-                                        .as_ref()
-                                        .map(|n| q_r.is_match(n.as_str()))
-                                        .unwrap_or(false)),
-                            _ => false,
-                        },
-                        Author::Virtual(name) => match q {
-                            AuthorQuery::Virtual(q_r) => q_r.is_match(name.as_str()),
-                            AuthorQuery::Any(q_r) => q_r.is_match(name.as_str()),
-                            _ => false,
-                        },
-                    })
-                    .unwrap_or(true)
-            })
-            .filter(|c| {
-                posted
-                    .as_ref()
-                    .map(|q| match q {
-                        PostedQuery::UserId(q_id) => *q_id == c.posted.id,
-                        PostedQuery::UserName(q_r) => q_r.is_match(c.posted.name.as_str()),
-                        PostedQuery::UserNick(q_r) => c
-                            .posted
-                            .nick
-                            .as_ref()
-                            .map(|n| q_r.is_match(n.as_str()))
-                            .unwrap_or(false),
-                        PostedQuery::Any(q_r) =>
-                            (q_r.is_match(c.posted.name.as_str())
-                                || c.posted
-                                    .nick
-                                    .as_ref()
-                                    .map(|n| q_r.is_match(n.as_str()))
-                                    .unwrap_or(false)),
-                    })
-                    .unwrap_or(true)
-            })
-            .filter(|c| {
-                content
-                    .as_ref()
-                    .map(|r| r.is_match(c.content.as_str()))
-                    .unwrap_or(true)
-            })
-            .filter(|c| {
-                liked
-                    .as_ref()
-                    .map(|s| s.is_subset(&c.liked))
-                    .unwrap_or(true)
-            })
-            .filter(|c| {
-                liked_num
-                    .as_ref()
-                    .map(|b| b.contains(&(c.liked.len() as u32)))
-                    .unwrap_or(true)
-            })
-            .filter(|c| {
-                pinned
-                    .as_ref()
-                    .map(|s| s.is_subset(&c.pinned))
-                    .unwrap_or(true)
-            })
-            .filter(|c| {
-                pinned_num
-                    .as_ref()
-                    .map(|b| b.contains(&(c.pinned.len() as u32)))
-                    .unwrap_or(true)
+                filter_content(&query, c) && filter_text(&query, c) && filter_archived(&query, c)
             })
             .cloned()
-            .collect();
+            .collect::<Vec<_>>();
+
+        if let Some(s) = query.sort {
+            sort_contents(&mut res, s);
+        }
 
         tracing::trace!("found - {:?}", res);
 
         Ok(res)
     }
 
+    async fn count(&self, query: ContentQuery) -> Result<u64> {
+        let res = self
+            .0
+            .lock()
+            .await
+            .iter()
+            .filter(|c| {
+                filter_content(&query, c) && filter_text(&query, c) && filter_archived(&query, c)
+            })
+            .count();
+
+        Ok(res as u64)
+    }
+
+    async fn finds_paged(
+        &self,
+        query: ContentQuery,
+        skip: u64,
+        limit: u64,
+    ) -> Result<(Vec<Content>, u64)> {
+        let mut all = self.finds(query).await?;
+        let total = all.len() as u64;
+
+        let res = all
+            .drain(..)
+            .skip(skip as usize)
+            .take(limit as usize)
+            .collect();
+
+        Ok((res, total))
+    }
+
+    async fn finds_after(
+        &self,
+        query: ContentQuery,
+        cursor: ContentId,
+        limit: u64,
+    ) -> Result<Vec<Content>> {
+        self.finds_by_cursor(query, cursor, true, limit).await
+    }
+
+    async fn finds_before(
+        &self,
+        query: ContentQuery,
+        cursor: ContentId,
+        limit: u64,
+    ) -> Result<Vec<Content>> {
+        self.finds_by_cursor(query, cursor, false, limit).await
+    }
+
     #[tracing::instrument(skip(self))]
     async fn update(
         &self,
@@ -310,6 +435,7 @@ impl ContentRepository for InMemoryRepository<Content> {
         ContentMutation {
             author,
             content,
+            title,
             edited,
         }: ContentMutation,
     ) -> Result<Content> {
@@ -321,27 +447,53 @@ impl ContentRepository for InMemoryRepository<Content> {
         if let Some(new_author) = author {
             item.author = new_author;
         }
-        match content {
-            Some(ContentContentMutation::Complete(new_content)) => {
-                item.content = new_content;
-            },
-            Some(ContentContentMutation::Sed { capture, replace }) => {
-                item.content = capture.replace(item.content.as_ref(), replace).to_string();
-            },
-            None => (),
-        };
+        if let Some(m) = content {
+            let new = m.apply(item.content.as_str());
+            let old = ::core::mem::replace(&mut item.content, new);
+            item.edit_history.push((edited, old));
+            item.lang = crate::utils::detect_lang(item.content.as_str());
+        }
+        if let Some(t) = title {
+            item.title = t;
+        }
 
         item.edited.push(edited);
+        item.version += 1;
 
         tracing::trace!("mutated - {:?}", item);
 
         Ok(item.clone())
     }
 
-    async fn get_liked(&self, id: ContentId) -> Result<std::collections::HashSet<UserId>> {
+    async fn get_liked(&self, id: ContentId) -> Result<Vec<UserId>> {
         let Content { liked, .. } = self.find(id).await?;
 
-        Ok(liked)
+        let mut res = liked.into_iter().collect::<Vec<_>>();
+        res.sort();
+
+        Ok(res)
+    }
+
+    async fn liked_page(&self, id: ContentId, skip: u64, limit: u64) -> Result<(Vec<UserId>, u64)> {
+        let Content { liked, .. } = self.find(id).await?;
+        let total = liked.len() as u64;
+
+        let mut all = liked.into_iter().collect::<Vec<_>>();
+        all.sort_unstable_by_key(|i| i.0);
+
+        let res = all
+            .drain(..)
+            .skip(skip as usize)
+            .take(limit as usize)
+            .collect();
+
+        Ok((res, total))
+    }
+
+    async fn count_liked(&self, id: ContentId) -> Result<u32> {
+        let Content { liked, .. } = self.find(id).await?;
+
+        Ok(liked.len() as u32)
     }
 
     async fn is_liked(&self, id: ContentId, user_id: UserId) -> Result<bool> {
@@ -369,10 +521,80 @@ impl ContentRepository for InMemoryRepository<Content> {
         Ok(item.liked.remove(&user_id))
     }
 
-    async fn get_pinned(&self, id: ContentId) -> Result<std::collections::HashSet<UserId>> {
+    async fn insert_liked_many(
+        &self,
+        ids: Vec<ContentId>,
+        user_id: UserId,
+    ) -> Result<Vec<Option<bool>>> {
+        let mut guard = self.0.lock().await;
+
+        ids.iter()
+            .map(|id| match find_mut(&mut guard, |c| c.id == *id) {
+                Ok(item) => Ok(Some(item.liked.insert(user_id))),
+                Err(RepositoryError::NotFound) => Ok(None),
+                Err(e) => Err(e),
+            })
+            .collect()
+    }
+
+    async fn delete_liked_many(
+        &self,
+        ids: Vec<ContentId>,
+        user_id: UserId,
+    ) -> Result<Vec<Option<bool>>> {
+        let mut guard = self.0.lock().await;
+
+        ids.iter()
+            .map(|id| match find_mut(&mut guard, |c| c.id == *id) {
+                Ok(item) => Ok(Some(item.liked.remove(&user_id))),
+                Err(RepositoryError::NotFound) => Ok(None),
+                Err(e) => Err(e),
+            })
+            .collect()
+    }
+
+    async fn get_pinned(&self, id: ContentId) -> Result<Vec<UserId>> {
+        let Content { pinned, .. } = self.find(id).await?;
+
+        let mut res = pinned.into_iter().collect::<Vec<_>>();
+        res.sort();
+
+        Ok(res)
+    }
+
+    async fn pinned_page(
+        &self,
+        id: ContentId,
+        skip: u64,
+        limit: u64,
+    ) -> Result<(Vec<UserId>, u64)> {
         let Content { pinned, .. } = self.find(id).await?;
+        let total = pinned.len() as u64;
+
+        let mut all = pinned.into_iter().collect::<Vec<_>>();
+        all.sort_unstable_by_key(|i| i.0);
+
+        let res = all
+            .drain(..)
+            .skip(skip as usize)
+            .take(limit as usize)
+            .collect();
+
+        Ok((res, total))
+    }
+
+    async fn count_pinned(&self, id: ContentId) -> Result<u32> {
+        let Content { pinned, .. } = self.find(id).await?;
+
+        Ok(pinned.len() as u32)
+    }
 
-        Ok(pinned)
+    async fn top_liked(&self, n: usize) -> Result<Vec<Content>> {
+        Ok(top_by(&self.0.lock().await, n, |c| c.liked.len()))
+    }
+
+    async fn top_pinned(&self, n: usize) -> Result<Vec<Content>> {
+        Ok(top_by(&self.0.lock().await, n, |c| c.pinned.len()))
     }
 
     async fn is_pinned(&self, id: ContentId, user_id: UserId) -> Result<bool> {
@@ -400,6 +622,106 @@ impl ContentRepository for InMemoryRepository<Content> {
         Ok(item.pinned.remove(&user_id))
     }
 
+    async fn merge_reactions(&self, from: ContentId, into: ContentId) -> Result<Content> {
+        let mut guard = self.0.lock().await;
+        let from = find_ref(&guard, |c| c.id == from)?;
+        let (liked, pinned) = (from.liked.clone(), from.pinned.clone());
+        let item = find_mut(&mut guard, |c| c.id == into)?;
+
+        item.liked.extend(liked);
+        item.pinned.extend(pinned);
+
+        Ok(item.clone())
+    }
+
+    async fn insert_tag(&self, id: ContentId, tag: String) -> Result<bool> {
+        let mut guard = self.0.lock().await;
+        let item = find_mut(&mut guard, |c| c.id == id)?;
+
+        Ok(item.tags.insert(tag))
+    }
+
+    async fn delete_tag(&self, id: ContentId, tag: String) -> Result<bool> {
+        let mut guard = self.0.lock().await;
+        let item = find_mut(&mut guard, |c| c.id == id)?;
+
+        Ok(item.tags.remove(&tag))
+    }
+
+    async fn rename_virtual(&self, from: String, to: String) -> Result<u64> {
+        let mut guard = self.0.lock().await;
+
+        let mut count = 0;
+        for item in guard.iter_mut() {
+            if let Author::Virtual(name) = &mut item.author {
+                if *name == from {
+                    *name = to.clone();
+                    count += 1;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    async fn reassign_posted_by(&self, from: UserId, posted: Posted) -> Result<u64> {
+        let mut guard = self.0.lock().await;
+
+        let mut count = 0;
+        for item in guard.iter_mut() {
+            if item.posted.id == from {
+                item.posted = posted.clone();
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    async fn reassign_posted(&self, id: ContentId, posted: Posted) -> Result<Content> {
+        let mut guard = self.0.lock().await;
+        let item = find_mut(&mut guard, |c| c.id == id)?;
+
+        item.posted = posted;
+
+        Ok(item.clone())
+    }
+
+    async fn archive_posted_by(&self, from: UserId, at: Date) -> Result<u64> {
+        let mut guard = self.0.lock().await;
+
+        let mut count = 0;
+        for item in guard.iter_mut() {
+            if item.posted.id == from && !item.archived {
+                item.archived = true;
+                item.archived_at = Some(at);
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    async fn archive(&self, id: ContentId, at: Date) -> Result<Content> {
+        let mut guard = self.0.lock().await;
+        let item = find_mut(&mut guard, |c| c.id == id && !c.archived)?;
+
+        item.archived = true;
+        item.archived_at = Some(at);
+
+        Ok(item.clone())
+    }
+
+    async fn restore(&self, id: ContentId) -> Result<Content> {
+        let mut guard = self.0.lock().await;
+        let item = find_mut(&mut guard, |c| c.id == id && c.archived)?;
+
+        item.archived = false;
+        item.archived_at = None;
+
+        Ok(item.clone())
+    }
+
     async fn delete(&self, id: ContentId) -> Result<Content> {
         let mut guard = self.0.lock().await;
         let mut res = guard
@@ -419,4 +741,19 @@ impl ContentRepository for InMemoryRepository<Content> {
 
         Ok(guard.remove(index))
     }
+
+    async fn export(&self, out: &mut (dyn AsyncWrite + Unpin + Send)) -> Result<usize> {
+        let guard = self.0.lock().await;
+
+        for item in guard.iter() {
+            let mut line = serde_json::to_vec(&MongoContentModel::from(item.clone()))
+                .map_err(|e| RepositoryError::Internal(e.into()))?;
+            line.push(b'\n');
+            out.write_all(&line)
+                .await
+                .map_err(|e| RepositoryError::Internal(e.into()))?;
+        }
+
+        Ok(guard.len())
+    }
 }
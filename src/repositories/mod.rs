@@ -1,14 +1,19 @@
+use core::time::Duration;
 use std::collections::HashSet;
 
 use async_trait::async_trait;
+use tokio::io::AsyncWrite;
 
-use crate::entities::{Content, ContentId, User, UserId};
+use crate::entities::{Content, ContentId, Date, Posted, User, UserId};
 use crate::usecases::content::{ContentMutation, ContentQuery};
 use crate::usecases::user::{UserMutation, UserQuery};
 
+mod cache;
+mod filters;
 mod mock;
 mod mongo;
 
+pub use cache::CachingContentRepository;
 pub use mock::InMemoryRepository;
 pub use mongo::{MongoContentRepository, MongoUserRepository};
 
@@ -20,14 +25,31 @@ pub trait UserRepository {
     async fn is_exists(&self, id: UserId) -> Result<bool>;
 
     async fn find(&self, id: UserId) -> Result<User>;
+    /// same as `find` for each id, at once. not found ones are skipped, and
+    /// found ones are in order of `ids`.
+    async fn find_many(&self, ids: &[UserId]) -> Result<Vec<User>>;
     async fn finds(&self, query: UserQuery) -> Result<Vec<User>>;
+    /// number of users `finds` returns.
+    async fn count(&self, query: UserQuery) -> Result<u64>;
 
     async fn update(&self, id: UserId, mutation: UserMutation) -> Result<User>;
 
-    async fn get_bookmark(&self, id: UserId) -> Result<HashSet<ContentId>>;
+    /// sorted by id, so that pages are stable.
+    async fn get_bookmark(&self, id: UserId) -> Result<Vec<ContentId>>;
+    async fn count_bookmark(&self, id: UserId) -> Result<u32>;
     async fn is_bookmark(&self, id: UserId, content_id: ContentId) -> Result<bool>;
     async fn insert_bookmark(&self, id: UserId, content_id: ContentId) -> Result<bool>;
     async fn delete_bookmark(&self, id: UserId, content_id: ContentId) -> Result<bool>;
+    /// bookmarks if not yet, otherwise unbookmarks. returns whether bookmarked
+    /// after that.
+    async fn toggle_bookmark(&self, id: UserId, content_id: ContentId) -> Result<bool>;
+    async fn bookmarked_ids(&self) -> Result<HashSet<ContentId>>;
+    /// replaces `from` with `into` in bookmarks of all users, at once. users
+    /// bookmarked both keep only `into`. returns number of changed users.
+    async fn replace_bookmark(&self, from: ContentId, into: ContentId) -> Result<u64>;
+    /// users sharing bookmarks with the user (excluding itself), with number of
+    /// shared ones. sorted by it in descending order.
+    async fn similar(&self, id: UserId) -> Result<Vec<(User, u32)>>;
 
     async fn delete(&self, id: UserId) -> Result<User>;
 }
@@ -38,27 +60,124 @@ pub trait ContentRepository {
     async fn is_exists(&self, id: ContentId) -> Result<bool>;
 
     async fn find(&self, id: ContentId) -> Result<Content>;
+    async fn find_by_short_id(&self, short_id: String) -> Result<Content>;
+    /// same as `find` for each id, at once. not found ones are skipped, and
+    /// found ones are in order of `ids`.
+    async fn find_many(&self, ids: &[ContentId]) -> Result<Vec<Content>>;
     async fn finds(&self, query: ContentQuery) -> Result<Vec<Content>>;
+    /// number of contents `finds` returns.
+    async fn count(&self, query: ContentQuery) -> Result<u64>;
+    /// finds only `limit` contents after `skip`, with total number of matched
+    /// contents.
+    async fn finds_paged(
+        &self,
+        query: ContentQuery,
+        skip: u64,
+        limit: u64,
+    ) -> Result<(Vec<Content>, u64)>;
+    /// finds only `limit` contents right after `cursor` in order of (created,
+    /// id). `query.sort` is ignored. `cursor` may be archived.
+    async fn finds_after(
+        &self,
+        query: ContentQuery,
+        cursor: ContentId,
+        limit: u64,
+    ) -> Result<Vec<Content>>;
+    /// same as `finds_after`, but right before `cursor`. still in ascending
+    /// order.
+    async fn finds_before(
+        &self,
+        query: ContentQuery,
+        cursor: ContentId,
+        limit: u64,
+    ) -> Result<Vec<Content>>;
 
     async fn update(&self, id: ContentId, mutation: ContentMutation) -> Result<Content>;
-
-    async fn get_liked(&self, id: ContentId) -> Result<HashSet<UserId>>;
+    /// replaces `Author::Virtual(from)` with `Author::Virtual(to)` in all
+    /// contents, at once. returns number of changed contents.
+    async fn rename_virtual(&self, from: String, to: String) -> Result<u64>;
+    /// replaces `posted` of content. not treated as edit.
+    async fn reassign_posted(&self, id: ContentId, posted: Posted) -> Result<Content>;
+    /// replaces `posted` of all contents posted by `from`, at once. returns
+    /// number of changed contents.
+    async fn reassign_posted_by(&self, from: UserId, posted: Posted) -> Result<u64>;
+
+    /// sorted by id, so that pages are stable.
+    async fn get_liked(&self, id: ContentId) -> Result<Vec<UserId>>;
+    async fn count_liked(&self, id: ContentId) -> Result<u32>;
+    /// finds only `limit` users after `skip` (in stable order), with total
+    /// number of them. the set is not loaded entirely.
+    async fn liked_page(&self, id: ContentId, skip: u64, limit: u64) -> Result<(Vec<UserId>, u64)>;
     async fn is_liked(&self, id: ContentId, user_id: UserId) -> Result<bool>;
     async fn insert_liked(&self, id: ContentId, user_id: UserId) -> Result<bool>;
     async fn delete_liked(&self, id: ContentId, user_id: UserId) -> Result<bool>;
-
-    async fn get_pinned(&self, id: ContentId) -> Result<HashSet<UserId>>;
+    /// same as `insert_liked` for each content, at once. `None` for contents
+    /// not found.
+    async fn insert_liked_many(
+        &self,
+        ids: Vec<ContentId>,
+        user_id: UserId,
+    ) -> Result<Vec<Option<bool>>>;
+    /// same as `delete_liked` for each content, at once. `None` for contents
+    /// not found.
+    async fn delete_liked_many(
+        &self,
+        ids: Vec<ContentId>,
+        user_id: UserId,
+    ) -> Result<Vec<Option<bool>>>;
+
+    /// sorted by id, so that pages are stable.
+    async fn get_pinned(&self, id: ContentId) -> Result<Vec<UserId>>;
+    async fn count_pinned(&self, id: ContentId) -> Result<u32>;
+    /// finds only `limit` users after `skip` (in stable order), with total
+    /// number of them. the set is not loaded entirely.
+    async fn pinned_page(&self, id: ContentId, skip: u64, limit: u64)
+        -> Result<(Vec<UserId>, u64)>;
     async fn is_pinned(&self, id: ContentId, user_id: UserId) -> Result<bool>;
     async fn insert_pinned(&self, id: ContentId, user_id: UserId) -> Result<bool>;
     async fn delete_pinned(&self, id: ContentId, user_id: UserId) -> Result<bool>;
-
+    /// adds liked and pinned users of `from` to `into`, at once. users in both
+    /// are counted once. `from` is left as is. returns `into` after that.
+    async fn merge_reactions(&self, from: ContentId, into: ContentId) -> Result<Content>;
+
+    /// at most `n` contents most liked, in descending order of it (ties are
+    /// older first). archived ones are excluded.
+    async fn top_liked(&self, n: usize) -> Result<Vec<Content>>;
+    /// same as `top_liked`, by pin.
+    async fn top_pinned(&self, n: usize) -> Result<Vec<Content>>;
+
+    async fn insert_tag(&self, id: ContentId, tag: String) -> Result<bool>;
+    async fn delete_tag(&self, id: ContentId, tag: String) -> Result<bool>;
+
+    /// hides content from `find`s (and the likes), keeping it.
+    async fn archive(&self, id: ContentId, at: Date) -> Result<Content>;
+    /// `archive`s all contents posted by `from`, at once. returns number of
+    /// newly archived contents.
+    async fn archive_posted_by(&self, from: UserId, at: Date) -> Result<u64>;
+    /// reverts `archive`. `NotFound` if not archived.
+    async fn restore(&self, id: ContentId) -> Result<Content>;
     async fn delete(&self, id: ContentId) -> Result<Content>;
+
+    /// writes all contents as NDJSON (serialized as mongo model), one by one.
+    /// returns number of written contents.
+    async fn export(&self, out: &mut (dyn AsyncWrite + Unpin + Send)) -> Result<usize>;
+}
+
+#[async_trait]
+pub trait HealthRepository {
+    /// round-trip time to backend. `None` if there's no backend to reach
+    /// (in-memory).
+    async fn ping(&self) -> Result<Option<Duration>>;
 }
 
 #[derive(Debug)]
 pub enum RepositoryError {
     NotFound,
-    NoUnique { matched: u32 },
+    NoUnique {
+        matched: u32,
+    },
+    /// modified by others after read.
+    Conflict,
     Internal(anyhow::Error),
 }
 
@@ -71,6 +190,7 @@ impl ::core::fmt::Display for RepositoryError {
                 "expected unique object, found non-unique objects (matched: {})",
                 matched
             ),
+            RepositoryError::Conflict => write!(f, "conflicted with concurrent modification."),
             RepositoryError::Internal(e) => write!(f, "internal error: {}", e),
         }
     }
@@ -1,7 +1,9 @@
 use anyhow::anyhow;
+use mongodb::bson;
 use mongodb::error::Result as MongoResult;
 
 use super::{RepositoryError, Result as RepoResult};
+use crate::entities::Date;
 
 pub fn convert_repo_err<T, E>(result: Result<T, E>) -> RepoResult<T>
 where E: Sync + Send + ::std::error::Error + 'static {
@@ -42,3 +44,17 @@ where N: ::core::convert::TryInto<i8> + ::core::fmt::Debug + Clone {
         n => unreachable!("expected 0 or 1, found: {}", n),
     }
 }
+
+/// stored as date (in millis), so comparable and sortable at server side.
+/// sub-millisecond part is truncated.
+pub fn date_to_bson(date: Date) -> bson::DateTime {
+    bson::DateTime::from_millis(date.timestamp_millis())
+}
+
+pub fn bson_to_date(date: bson::DateTime) -> Date {
+    use chrono::TimeZone;
+
+    ::chrono::Utc
+        .timestamp_millis_opt(date.timestamp_millis())
+        .unwrap()
+}
@@ -1,4 +1,6 @@
-use mongodb::bson::doc;
+use core::time::Duration;
+
+use mongodb::bson::{doc, Bson, Document};
 use mongodb::error::Result as MongoResult;
 use mongodb::options::{Acknowledgment, ReadConcern, TransactionOptions, WriteConcern};
 use mongodb::{Client, ClientSession, Collection, Database};
@@ -10,18 +12,47 @@ use crate::utils::LetChain;
 
 pub async fn initialize_coll(
     coll_name: impl Into<::mongodb::bson::Bson>,
+    extra_unique_keys: &[&str],
+    text_keys: &[&str],
     db: &Database,
 ) -> MongoResult<()> {
+    let mut indexes = vec![doc! {
+        "name": "unique_id",
+        "key": {
+            "id": 1
+        },
+        "unique": true
+    }];
+
+    for field in extra_unique_keys.iter().map(|s| s.to_string()) {
+        indexes.push(doc! {
+            "name": format!("unique_{}", field),
+            "key": {
+                field: 1
+            },
+            "unique": true,
+            // allows documents created before the key was introduced.
+            "sparse": true
+        });
+    }
+
+    // only one text index is allowed per collection.
+    if !text_keys.is_empty() {
+        let mut key = doc! {};
+        for field in text_keys {
+            key.insert(*field, "text");
+        }
+
+        indexes.push(doc! {
+            "name": "text",
+            "key": key,
+        });
+    }
+
     db.run_command(
         doc! {
             "createIndexes": coll_name.into(),
-            "indexes": [{
-                "name": "unique_id",
-                "key": {
-                    "id": 1
-                },
-                "unique": true
-            }],
+            "indexes": indexes,
         },
         None,
     )
@@ -82,41 +113,166 @@ where
     }
 }
 
+/// awaits `f`, then warns with `op` and shape of `filter` if it took longer
+/// than `threshold`. `None` disables.
+pub async fn timed<F: ::core::future::Future>(
+    threshold: Option<Duration>,
+    op: &str,
+    filter: &Document,
+    f: F,
+) -> F::Output {
+    let started = ::tokio::time::Instant::now();
+    let res = f.await;
+    let elapsed = started.elapsed();
+
+    if threshold.map(|t| elapsed >= t).unwrap_or(false) {
+        tracing::warn!(
+            op,
+            elapsed_ms = elapsed.as_millis() as u64,
+            shape = %query_shape(filter),
+            "slow query"
+        );
+    }
+
+    res
+}
+
+/// `filter` with values replaced by `?`, so that the same kind of queries
+/// look the same (and values are not logged).
+fn query_shape(filter: &Document) -> Document {
+    fn value_shape(v: &Bson) -> Bson {
+        match v {
+            Bson::Document(d) => Bson::Document(query_shape(d)),
+            // e.g. operands of `$or`.
+            Bson::Array(a) if a.iter().any(|b| matches!(b, Bson::Document(_))) =>
+                Bson::Array(a.iter().map(value_shape).collect()),
+            _ => Bson::String("?".to_string()),
+        }
+    }
+
+    filter
+        .iter()
+        .map(|(k, v)| (k.clone(), value_shape(v)))
+        .collect()
+}
+
 pub async fn get_set<T>(
     coll: &Collection<T>,
     id: impl Into<::mongodb::bson::Bson>,
+    slow: Option<Duration>,
 ) -> RepoResult<T>
 where
     T: Sync + Send + Unpin + ::serde::de::DeserializeOwned,
 {
-    let res = coll
-        .find_one(doc! { "id": id.into() }, None)
-        .instrument(tracing::trace_span!("find_one"))
-        .await
-        .let_(convert_repo_err)?
-        .let_(convert_404_or)?;
+    let filter = doc! { "id": id.into() };
+    let res = timed(
+        slow,
+        "get_set",
+        &filter,
+        coll.find_one(filter.clone(), None)
+            .instrument(tracing::trace_span!("find_one")),
+    )
+    .await
+    .let_(convert_repo_err)?
+    .let_(convert_404_or)?;
 
     Ok(res)
 }
 
+/// reads only `limit` items of set after `skip` (in stored order), with cached
+/// size of the set.
+pub async fn get_set_page<T>(
+    name: impl AsRef<str>,
+    coll: &Collection<T>,
+    id: impl Into<::mongodb::bson::Bson>,
+    skip: u64,
+    limit: u64,
+    slow: Option<Duration>,
+) -> RepoResult<(Vec<::mongodb::bson::Bson>, u64)> {
+    let name = name.as_ref();
+    let size_name = format!("{}_size", name);
+    let opts = ::mongodb::options::FindOneOptions::builder()
+        .projection(doc! {
+            name: { "$slice": [skip as i64, limit as i64] },
+            &size_name: 1,
+        })
+        .build();
+    let filter = doc! { "id": id.into() };
+    let mut res = timed(
+        slow,
+        "get_set_page",
+        &filter,
+        coll.clone_with_type::<Document>()
+            .find_one(filter.clone(), opts)
+            .instrument(tracing::trace_span!("find_one")),
+    )
+    .await
+    .let_(convert_repo_err)?
+    .let_(convert_404_or)?;
+
+    let items = match res.remove(name) {
+        Some(::mongodb::bson::Bson::Array(a)) => a,
+        _ => vec![],
+    };
+    let size = res
+        .get(&size_name)
+        .and_then(::mongodb::bson::Bson::as_i64)
+        .unwrap_or(0);
+
+    Ok((items, size as u64))
+}
+
+/// reads cached size of set (`{name}_size`), without loading the set.
+pub async fn get_size<T>(
+    name: impl AsRef<str>,
+    coll: &Collection<T>,
+    id: impl Into<::mongodb::bson::Bson>,
+    slow: Option<Duration>,
+) -> RepoResult<u32> {
+    let size_name = format!("{}_size", name.as_ref());
+    let opts = ::mongodb::options::FindOneOptions::builder()
+        .projection(doc! { &size_name: 1 })
+        .build();
+    let filter = doc! { "id": id.into() };
+    let res = timed(
+        slow,
+        "get_size",
+        &filter,
+        coll.clone_with_type::<Document>()
+            .find_one(filter.clone(), opts)
+            .instrument(tracing::trace_span!("find_one")),
+    )
+    .await
+    .let_(convert_repo_err)?
+    .let_(convert_404_or)?
+    .get(&size_name)
+    .and_then(::mongodb::bson::Bson::as_i64)
+    .unwrap_or(0);
+
+    Ok(res as u32)
+}
+
 pub async fn is_contains<T>(
     name: impl AsRef<str>,
     coll: &Collection<T>,
     id: impl Into<::mongodb::bson::Bson>,
     target: impl Into<::mongodb::bson::Bson>,
+    slow: Option<Duration>,
 ) -> RepoResult<bool> {
-    let res = coll
-        .count_documents(
-            doc! {
-                "id": id.into(),
-                name.as_ref(): { "$in": [target.into()] }
-            },
-            None,
-        )
-        .instrument(tracing::trace_span!("count_documents"))
-        .await
-        .let_(convert_repo_err)?
-        .let_(to_bool);
+    let filter = doc! {
+        "id": id.into(),
+        name.as_ref(): { "$in": [target.into()] }
+    };
+    let res = timed(
+        slow,
+        "is_contains",
+        &filter,
+        coll.count_documents(filter.clone(), None)
+            .instrument(tracing::trace_span!("count_documents")),
+    )
+    .await
+    .let_(convert_repo_err)?
+    .let_(to_bool);
 
     Ok(res)
 }
@@ -127,6 +283,65 @@ pub enum ModifyOpTy {
     Pull,
 }
 
+/// modifies set (and its size) in given session. `None` if not found.
+async fn modify_set_with_session<T>(
+    name: &str,
+    coll: &Collection<T>,
+    session: &mut ClientSession,
+    id: &::mongodb::bson::Bson,
+    target: &::mongodb::bson::Bson,
+    ty: ModifyOpTy,
+) -> MongoResult<Option<bool>> {
+    let operation = match ty {
+        ModifyOpTy::Push => "$addToSet",
+        ModifyOpTy::Pull => "$pull",
+    };
+    let res = coll
+        .update_one_with_session(
+            doc! { "id": id },
+            doc! { operation: { name: target } },
+            None,
+            session,
+        )
+        .instrument(tracing::trace_span!("update_one_with_session"))
+        .await?;
+
+    if !res.matched_count.let_(to_bool) {
+        return Ok(None);
+    };
+    if !res.modified_count.let_(to_bool) {
+        return Ok(Some(false));
+    }
+
+    let inc_name = &format!("{}_size", name);
+    let inc_value = match ty {
+        ModifyOpTy::Push => 1,
+        ModifyOpTy::Pull => -1,
+    };
+    let res = coll
+        .update_one_with_session(
+            doc! { "id": id },
+            doc! { "$inc": { inc_name: inc_value } },
+            None,
+            session,
+        )
+        .instrument(tracing::trace_span!("update_one_with_session"))
+        .await?;
+
+    if !res.matched_count.let_(to_bool) {
+        unreachable!("not found value");
+    }
+    if !res.modified_count.let_(to_bool) {
+        let op = match ty {
+            ModifyOpTy::Push => "inc",
+            ModifyOpTy::Pull => "dec",
+        };
+        unreachable!("cannot {} {} field", op, inc_name);
+    }
+
+    Ok(Some(true))
+}
+
 pub async fn modify_set<T>(
     name: impl AsRef<str>,
     coll: &Collection<T>,
@@ -134,6 +349,7 @@ pub async fn modify_set<T>(
     id: impl Into<::mongodb::bson::Bson>,
     target: impl Into<::mongodb::bson::Bson>,
     ty: ModifyOpTy,
+    slow: Option<Duration>,
 ) -> RepoResult<bool> {
     async fn transaction<T>(
         name: &str,
@@ -145,63 +361,136 @@ pub async fn modify_set<T>(
     ) -> MongoResult<Option<bool>> {
         let mut session = make_session(client).await?;
 
-        let operation = match ty {
-            ModifyOpTy::Push => "$addToSet",
-            ModifyOpTy::Pull => "$pull",
-        };
-        let res = coll
-            .update_one_with_session(
-                doc! { "id": id },
-                doc! { operation: { name: target } },
-                None,
-                &mut session,
-            )
-            .instrument(tracing::trace_span!("update_one_with_session"))
-            .await?;
-
-        if !res.matched_count.let_(to_bool) {
+        let res = modify_set_with_session(name, coll, &mut session, id, target, ty).await?;
+        if res.is_none() {
             return Ok(None);
-        };
-        if !res.modified_count.let_(to_bool) {
-            return Ok(Some(false));
         }
 
-        let inc_name = &format!("{}_size", name);
-        let inc_value = match ty {
-            ModifyOpTy::Push => 1,
-            ModifyOpTy::Pull => -1,
-        };
-        let res = coll
-            .update_one_with_session(
-                doc! { "id": id },
-                doc! { "$inc": { inc_name: inc_value } },
+        process_transaction(&mut session).await.map(|_| res)
+    }
+
+    let id_bson = id.into();
+    let target_bson = target.into();
+
+    let res = timed(
+        slow,
+        "modify_set",
+        &doc! { "id": id_bson.clone() },
+        exec_transaction(
+            transaction,
+            (name.as_ref(), coll, client, &id_bson, &target_bson, ty),
+        ),
+    )
+    .await;
+    res.let_(convert_repo_err)?.let_(convert_404_or)
+}
+
+/// pushes target if not contained, otherwise pulls it. returns whether
+/// contained after that.
+pub async fn toggle_set<T>(
+    name: impl AsRef<str>,
+    coll: &Collection<T>,
+    client: &Client,
+    id: impl Into<::mongodb::bson::Bson>,
+    target: impl Into<::mongodb::bson::Bson>,
+    slow: Option<Duration>,
+) -> RepoResult<bool> {
+    async fn transaction<T>(
+        name: &str,
+        coll: &Collection<T>,
+        client: &Client,
+        id: &::mongodb::bson::Bson,
+        target: &::mongodb::bson::Bson,
+    ) -> MongoResult<Option<bool>> {
+        let mut session = make_session(client).await?;
+
+        let contains = coll
+            .count_documents_with_session(
+                doc! { "id": id, name: { "$in": [target] } },
                 None,
                 &mut session,
             )
-            .instrument(tracing::trace_span!("update_one_with_session"))
-            .await?;
+            .instrument(tracing::trace_span!("count_documents_with_session"))
+            .await?
+            .let_(to_bool);
+        let ty = match contains {
+            true => ModifyOpTy::Pull,
+            false => ModifyOpTy::Push,
+        };
 
-        if !res.matched_count.let_(to_bool) {
-            unreachable!("not found value");
-        }
-        if !res.modified_count.let_(to_bool) {
-            let op = match ty {
-                ModifyOpTy::Push => "inc",
-                ModifyOpTy::Pull => "dec",
-            };
-            unreachable!("cannot {} {} field", op, inc_name);
+        let res = modify_set_with_session(name, coll, &mut session, id, target, ty).await?;
+        if res.is_none() {
+            return Ok(None);
         }
 
-        process_transaction(&mut session).await.map(|_| Some(true))
+        process_transaction(&mut session)
+            .await
+            .map(|_| Some(!contains))
     }
 
     let id_bson = id.into();
     let target_bson = target.into();
 
-    let res = exec_transaction(
-        transaction,
-        (name.as_ref(), coll, client, &id_bson, &target_bson, ty),
+    let res = timed(
+        slow,
+        "toggle_set",
+        &doc! { "id": id_bson.clone() },
+        exec_transaction(
+            transaction,
+            (name.as_ref(), coll, client, &id_bson, &target_bson),
+        ),
     )
     .await;
     res.let_(convert_repo_err)?.let_(convert_404_or)
 }
+
+/// same as `modify_set` for each id, in a transaction. `None` for ids not
+/// found (doesn't abort others).
+pub async fn modify_set_many<T>(
+    name: impl AsRef<str>,
+    coll: &Collection<T>,
+    client: &Client,
+    ids: Vec<::mongodb::bson::Bson>,
+    target: impl Into<::mongodb::bson::Bson>,
+    ty: ModifyOpTy,
+    slow: Option<Duration>,
+) -> RepoResult<Vec<Option<bool>>> {
+    async fn transaction<T>(
+        name: &str,
+        coll: &Collection<T>,
+        client: &Client,
+        ids: &[::mongodb::bson::Bson],
+        target: &::mongodb::bson::Bson,
+        ty: ModifyOpTy,
+    ) -> MongoResult<Vec<Option<bool>>> {
+        let mut session = make_session(client).await?;
+
+        let mut res = vec![];
+        for id in ids {
+            res.push(modify_set_with_session(name, coll, &mut session, id, target, ty).await?);
+        }
+
+        process_transaction(&mut session).await.map(|_| res)
+    }
+
+    let target_bson = target.into();
+
+    timed(
+        slow,
+        "modify_set_many",
+        &doc! { "id": { "$in": ids.clone() } },
+        exec_transaction(
+            transaction,
+            (
+                name.as_ref(),
+                coll,
+                client,
+                ids.as_slice(),
+                &target_bson,
+                ty,
+            ),
+        ),
+    )
+    .await
+    .let_(convert_repo_err)
+}
@@ -1,122 +1,417 @@
-use core::ops::Bound;
-use std::collections::HashSet;
+use core::time::Duration;
+use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
-use mongodb::bson::{doc, Document};
+use mongodb::bson::{doc, Bson, Document};
 use mongodb::{bson, Client, Collection, Database};
 use serenity::futures::TryStreamExt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tracing::Instrument;
 
-use super::{ContentRepository, RepositoryError, Result, UserRepository};
-use crate::entities::{Author, Content, ContentId, User, UserId};
-use crate::usecases::content::{
-    AuthorQuery, ContentContentMutation, ContentMutation, ContentQuery, PostedQuery,
-};
+use super::filters::{cursor_key, filter_archived, filter_content, page_by_cursor, sort_contents};
+use super::{ContentRepository, HealthRepository, RepositoryError, Result, UserRepository};
+use crate::entities::{Content, ContentId, Date, Posted, User, UserId};
+use crate::usecases::content::{ContentMutation, ContentQuery};
 use crate::usecases::user::{UserMutation, UserQuery};
-use crate::utils::{self, LetChain};
+use crate::utils::LetChain;
 
 mod converters;
 mod helpers;
-mod models;
+pub(super) mod models;
 mod type_convert;
 
 use converters::*;
 use helpers::*;
 use models::*;
+use type_convert::{is_fully_translated, legacy_dates_set, root_query_doc, sort_doc};
 
 pub struct MongoUserRepository {
     client: Client,
     coll: Collection<MongoUserModel>,
+    /// operations taking longer than this are warned. (see `timed`)
+    slow_query: Option<Duration>,
 }
 
 impl MongoUserRepository {
-    pub async fn new_with(client: Client, db: Database) -> ::anyhow::Result<Self> {
-        initialize_coll("user", &db)
+    pub async fn new_with(
+        client: Client,
+        db: Database,
+        slow_query: Option<Duration>,
+    ) -> ::anyhow::Result<Self> {
+        initialize_coll("user", &[], &[], &db)
             .await
             .map_err(::anyhow::Error::new)?;
 
         let coll = db.collection("user");
 
-        Ok(Self { client, coll })
+        Ok(Self {
+            client,
+            coll,
+            slow_query,
+        })
     }
 }
 
 pub struct MongoContentRepository {
     client: Client,
     coll: Collection<MongoContentModel>,
+    /// same as [`MongoUserRepository`]'s.
+    slow_query: Option<Duration>,
 }
 
 impl MongoContentRepository {
-    pub async fn new_with(client: Client, db: Database) -> ::anyhow::Result<Self> {
-        initialize_coll("content", &db)
+    pub async fn new_with(
+        client: Client,
+        db: Database,
+        slow_query: Option<Duration>,
+    ) -> ::anyhow::Result<Self> {
+        initialize_coll("content", &["short_id"], &["content"], &db)
             .await
             .map_err(::anyhow::Error::new)?;
 
+        let migrated = Self::migrate_dates(&db)
+            .await
+            .map_err(::anyhow::Error::new)?;
+        if migrated > 0 {
+            tracing::info!("migrated dates - {} contents", migrated);
+        }
+
+        Ok(Self::open(client, db, slow_query))
+    }
+
+    /// opens collection as is. (neither indexes nor migrations are applied)
+    pub fn open(client: Client, db: Database, slow_query: Option<Duration>) -> Self {
         let coll = db.collection("content");
 
-        Ok(Self { client, coll })
+        Self {
+            client,
+            coll,
+            slow_query,
+        }
+    }
+
+    /// converts dates stored as string by older versions into date. safe to
+    /// run repeatedly (converted ones are not matched). returns number of
+    /// converted contents.
+    async fn migrate_dates(db: &Database) -> ::mongodb::error::Result<u64> {
+        let raw = db.collection::<Document>("content");
+
+        let mut cursor = raw
+            .find(
+                doc! { "$or": [
+                    { "created": { "$type": "string" } },
+                    { "edited": { "$type": "string" } },
+                    { "edit_history.replaced": { "$type": "string" } },
+                    { "archived_at": { "$type": "string" } },
+                ] },
+                None,
+            )
+            .instrument(tracing::trace_span!("find"))
+            .await?;
+
+        let mut count = 0;
+        while let Some(d) = cursor.try_next().await? {
+            let set = match legacy_dates_set(&d) {
+                Some(s) => s,
+                None => continue,
+            };
+
+            raw.update_one(
+                doc! { "_id": d.get("_id").cloned() },
+                doc! { "$set": set },
+                None,
+            )
+            .instrument(tracing::trace_span!("update_one"))
+            .await?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// at most `n` not archived contents in descending order of cached size of
+    /// set (`{name}_size`), ties are older first.
+    async fn top_by_size(&self, name: &str, n: usize) -> Result<Vec<Content>> {
+        let options = ::mongodb::options::FindOptions::builder()
+            .sort(doc! { format!("{}_size", name): -1, "created": 1 })
+            .limit(n as i64)
+            .build();
+
+        let filter = doc! { "archived": { "$ne": true } };
+        let res = timed(self.slow_query, "content.top_by_size", &filter, async {
+            self.coll
+                .find(filter.clone(), options)
+                .instrument(tracing::trace_span!("find"))
+                .await?
+                .try_collect::<Vec<_>>()
+                .instrument(tracing::trace_span!("try_collect"))
+                .await
+        })
+        .await
+        .let_(convert_repo_err)?
+        .drain(..)
+        .map::<Content, _>(|m| m.into())
+        .collect();
+
+        Ok(res)
+    }
+
+    /// sets `set` to the content if it matches `filter`, in a transaction.
+    async fn set_archived(
+        &self,
+        id: ContentId,
+        filter: Document,
+        set: Document,
+    ) -> Result<Content> {
+        async fn transaction(
+            this: &MongoContentRepository,
+            id: ContentId,
+            mut filter: Document,
+            set: Document,
+        ) -> ::mongodb::error::Result<Option<Content>> {
+            let mut session = make_session(&this.client).await?;
+
+            filter.insert("id", id);
+            let res = this
+                .coll
+                .update_one_with_session(filter, doc! { "$set": set }, None, &mut session)
+                .instrument(tracing::trace_span!("update_one_with_session"))
+                .await?;
+
+            if res.matched_count == 0 {
+                return Ok(None);
+            }
+
+            let content = this
+                .coll
+                .find_one_with_session(doc! { "id": id }, None, &mut session)
+                .instrument(tracing::trace_span!("find_one_with_session"))
+                .await?
+                .unwrap()
+                .into();
+
+            process_transaction(&mut session)
+                .await
+                .map(|_| Some(content))
+        }
+
+        let res = timed(
+            self.slow_query,
+            "content.set_archived",
+            &doc! { "id": id },
+            exec_transaction(transaction, (self, id, filter, set)),
+        )
+        .await;
+        Ok(res.let_(convert_repo_err)?.let_(convert_404_or)?)
+    }
+
+    /// pages on server if the query is fully translated, otherwise on client.
+    async fn finds_by_cursor(
+        &self,
+        query: ContentQuery,
+        cursor: ContentId,
+        after: bool,
+        limit: u64,
+    ) -> Result<Vec<Content>> {
+        // archived one is also a valid position.
+        let pivot_filter = doc! { "id": cursor };
+        let pivot: Content = timed(
+            self.slow_query,
+            "content.find",
+            &pivot_filter,
+            self.coll
+                .find_one(pivot_filter.clone(), None)
+                .instrument(tracing::trace_span!("find_one")),
+        )
+        .await
+        .let_(convert_repo_err)?
+        .let_(convert_404_or)?
+        .into();
+
+        if !is_fully_translated(&query) {
+            let all = self.finds(query).await?;
+            return Ok(page_by_cursor(
+                all,
+                cursor_key(&pivot),
+                after,
+                limit as usize,
+            ));
+        }
+
+        let (op, order) = match after {
+            true => ("$gt", 1),
+            false => ("$lt", -1),
+        };
+        let created = date_to_bson(pivot.created);
+        // ids are stored as hyphenated lowercase, so ordered same as `ContentId`.
+        let filter = doc! {
+            "$and": [
+                root_query_doc(&query),
+                { "$or": [
+                    { "created": { op: created } },
+                    { "created": created, "id": { op: cursor } },
+                ] },
+            ],
+        };
+        let options = ::mongodb::options::FindOptions::builder()
+            .limit(limit as i64)
+            .sort(doc! { "created": order, "id": order })
+            .build();
+
+        let mut res = timed(self.slow_query, "content.finds_by_cursor", &filter, async {
+            self.coll
+                .find(filter.clone(), options)
+                .instrument(tracing::trace_span!("find"))
+                .await?
+                .try_collect::<Vec<_>>()
+                .instrument(tracing::trace_span!("try_collect"))
+                .await
+        })
+        .await
+        .let_(convert_repo_err)?
+        .drain(..)
+        .map::<Content, _>(|m| m.into())
+        .collect::<Vec<_>>();
+
+        // fetched from the nearest one.
+        if !after {
+            res.reverse();
+        }
+
+        Ok(res)
+    }
+}
+
+#[async_trait]
+impl HealthRepository for MongoUserRepository {
+    async fn ping(&self) -> Result<Option<::core::time::Duration>> {
+        let start = ::std::time::Instant::now();
+
+        self.client
+            .database("admin")
+            .run_command(doc! { "ping": 1 }, None)
+            .instrument(tracing::trace_span!("run_command"))
+            .await
+            .let_(convert_repo_err)?;
+
+        Ok(Some(start.elapsed()))
     }
 }
 
 #[async_trait]
 impl UserRepository for MongoUserRepository {
     async fn insert(&self, user: User) -> Result<bool> {
+        let filter = doc! { "id": user.id };
         let model: MongoUserModel = user.into();
 
         tracing::trace!("insert - {:?}", model);
 
-        let res = self
-            .coll
-            .insert_one(model, None)
-            .instrument(tracing::trace_span!("insert_one"))
-            .await
-            .let_(try_unique_check)?;
+        let res = timed(
+            self.slow_query,
+            "user.insert",
+            &filter,
+            self.coll
+                .insert_one(model, None)
+                .instrument(tracing::trace_span!("insert_one")),
+        )
+        .await
+        .let_(try_unique_check)?;
 
         Ok(res)
     }
 
     async fn is_exists(&self, id: UserId) -> Result<bool> {
-        let res = self
-            .coll
-            .count_documents(doc! { "id": id }, None)
-            .instrument(tracing::trace_span!("count_documents"))
-            .await
-            .let_(convert_repo_err)?
-            .let_(to_bool);
+        let filter = doc! { "id": id };
+        let res = timed(
+            self.slow_query,
+            "user.is_exists",
+            &filter,
+            self.coll
+                .count_documents(filter.clone(), None)
+                .instrument(tracing::trace_span!("count_documents")),
+        )
+        .await
+        .let_(convert_repo_err)?
+        .let_(to_bool);
 
         Ok(res)
     }
 
     async fn find(&self, id: UserId) -> Result<User> {
-        let user: User = self
-            .coll
-            .find_one(doc! { "id": id }, None)
-            .instrument(tracing::trace_span!("find_one"))
-            .await
-            .let_(convert_repo_err)?
-            .let_(convert_404_or)?
-            .into();
+        let filter = doc! { "id": id };
+        let user: User = timed(
+            self.slow_query,
+            "user.find",
+            &filter,
+            self.coll
+                .find_one(filter.clone(), None)
+                .instrument(tracing::trace_span!("find_one")),
+        )
+        .await
+        .let_(convert_repo_err)?
+        .let_(convert_404_or)?
+        .into();
         assert_eq!(user.id, id, "not matched id!");
 
         Ok(user)
     }
 
+    async fn find_many(&self, ids: &[UserId]) -> Result<Vec<User>> {
+        let filter = doc! { "id": { "$in": ids.to_vec() } };
+        let mut found = timed(self.slow_query, "user.find_many", &filter, async {
+            self.coll
+                .find(filter.clone(), None)
+                .instrument(tracing::trace_span!("find"))
+                .await?
+                .try_collect::<Vec<_>>()
+                .instrument(tracing::trace_span!("try_collect"))
+                .await
+        })
+        .await
+        .let_(convert_repo_err)?
+        .drain(..)
+        .map::<User, _>(|m| m.into())
+        .map(|u| (u.id, u))
+        .collect::<HashMap<_, _>>();
+
+        // `$in` doesn't keep order.
+        Ok(ids.iter().filter_map(|id| found.remove(id)).collect())
+    }
+
     async fn finds(&self, query: UserQuery) -> Result<Vec<User>> {
         let query_doc: Document = query.into();
 
-        let res = self
-            .coll
-            .find(query_doc, None)
-            .instrument(tracing::trace_span!("find"))
-            .await
-            .let_(convert_repo_err)?
-            .try_collect::<Vec<_>>()
-            .instrument(tracing::trace_span!("try_collect"))
-            .await
-            .let_(convert_repo_err)?
-            .drain(..)
-            .map(|m| m.into())
-            .collect();
+        let res = timed(self.slow_query, "user.finds", &query_doc, async {
+            self.coll
+                .find(query_doc.clone(), None)
+                .instrument(tracing::trace_span!("find"))
+                .await?
+                .try_collect::<Vec<_>>()
+                .instrument(tracing::trace_span!("try_collect"))
+                .await
+        })
+        .await
+        .let_(convert_repo_err)?
+        .drain(..)
+        .map(|m| m.into())
+        .collect();
+
+        Ok(res)
+    }
+
+    async fn count(&self, query: UserQuery) -> Result<u64> {
+        let query_doc: Document = query.into();
+
+        let res = timed(
+            self.slow_query,
+            "user.count",
+            &query_doc,
+            self.coll
+                .count_documents(query_doc.clone(), None)
+                .instrument(tracing::trace_span!("count_documents")),
+        )
+        .await
+        .let_(convert_repo_err)?;
 
         Ok(res)
     }
@@ -160,33 +455,49 @@ impl UserRepository for MongoUserRepository {
             process_transaction(&mut session).await.map(|_| Some(user))
         }
 
-        let res = exec_transaction(transaction, (self, id, mutation_doc)).await;
+        let res = timed(
+            self.slow_query,
+            "user.update",
+            &doc! { "id": id },
+            exec_transaction(transaction, (self, id, mutation_doc)),
+        )
+        .await;
         Ok(res.let_(convert_repo_err)?.let_(convert_404_or)?)
     }
 
-    async fn get_bookmark(&self, id: UserId) -> Result<HashSet<ContentId>> {
+    async fn get_bookmark(&self, id: UserId) -> Result<Vec<ContentId>> {
         #[derive(::serde::Deserialize)]
         struct Model {
             bookmark: HashSet<String>,
         }
 
-        let res = get_set(&self.coll.clone_with_type::<Model>(), id.to_string())
-            .await?
-            .bookmark
-            .drain()
-            .map(|s| s.parse::<::uuid::Uuid>().unwrap())
-            .map(ContentId)
-            .collect();
+        let mut res = get_set(
+            &self.coll.clone_with_type::<Model>(),
+            id.to_string(),
+            self.slow_query,
+        )
+        .await?
+        .bookmark
+        .drain()
+        .map(|s| s.parse::<::uuid::Uuid>().unwrap())
+        .map(ContentId)
+        .collect::<Vec<_>>();
+        res.sort();
 
         Ok(res)
     }
 
+    async fn count_bookmark(&self, id: UserId) -> Result<u32> {
+        get_size("bookmark", &self.coll, id.to_string(), self.slow_query).await
+    }
+
     async fn is_bookmark(&self, id: UserId, content_id: ContentId) -> Result<bool> {
         is_contains(
             "bookmark",
             &self.coll,
             id.to_string(),
             content_id.to_string(),
+            self.slow_query,
         )
         .await
     }
@@ -199,6 +510,7 @@ impl UserRepository for MongoUserRepository {
             id.to_string(),
             content_id.to_string(),
             ModifyOpTy::Push,
+            self.slow_query,
         )
         .await
     }
@@ -211,10 +523,140 @@ impl UserRepository for MongoUserRepository {
             id.to_string(),
             content_id.to_string(),
             ModifyOpTy::Pull,
+            self.slow_query,
+        )
+        .await
+    }
+
+    async fn toggle_bookmark(&self, id: UserId, content_id: ContentId) -> Result<bool> {
+        toggle_set(
+            "bookmark",
+            &self.coll,
+            &self.client,
+            id.to_string(),
+            content_id.to_string(),
+            self.slow_query,
         )
         .await
     }
 
+    async fn replace_bookmark(&self, from: ContentId, into: ContentId) -> Result<u64> {
+        async fn transaction(
+            this: &MongoUserRepository,
+            from: ContentId,
+            into: ContentId,
+        ) -> ::mongodb::error::Result<u64> {
+            let mut session = make_session(&this.client).await?;
+
+            // bookmarked both, so only drops `from`.
+            let dropped = this
+                .coll
+                .update_many_with_session(
+                    doc! { "$and": [{ "bookmark": from }, { "bookmark": into }] },
+                    doc! { "$pull": { "bookmark": from }, "$inc": { "bookmark_size": -1 } },
+                    None,
+                    &mut session,
+                )
+                .instrument(tracing::trace_span!("update_many_with_session"))
+                .await?;
+
+            // the rest, so size is unchanged.
+            let replaced = this
+                .coll
+                .update_many_with_session(
+                    doc! { "bookmark": from },
+                    doc! { "$set": { "bookmark.$": into } },
+                    None,
+                    &mut session,
+                )
+                .instrument(tracing::trace_span!("update_many_with_session"))
+                .await?;
+
+            process_transaction(&mut session)
+                .await
+                .map(|_| dropped.modified_count + replaced.modified_count)
+        }
+
+        let res = timed(
+            self.slow_query,
+            "user.replace_bookmark",
+            &doc! { "bookmark": from },
+            exec_transaction(transaction, (self, from, into)),
+        )
+        .await;
+        res.let_(convert_repo_err)
+    }
+
+    async fn bookmarked_ids(&self) -> Result<HashSet<ContentId>> {
+        let res = timed(
+            self.slow_query,
+            "user.bookmarked_ids",
+            &doc! {},
+            self.coll
+                .distinct("bookmark", None, None)
+                .instrument(tracing::trace_span!("distinct")),
+        )
+        .await
+        .let_(convert_repo_err)?
+        .drain(..)
+        .map(|b| match b {
+            bson::Bson::String(s) => s.parse().map(ContentId).unwrap(),
+            b => unreachable!("expected string, found: {:?}", b),
+        })
+        .collect();
+
+        Ok(res)
+    }
+
+    async fn similar(&self, id: UserId) -> Result<Vec<(User, u32)>> {
+        let filter = doc! { "id": id };
+        let target: User = timed(
+            self.slow_query,
+            "user.find",
+            &filter,
+            self.coll
+                .find_one(filter.clone(), None)
+                .instrument(tracing::trace_span!("find_one")),
+        )
+        .await
+        .let_(convert_repo_err)?
+        .let_(convert_404_or)?
+        .into();
+        let bookmark = target.bookmark.into_iter().collect::<Vec<_>>();
+
+        let filter = doc! { "id": { "$ne": id }, "bookmark": { "$in": bookmark.clone() } };
+        let pipeline = [
+            doc! { "$match": filter.clone() },
+            doc! {
+                "$addFields": {
+                    "shared": { "$size": { "$setIntersection": ["$bookmark", bookmark] } }
+                }
+            },
+            doc! { "$sort": { "shared": -1, "id": 1 } },
+        ];
+
+        let res = timed(self.slow_query, "user.similar", &filter, async {
+            self.coll
+                .aggregate(pipeline, None)
+                .instrument(tracing::trace_span!("aggregate"))
+                .await?
+                .try_collect::<Vec<_>>()
+                .instrument(tracing::trace_span!("try_collect"))
+                .await
+        })
+        .await
+        .let_(convert_repo_err)?
+        .drain(..)
+        .map(|d| {
+            let shared = d.get_i32("shared").unwrap() as u32;
+            let user: User = bson::from_document::<MongoUserModel>(d).unwrap().into();
+            (user, shared)
+        })
+        .collect();
+
+        Ok(res)
+    }
+
     async fn delete(&self, id: UserId) -> Result<User> {
         async fn transaction(
             this: &MongoUserRepository,
@@ -248,7 +690,13 @@ impl UserRepository for MongoUserRepository {
             process_transaction(&mut session).await.map(|_| Some(user))
         }
 
-        let res = exec_transaction(transaction, (self, id)).await;
+        let res = timed(
+            self.slow_query,
+            "user.delete",
+            &doc! { "id": id },
+            exec_transaction(transaction, (self, id)),
+        )
+        .await;
         Ok(res.let_(convert_repo_err)?.let_(convert_404_or)?)
     }
 }
@@ -256,179 +704,223 @@ impl UserRepository for MongoUserRepository {
 #[async_trait]
 impl ContentRepository for MongoContentRepository {
     async fn insert(&self, content: Content) -> Result<bool> {
+        let filter = doc! { "id": content.id };
         let model: MongoContentModel = content.into();
 
-        let res = self
-            .coll
-            .insert_one(model, None)
-            .instrument(tracing::trace_span!("insert_one"))
-            .await
-            .let_(try_unique_check)?;
+        let res = timed(
+            self.slow_query,
+            "content.insert",
+            &filter,
+            self.coll
+                .insert_one(model, None)
+                .instrument(tracing::trace_span!("insert_one")),
+        )
+        .await
+        .let_(try_unique_check)?;
 
         Ok(res)
     }
 
     async fn is_exists(&self, id: ContentId) -> Result<bool> {
-        let res = self
-            .coll
-            .count_documents(doc! { "id": id }, None)
-            .instrument(tracing::trace_span!("count_documents"))
-            .await
-            .let_(convert_repo_err)?
-            .let_(to_bool);
+        let filter = doc! { "id": id, "archived": { "$ne": true } };
+        let res = timed(
+            self.slow_query,
+            "content.is_exists",
+            &filter,
+            self.coll
+                .count_documents(filter.clone(), None)
+                .instrument(tracing::trace_span!("count_documents")),
+        )
+        .await
+        .let_(convert_repo_err)?
+        .let_(to_bool);
 
         Ok(res)
     }
 
     async fn find(&self, id: ContentId) -> Result<Content> {
-        let content: Content = self
-            .coll
-            .find_one(doc! { "id": id }, None)
-            .instrument(tracing::trace_span!("find_one"))
-            .await
-            .let_(convert_repo_err)?
-            .let_(convert_404_or)?
-            .into();
+        let filter = doc! { "id": id, "archived": { "$ne": true } };
+        let content: Content = timed(
+            self.slow_query,
+            "content.find",
+            &filter,
+            self.coll
+                .find_one(filter.clone(), None)
+                .instrument(tracing::trace_span!("find_one")),
+        )
+        .await
+        .let_(convert_repo_err)?
+        .let_(convert_404_or)?
+        .into();
         assert_eq!(content.id, id, "not matched id!");
 
         Ok(content)
     }
 
-    async fn finds(
-        &self,
-        ContentQuery {
-            author,
-            posted,
-            content,
-            liked,
-            liked_num,
-            pinned,
-            pinned_num,
-        }: ContentQuery,
-    ) -> Result<Vec<Content>> {
-        let query_doc = {
-            let mut doc = doc! {};
+    async fn find_by_short_id(&self, short_id: String) -> Result<Content> {
+        let filter = doc! { "short_id": short_id.as_str(), "archived": { "$ne": true } };
+        let content: Content = timed(
+            self.slow_query,
+            "content.find_by_short_id",
+            &filter,
+            self.coll
+                .find_one(filter.clone(), None)
+                .instrument(tracing::trace_span!("find_one")),
+        )
+        .await
+        .let_(convert_repo_err)?
+        .let_(convert_404_or)?
+        .into();
+        assert_eq!(content.short_id, short_id, "not matched short_id!");
 
-            if let Some(mut set) = liked {
-                if !set.is_empty() {
-                    doc.insert("liked", doc! { "$in": set.drain().collect::<Vec<_>>() });
-                }
-            }
+        Ok(content)
+    }
 
-            if let Some((g, l)) = liked_num {
-                let mut num_q = doc! {};
+    async fn find_many(&self, ids: &[ContentId]) -> Result<Vec<Content>> {
+        let filter = doc! { "id": { "$in": ids.to_vec() }, "archived": { "$ne": true } };
+        let mut found = timed(self.slow_query, "content.find_many", &filter, async {
+            self.coll
+                .find(filter.clone(), None)
+                .instrument(tracing::trace_span!("find"))
+                .await?
+                .try_collect::<Vec<_>>()
+                .instrument(tracing::trace_span!("try_collect"))
+                .await
+        })
+        .await
+        .let_(convert_repo_err)?
+        .drain(..)
+        .map::<Content, _>(|m| m.into())
+        .map(|c| (c.id, c))
+        .collect::<HashMap<_, _>>();
+
+        // `$in` doesn't keep order.
+        Ok(ids.iter().filter_map(|id| found.remove(id)).collect())
+    }
 
-                match g {
-                    Bound::Unbounded => (),
-                    Bound::Included(n) => num_q.insert("$gte", n).let_(::core::mem::drop),
-                    Bound::Excluded(n) => num_q.insert("$gt", n).let_(::core::mem::drop),
-                }
+    async fn finds(&self, query: ContentQuery) -> Result<Vec<Content>> {
+        let query_doc = root_query_doc(&query);
 
-                match l {
-                    Bound::Unbounded => (),
-                    Bound::Included(n) => num_q.insert("$lte", n).let_(::core::mem::drop),
-                    Bound::Excluded(n) => num_q.insert("$lt", n).let_(::core::mem::drop),
-                }
+        let mut res = timed(self.slow_query, "content.finds", &query_doc, async {
+            self.coll
+                .find(query_doc.clone(), None)
+                .instrument(tracing::trace_span!("find"))
+                .await?
+                .try_collect::<Vec<_>>()
+                .instrument(tracing::trace_span!("try_collect"))
+                .await
+        })
+        .await
+        .let_(convert_repo_err)?
+        .drain(..)
+            .map::<Content, _>(|m| m.into())
+            // server-side query only narrows candidates (regex and the likes are not translated),
+            // so evaluating the whole query here.
+            .filter(|c| filter_content(&query, c) && filter_archived(&query, c))
+            .collect::<Vec<_>>();
 
-                if !num_q.is_empty() {
-                    doc.insert("liked_size", num_q);
-                }
-            }
+        // candidates are filtered at client side, so sorting here too.
+        if let Some(s) = query.sort {
+            sort_contents(&mut res, s);
+        }
 
-            if let Some(mut set) = pinned {
-                if !set.is_empty() {
-                    doc.insert("pinned", doc! { "$in": set.drain().collect::<Vec<_>>() });
-                }
-            }
+        Ok(res)
+    }
 
-            if let Some((g, l)) = pinned_num {
-                let mut num_q = doc! {};
+    async fn count(&self, query: ContentQuery) -> Result<u64> {
+        // evaluating at client side needs all candidates anyway.
+        if !is_fully_translated(&query) {
+            return Ok(self.finds(query).await?.len() as u64);
+        }
 
-                match g {
-                    Bound::Unbounded => (),
-                    Bound::Included(n) => num_q.insert("$gte", n).let_(::core::mem::drop),
-                    Bound::Excluded(n) => num_q.insert("$gt", n).let_(::core::mem::drop),
-                }
+        let query_doc = root_query_doc(&query);
 
-                match l {
-                    Bound::Unbounded => (),
-                    Bound::Included(n) => num_q.insert("$lte", n).let_(::core::mem::drop),
-                    Bound::Excluded(n) => num_q.insert("$lt", n).let_(::core::mem::drop),
-                }
+        let res = timed(
+            self.slow_query,
+            "content.count",
+            &query_doc,
+            self.coll
+                .count_documents(query_doc.clone(), None)
+                .instrument(tracing::trace_span!("count_documents")),
+        )
+        .await
+        .let_(convert_repo_err)?;
 
-                if !num_q.is_empty() {
-                    doc.insert("pinned_size", num_q);
-                }
-            }
+        Ok(res)
+    }
 
-            doc
-        };
+    async fn finds_paged(
+        &self,
+        query: ContentQuery,
+        skip: u64,
+        limit: u64,
+    ) -> Result<(Vec<Content>, u64)> {
+        // evaluating at client side needs all candidates anyway.
+        if !is_fully_translated(&query) {
+            let mut all = self.finds(query).await?;
+            let total = all.len() as u64;
+            let res = all
+                .drain(..)
+                .skip(skip as usize)
+                .take(limit as usize)
+                .collect();
+
+            return Ok((res, total));
+        }
 
-        let mut tmp_res = self
-            .coll
-            .find(query_doc, None)
-            .instrument(tracing::trace_span!("find"))
-            .await
-            .let_(convert_repo_err)?
-            .try_collect::<Vec<_>>()
-            .instrument(tracing::trace_span!("try_collect"))
-            .await
-            .let_(convert_repo_err)?
-            .drain(..)
-            .map::<Content, _>(|m| m.into())
-            .collect::<Vec<_>>();
+        let query_doc = root_query_doc(&query);
 
-        let res = tmp_res
-            .drain(..)
-            .filter(|c| match &author {
-                Some(AuthorQuery::UserId(id_q)) => match &c.author {
-                    Author::User { id, .. } => id_q == id,
-                    _ => false,
-                },
-                Some(AuthorQuery::UserName(name_q)) => match &c.author {
-                    Author::User { name, .. } => name_q.is_match(name.as_str()),
-                    _ => false,
-                },
-                Some(AuthorQuery::UserNick(nick_q)) => match &c.author {
-                    Author::User { nick, .. } =>
-                        nick.as_ref().map_or(false, |s| nick_q.is_match(s.as_str())),
-                    _ => false,
-                },
-                Some(AuthorQuery::Virtual(name_q)) => match &c.author {
-                    Author::Virtual(name) => name_q.is_match(name.as_str()),
-                    _ => false,
-                },
-                Some(AuthorQuery::Any(any_q)) => match &c.author {
-                    Author::User { name, nick, .. } =>
-                        any_q.is_match(name.as_str())
-                            || nick.as_ref().map_or(false, |s| any_q.is_match(s.as_str())),
-                    Author::Virtual(name) => any_q.is_match(name.as_str()),
-                },
-                None => true,
-            })
-            .filter(|c| match &posted {
-                Some(PostedQuery::UserId(id_q)) => &c.posted.id == id_q,
-                Some(PostedQuery::UserName(name_q)) => name_q.is_match(c.posted.name.as_str()),
-                Some(PostedQuery::UserNick(nick_q)) => c
-                    .posted
-                    .nick
-                    .as_ref()
-                    .map_or(false, |s| nick_q.is_match(s.as_str())),
-                Some(PostedQuery::Any(any_q)) =>
-                    any_q.is_match(c.posted.name.as_str())
-                        || c.posted
-                            .nick
-                            .as_ref()
-                            .map_or(false, |s| any_q.is_match(s.as_str())),
-                None => true,
-            })
-            .filter(|c| match &content {
-                Some(content_q) => content_q.is_match(c.content.as_str()),
-                None => true,
-            })
-            .collect();
+        let total = timed(
+            self.slow_query,
+            "content.finds_paged",
+            &query_doc,
+            self.coll
+                .count_documents(query_doc.clone(), None)
+                .instrument(tracing::trace_span!("count_documents")),
+        )
+        .await
+        .let_(convert_repo_err)?;
+
+        let options = ::mongodb::options::FindOptions::builder()
+            .skip(skip)
+            .limit(limit as i64)
+            .sort(query.sort.and_then(sort_doc))
+            .build();
+
+        let res = timed(self.slow_query, "content.finds_paged", &query_doc, async {
+            self.coll
+                .find(query_doc.clone(), options)
+                .instrument(tracing::trace_span!("find"))
+                .await?
+                .try_collect::<Vec<_>>()
+                .instrument(tracing::trace_span!("try_collect"))
+                .await
+        })
+        .await
+        .let_(convert_repo_err)?
+        .drain(..)
+        .map::<Content, _>(|m| m.into())
+        .collect();
 
-        Ok(res)
+        Ok((res, total))
+    }
+
+    async fn finds_after(
+        &self,
+        query: ContentQuery,
+        cursor: ContentId,
+        limit: u64,
+    ) -> Result<Vec<Content>> {
+        self.finds_by_cursor(query, cursor, true, limit).await
+    }
+
+    async fn finds_before(
+        &self,
+        query: ContentQuery,
+        cursor: ContentId,
+        limit: u64,
+    ) -> Result<Vec<Content>> {
+        self.finds_by_cursor(query, cursor, false, limit).await
     }
 
     async fn update(&self, id: ContentId, mutation: ContentMutation) -> Result<Content> {
@@ -438,9 +930,10 @@ impl ContentRepository for MongoContentRepository {
             ContentMutation {
                 author,
                 content,
+                title,
                 edited,
             }: ContentMutation,
-        ) -> ::mongodb::error::Result<Option<Content>> {
+        ) -> ::mongodb::error::Result<Result<Content>> {
             let mut session = make_session(&this.client).await?;
 
             let mut target_content: Content = match this
@@ -450,31 +943,42 @@ impl ContentRepository for MongoContentRepository {
                 .await?
             {
                 Some(c) => c.into(),
-                None => return Ok(None),
+                None => return Ok(Err(RepositoryError::NotFound)),
             };
 
+            let version = target_content.version;
+            target_content.version += 1;
+
             if let Some(a) = author {
                 target_content.author = a;
             }
 
-            if let Some(c) = content {
-                match c {
-                    ContentContentMutation::Sed { capture, replace } =>
-                        target_content.content = capture
-                            .replace(target_content.content.as_str(), replace)
-                            .to_string(),
-                    ContentContentMutation::Complete(s) => target_content.content = s,
-                }
+            if let Some(m) = content {
+                let new = m.apply(target_content.content.as_str());
+                let old = ::core::mem::replace(&mut target_content.content, new);
+                target_content.edit_history.push((edited, old));
+                target_content.lang = crate::utils::detect_lang(target_content.content.as_str());
+            }
+
+            if let Some(t) = title {
+                target_content.title = t;
             }
 
+            // contents updated before versioning was introduced don't have this.
+            let version_q: Bson = match version {
+                0 => doc! { "$in": [0_i64, null] }.into(),
+                v => (v as i64).into(),
+            };
+
             let target_model: MongoContentModel = target_content.into();
-            let edited_str = utils::date_to_string(edited);
-            this.coll
+            let edited_bson = date_to_bson(edited);
+            let res = this
+                .coll
                 .update_one_with_session(
-                    doc! { "id": id },
+                    doc! { "id": id, "version": version_q },
                     doc! {
                         "$set": bson::to_document(&target_model).unwrap(),
-                        "$push": { "edited": edited_str }
+                        "$push": { "edited": edited_bson }
                     },
                     None,
                     &mut session,
@@ -482,6 +986,10 @@ impl ContentRepository for MongoContentRepository {
                 .instrument(tracing::trace_span!("update_one_with_session"))
                 .await?;
 
+            if res.matched_count == 0 {
+                return Ok(Err(RepositoryError::Conflict));
+            }
+
             let new_content = this
                 .coll
                 .find_one_with_session(doc! { "id": id }, None, &mut session)
@@ -492,32 +1000,75 @@ impl ContentRepository for MongoContentRepository {
 
             process_transaction(&mut session)
                 .await
-                .map(|_| Some(new_content))
+                .map(|_| Ok(new_content))
         }
 
-        let res = exec_transaction(transaction, (self, id, mutation)).await;
-        Ok(res.let_(convert_repo_err)?.let_(convert_404_or)?)
+        let res = timed(
+            self.slow_query,
+            "content.update",
+            &doc! { "id": id },
+            exec_transaction(transaction, (self, id, mutation)),
+        )
+        .await;
+        res.let_(convert_repo_err)?
     }
 
-    async fn get_liked(&self, id: ContentId) -> Result<HashSet<UserId>> {
+    async fn get_liked(&self, id: ContentId) -> Result<Vec<UserId>> {
         #[derive(::serde::Deserialize)]
         struct Model {
             liked: HashSet<String>,
         }
 
-        let res = get_set(&self.coll.clone_with_type::<Model>(), id.to_string())
-            .await?
-            .liked
-            .drain()
+        let mut res = get_set(
+            &self.coll.clone_with_type::<Model>(),
+            id.to_string(),
+            self.slow_query,
+        )
+        .await?
+        .liked
+        .drain()
+        .map(|s| s.parse::<u64>().unwrap())
+        .map(UserId)
+        .collect::<Vec<_>>();
+        res.sort();
+
+        Ok(res)
+    }
+
+    async fn liked_page(&self, id: ContentId, skip: u64, limit: u64) -> Result<(Vec<UserId>, u64)> {
+        let (items, total) = get_set_page(
+            "liked",
+            &self.coll,
+            id.to_string(),
+            skip,
+            limit,
+            self.slow_query,
+        )
+        .await?;
+
+        let res = items
+            .iter()
+            .filter_map(Bson::as_str)
             .map(|s| s.parse::<u64>().unwrap())
             .map(UserId)
             .collect();
 
-        Ok(res)
+        Ok((res, total))
+    }
+
+    async fn count_liked(&self, id: ContentId) -> Result<u32> {
+        get_size("liked", &self.coll, id.to_string(), self.slow_query).await
     }
 
     async fn is_liked(&self, id: ContentId, user_id: UserId) -> Result<bool> {
-        is_contains("liked", &self.coll, id.to_string(), user_id.to_string()).await
+        is_contains(
+            "liked",
+            &self.coll,
+            id.to_string(),
+            user_id.to_string(),
+            self.slow_query,
+        )
+        .await
     }
 
     async fn insert_liked(&self, id: ContentId, user_id: UserId) -> Result<bool> {
@@ -528,6 +1079,7 @@ impl ContentRepository for MongoContentRepository {
             id.to_string(),
             user_id.to_string(),
             ModifyOpTy::Push,
+            self.slow_query,
         )
         .await
     }
@@ -540,29 +1092,114 @@ impl ContentRepository for MongoContentRepository {
             id.to_string(),
             user_id.to_string(),
             ModifyOpTy::Pull,
+            self.slow_query,
         )
         .await
     }
 
-    async fn get_pinned(&self, id: ContentId) -> Result<HashSet<UserId>> {
+    async fn insert_liked_many(
+        &self,
+        ids: Vec<ContentId>,
+        user_id: UserId,
+    ) -> Result<Vec<Option<bool>>> {
+        modify_set_many(
+            "liked",
+            &self.coll,
+            &self.client,
+            ids.iter().map(|i| i.to_string().into()).collect(),
+            user_id.to_string(),
+            ModifyOpTy::Push,
+            self.slow_query,
+        )
+        .await
+    }
+
+    async fn delete_liked_many(
+        &self,
+        ids: Vec<ContentId>,
+        user_id: UserId,
+    ) -> Result<Vec<Option<bool>>> {
+        modify_set_many(
+            "liked",
+            &self.coll,
+            &self.client,
+            ids.iter().map(|i| i.to_string().into()).collect(),
+            user_id.to_string(),
+            ModifyOpTy::Pull,
+            self.slow_query,
+        )
+        .await
+    }
+
+    async fn get_pinned(&self, id: ContentId) -> Result<Vec<UserId>> {
         #[derive(::serde::Deserialize)]
         struct Model {
             pinned: HashSet<String>,
         }
 
-        let res = get_set(&self.coll.clone_with_type::<Model>(), id.to_string())
-            .await?
-            .pinned
-            .drain()
+        let mut res = get_set(
+            &self.coll.clone_with_type::<Model>(),
+            id.to_string(),
+            self.slow_query,
+        )
+        .await?
+        .pinned
+        .drain()
+        .map(|s| s.parse::<u64>().unwrap())
+        .map(UserId)
+        .collect::<Vec<_>>();
+        res.sort();
+
+        Ok(res)
+    }
+
+    async fn pinned_page(
+        &self,
+        id: ContentId,
+        skip: u64,
+        limit: u64,
+    ) -> Result<(Vec<UserId>, u64)> {
+        let (items, total) = get_set_page(
+            "pinned",
+            &self.coll,
+            id.to_string(),
+            skip,
+            limit,
+            self.slow_query,
+        )
+        .await?;
+
+        let res = items
+            .iter()
+            .filter_map(Bson::as_str)
             .map(|s| s.parse::<u64>().unwrap())
             .map(UserId)
             .collect();
 
-        Ok(res)
+        Ok((res, total))
+    }
+
+    async fn count_pinned(&self, id: ContentId) -> Result<u32> {
+        get_size("pinned", &self.coll, id.to_string(), self.slow_query).await
+    }
+
+    async fn top_liked(&self, n: usize) -> Result<Vec<Content>> {
+        self.top_by_size("liked", n).await
+    }
+
+    async fn top_pinned(&self, n: usize) -> Result<Vec<Content>> {
+        self.top_by_size("pinned", n).await
     }
 
     async fn is_pinned(&self, id: ContentId, user_id: UserId) -> Result<bool> {
-        is_contains("pinned", &self.coll, id.to_string(), user_id.to_string()).await
+        is_contains(
+            "pinned",
+            &self.coll,
+            id.to_string(),
+            user_id.to_string(),
+            self.slow_query,
+        )
+        .await
     }
 
     async fn insert_pinned(&self, id: ContentId, user_id: UserId) -> Result<bool> {
@@ -573,6 +1210,7 @@ impl ContentRepository for MongoContentRepository {
             id.to_string(),
             user_id.to_string(),
             ModifyOpTy::Push,
+            self.slow_query,
         )
         .await
     }
@@ -585,6 +1223,126 @@ impl ContentRepository for MongoContentRepository {
             id.to_string(),
             user_id.to_string(),
             ModifyOpTy::Pull,
+            self.slow_query,
+        )
+        .await
+    }
+
+    async fn merge_reactions(&self, from: ContentId, into: ContentId) -> Result<Content> {
+        async fn transaction(
+            this: &MongoContentRepository,
+            from: ContentId,
+            into: ContentId,
+        ) -> ::mongodb::error::Result<Option<Content>> {
+            let mut session = make_session(&this.client).await?;
+
+            let from_model = match this
+                .coll
+                .find_one_with_session(doc! { "id": from }, None, &mut session)
+                .instrument(tracing::trace_span!("find_one_with_session"))
+                .await?
+            {
+                Some(m) => m,
+                None => return Ok(None),
+            };
+
+            let liked = from_model.liked.into_iter().collect::<Vec<_>>();
+            let pinned = from_model.pinned.into_iter().collect::<Vec<_>>();
+            let res = this
+                .coll
+                .update_one_with_session(
+                    doc! { "id": into },
+                    doc! { "$addToSet": {
+                        "liked": { "$each": liked },
+                        "pinned": { "$each": pinned },
+                    } },
+                    None,
+                    &mut session,
+                )
+                .instrument(tracing::trace_span!("update_one_with_session"))
+                .await?;
+
+            if res.matched_count == 0 {
+                return Ok(None);
+            }
+
+            let model = this
+                .coll
+                .find_one_with_session(doc! { "id": into }, None, &mut session)
+                .instrument(tracing::trace_span!("find_one_with_session"))
+                .await?
+                .unwrap();
+
+            // sizes cannot be incremented, since overlaps are unknown.
+            this.coll
+                .update_one_with_session(
+                    doc! { "id": into },
+                    doc! { "$set": {
+                        "liked_size": model.liked.len() as i64,
+                        "pinned_size": model.pinned.len() as i64,
+                    } },
+                    None,
+                    &mut session,
+                )
+                .instrument(tracing::trace_span!("update_one_with_session"))
+                .await?;
+
+            process_transaction(&mut session)
+                .await
+                .map(|_| Some(model.into()))
+        }
+
+        let res = timed(
+            self.slow_query,
+            "content.merge_reactions",
+            &doc! { "id": { "$in": [from, into] } },
+            exec_transaction(transaction, (self, from, into)),
+        )
+        .await;
+        Ok(res.let_(convert_repo_err)?.let_(convert_404_or)?)
+    }
+
+    async fn insert_tag(&self, id: ContentId, tag: String) -> Result<bool> {
+        modify_set(
+            "tags",
+            &self.coll,
+            &self.client,
+            id.to_string(),
+            tag,
+            ModifyOpTy::Push,
+            self.slow_query,
+        )
+        .await
+    }
+
+    async fn delete_tag(&self, id: ContentId, tag: String) -> Result<bool> {
+        modify_set(
+            "tags",
+            &self.coll,
+            &self.client,
+            id.to_string(),
+            tag,
+            ModifyOpTy::Pull,
+            self.slow_query,
+        )
+        .await
+    }
+
+    async fn archive(&self, id: ContentId, at: Date) -> Result<Content> {
+        let at_bson = date_to_bson(at);
+        self.set_archived(
+            id,
+            doc! { "archived": { "$ne": true } },
+            doc! { "archived": true, "archived_at": at_bson },
+        )
+        .await
+    }
+
+    async fn restore(&self, id: ContentId) -> Result<Content> {
+        self.set_archived(
+            id,
+            doc! { "archived": true },
+            doc! { "archived": false, "archived_at": null },
         )
         .await
     }
@@ -625,7 +1383,188 @@ impl ContentRepository for MongoContentRepository {
                 .map(|_| Some(content))
         }
 
-        let res = exec_transaction(transaction, (self, id)).await;
+        let res = timed(
+            self.slow_query,
+            "content.delete",
+            &doc! { "id": id },
+            exec_transaction(transaction, (self, id)),
+        )
+        .await;
+        Ok(res.let_(convert_repo_err)?.let_(convert_404_or)?)
+    }
+
+    async fn rename_virtual(&self, from: String, to: String) -> Result<u64> {
+        async fn transaction(
+            this: &MongoContentRepository,
+            from: &str,
+            to: &str,
+        ) -> ::mongodb::error::Result<u64> {
+            let mut session = make_session(&this.client).await?;
+
+            // `MongoContentAuthorModel` is externally tagged.
+            let res = this
+                .coll
+                .update_many_with_session(
+                    doc! { "author.Virtual": from },
+                    doc! { "$set": { "author.Virtual": to } },
+                    None,
+                    &mut session,
+                )
+                .instrument(tracing::trace_span!("update_many_with_session"))
+                .await?;
+
+            process_transaction(&mut session)
+                .await
+                .map(|_| res.modified_count)
+        }
+
+        let res = timed(
+            self.slow_query,
+            "content.rename_virtual",
+            &doc! { "author.Virtual": from.as_str() },
+            exec_transaction(transaction, (self, from.as_str(), to.as_str())),
+        )
+        .await;
+        res.let_(convert_repo_err)
+    }
+
+    async fn reassign_posted(&self, id: ContentId, posted: Posted) -> Result<Content> {
+        async fn transaction(
+            this: &MongoContentRepository,
+            id: ContentId,
+            posted: Posted,
+        ) -> ::mongodb::error::Result<Option<Content>> {
+            let mut session = make_session(&this.client).await?;
+
+            let posted_model: MongoContentPostedModel = posted.into();
+            let res = this
+                .coll
+                .update_one_with_session(
+                    doc! { "id": id },
+                    doc! { "$set": { "posted": bson::to_bson(&posted_model).unwrap() } },
+                    None,
+                    &mut session,
+                )
+                .instrument(tracing::trace_span!("update_one_with_session"))
+                .await?;
+
+            if res.matched_count == 0 {
+                return Ok(None);
+            }
+
+            let new_content = this
+                .coll
+                .find_one_with_session(doc! { "id": id }, None, &mut session)
+                .instrument(tracing::trace_span!("find_one_with_session"))
+                .await?
+                .unwrap()
+                .into();
+
+            process_transaction(&mut session)
+                .await
+                .map(|_| Some(new_content))
+        }
+
+        let res = timed(
+            self.slow_query,
+            "content.reassign_posted",
+            &doc! { "id": id },
+            exec_transaction(transaction, (self, id, posted)),
+        )
+        .await;
         Ok(res.let_(convert_repo_err)?.let_(convert_404_or)?)
     }
+
+    async fn reassign_posted_by(&self, from: UserId, posted: Posted) -> Result<u64> {
+        async fn transaction(
+            this: &MongoContentRepository,
+            from: UserId,
+            posted: Posted,
+        ) -> ::mongodb::error::Result<u64> {
+            let mut session = make_session(&this.client).await?;
+
+            let posted_model: MongoContentPostedModel = posted.into();
+            let res = this
+                .coll
+                .update_many_with_session(
+                    doc! { "posted.id": from },
+                    doc! { "$set": { "posted": bson::to_bson(&posted_model).unwrap() } },
+                    None,
+                    &mut session,
+                )
+                .instrument(tracing::trace_span!("update_many_with_session"))
+                .await?;
+
+            process_transaction(&mut session)
+                .await
+                .map(|_| res.modified_count)
+        }
+
+        let res = timed(
+            self.slow_query,
+            "content.reassign_posted_by",
+            &doc! { "posted.id": from },
+            exec_transaction(transaction, (self, from, posted)),
+        )
+        .await;
+        res.let_(convert_repo_err)
+    }
+
+    async fn archive_posted_by(&self, from: UserId, at: Date) -> Result<u64> {
+        async fn transaction(
+            this: &MongoContentRepository,
+            from: UserId,
+            at: bson::DateTime,
+        ) -> ::mongodb::error::Result<u64> {
+            let mut session = make_session(&this.client).await?;
+
+            let res = this
+                .coll
+                .update_many_with_session(
+                    doc! { "posted.id": from, "archived": { "$ne": true } },
+                    doc! { "$set": { "archived": true, "archived_at": at } },
+                    None,
+                    &mut session,
+                )
+                .instrument(tracing::trace_span!("update_many_with_session"))
+                .await?;
+
+            process_transaction(&mut session)
+                .await
+                .map(|_| res.modified_count)
+        }
+
+        let res = timed(
+            self.slow_query,
+            "content.archive_posted_by",
+            &doc! { "posted.id": from },
+            exec_transaction(transaction, (self, from, date_to_bson(at))),
+        )
+        .await;
+        res.let_(convert_repo_err)
+    }
+
+    async fn export(&self, out: &mut (dyn AsyncWrite + Unpin + Send)) -> Result<usize> {
+        // only opening, since the rest is bound by `out`.
+        let mut cursor = timed(
+            self.slow_query,
+            "content.export",
+            &doc! {},
+            self.coll
+                .find(doc! {}, None)
+                .instrument(tracing::trace_span!("find")),
+        )
+        .await
+        .let_(convert_repo_err)?;
+
+        let mut count = 0;
+        while let Some(model) = cursor.try_next().await.let_(convert_repo_err)? {
+            let mut line = serde_json::to_vec(&model).let_(convert_repo_err)?;
+            line.push(b'\n');
+            out.write_all(&line).await.let_(convert_repo_err)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
 }
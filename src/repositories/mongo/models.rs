@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+use mongodb::bson::DateTime;
+
 use crate::entities::ContentId;
 
 #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
@@ -9,6 +11,11 @@ pub struct MongoUserModel {
     pub sub_admin: bool,
     pub bookmark: HashSet<ContentId>,
     pub bookmark_size: i64,
+    // users granted before auditing was introduced don't have this.
+    #[serde(default)]
+    pub granted_by: Option<String>,
+    #[serde(default)]
+    pub granted_at: Option<String>,
 }
 
 #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
@@ -21,8 +28,41 @@ pub struct MongoContentModel {
     pub liked_size: i64,
     pub pinned: HashSet<String>,
     pub pinned_size: i64,
-    pub created: String,
-    pub edited: Vec<String>,
+    // stored as string before, see `legacy_dates_set`.
+    pub created: DateTime,
+    pub edited: Vec<DateTime>,
+    // contents posted before short id was introduced don't have this.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub short_id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    // contents posted before source url was introduced don't have this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+    #[serde(default)]
+    pub anonymous: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    // contents edited before history was introduced don't have this.
+    #[serde(default)]
+    pub edit_history: Vec<MongoContentRevisionModel>,
+    // contents updated before versioning was introduced don't have this.
+    #[serde(default)]
+    pub version: i64,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub archived_at: Option<DateTime>,
+    #[serde(default)]
+    pub tags: HashSet<String>,
+    #[serde(default)]
+    pub tags_size: i64,
+}
+
+#[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+pub struct MongoContentRevisionModel {
+    pub replaced: DateTime,
+    pub content: String,
 }
 
 #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
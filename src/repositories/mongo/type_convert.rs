@@ -2,11 +2,13 @@ use core::ops::Bound;
 
 use mongodb::bson::{doc, Bson, Document};
 
+use super::converters::{bson_to_date, date_to_bson};
 use super::{
-    LetChain, MongoContentAuthorModel, MongoContentModel, MongoContentPostedModel, MongoUserModel,
-    UserMutation, UserQuery,
+    ContentQuery, LetChain, MongoContentAuthorModel, MongoContentModel, MongoContentPostedModel,
+    MongoContentRevisionModel, MongoUserModel, UserMutation, UserQuery,
 };
 use crate::entities::{Author, Content, ContentId, Posted, User, UserId};
+use crate::usecases::content::{AuthorQuery, ContentSort, PostedQuery, SortKey};
 use crate::utils;
 
 impl From<UserQuery> for Document {
@@ -14,6 +16,8 @@ impl From<UserQuery> for Document {
         UserQuery {
             bookmark,
             bookmark_num,
+            admin,
+            sub_admin,
         }: UserQuery,
     ) -> Self {
         let mut query = doc! {};
@@ -45,11 +49,26 @@ impl From<UserQuery> for Document {
             }
         }
 
+        if let Some(b) = admin {
+            query.insert("admin", b);
+        }
+
+        if let Some(b) = sub_admin {
+            query.insert("sub_admin", b);
+        }
+
         query
     }
 }
 impl From<UserMutation> for Document {
-    fn from(UserMutation { admin, sub_admin }: UserMutation) -> Self {
+    fn from(
+        UserMutation {
+            admin,
+            sub_admin,
+            granted_by,
+            granted_at,
+        }: UserMutation,
+    ) -> Self {
         let mut mutation = doc! {};
 
         if let Some(val) = admin {
@@ -60,10 +79,328 @@ impl From<UserMutation> for Document {
             mutation.insert("sub_admin", val);
         }
 
+        if let Some(val) = granted_by {
+            mutation.insert("granted_by", val.to_string());
+        }
+
+        if let Some(val) = granted_at {
+            mutation.insert("granted_at", utils::date_to_string(val));
+        }
+
         mutation
     }
 }
 
+impl From<&ContentQuery> for Document {
+    fn from(
+        ContentQuery {
+            author,
+            posted,
+            content: _,
+            content_exact,
+            text,
+            liked,
+            liked_num,
+            liked_eq,
+            has_likes,
+            pinned,
+            pinned_num,
+            pinned_eq,
+            has_pins,
+            min_engagement,
+            lang,
+            // compares embedded fields, so evaluated at client side.
+            self_unengaged: _,
+            created_hour,
+            created_weekday,
+            created_range,
+            edited_after,
+            pinned_not_liked_by,
+            bookmarked: _,
+            author_registered: _,
+            any_of,
+            // applied as find option.
+            sort: _,
+            tags,
+            // applies to root query only. (see `root_query_doc`)
+            include_archived: _,
+        }: &ContentQuery,
+    ) -> Self {
+        let mut query = doc! {};
+
+        // author is externally tagged, so `author.User.id` is absent on virtual
+        // authors. regex and substring queries are evaluated at client side.
+        match author {
+            Some(AuthorQuery::UserId(id)) =>
+                query.insert("author.User.id", *id).let_(::core::mem::drop),
+            Some(AuthorQuery::AnyOfUsers(set)) if !set.is_empty() => query
+                .insert(
+                    "author.User.id",
+                    doc! { "$in": set.iter().copied().collect::<Vec<_>>() },
+                )
+                .let_(::core::mem::drop),
+            _ => (),
+        }
+
+        if let Some(PostedQuery::UserId(id)) = posted {
+            query.insert("posted.id", *id);
+        }
+
+        if let Some(set) = liked {
+            if !set.is_empty() {
+                query.insert(
+                    "liked",
+                    doc! { "$in": set.iter().copied().collect::<Vec<_>>() },
+                );
+            }
+        }
+
+        if let Some((g, l)) = liked_num {
+            let mut num_q = doc! {};
+
+            match *g {
+                Bound::Unbounded => (),
+                Bound::Included(n) => num_q.insert("$gte", n).let_(::core::mem::drop),
+                Bound::Excluded(n) => num_q.insert("$gt", n).let_(::core::mem::drop),
+            }
+
+            match *l {
+                Bound::Unbounded => (),
+                Bound::Included(n) => num_q.insert("$lte", n).let_(::core::mem::drop),
+                Bound::Excluded(n) => num_q.insert("$lt", n).let_(::core::mem::drop),
+            }
+
+            if !num_q.is_empty() {
+                query.insert("liked_size", num_q);
+            }
+        }
+
+        if let Some(n) = liked_eq {
+            query.insert("liked_size", *n);
+        }
+
+        // exclusive with `liked_num` and `liked_eq`. (see parser)
+        match has_likes {
+            Some(true) => query
+                .insert("liked_size", doc! { "$gt": 0 })
+                .let_(::core::mem::drop),
+            Some(false) => query.insert("liked_size", 0).let_(::core::mem::drop),
+            None => (),
+        }
+
+        if let Some(set) = pinned {
+            if !set.is_empty() {
+                query.insert(
+                    "pinned",
+                    doc! { "$in": set.iter().copied().collect::<Vec<_>>() },
+                );
+            }
+        }
+
+        if let Some((g, l)) = pinned_num {
+            let mut num_q = doc! {};
+
+            match *g {
+                Bound::Unbounded => (),
+                Bound::Included(n) => num_q.insert("$gte", n).let_(::core::mem::drop),
+                Bound::Excluded(n) => num_q.insert("$gt", n).let_(::core::mem::drop),
+            }
+
+            match *l {
+                Bound::Unbounded => (),
+                Bound::Included(n) => num_q.insert("$lte", n).let_(::core::mem::drop),
+                Bound::Excluded(n) => num_q.insert("$lt", n).let_(::core::mem::drop),
+            }
+
+            if !num_q.is_empty() {
+                query.insert("pinned_size", num_q);
+            }
+        }
+
+        if let Some(n) = pinned_eq {
+            query.insert("pinned_size", *n);
+        }
+
+        // exclusive with `pinned_num` and `pinned_eq`. (see parser)
+        match has_pins {
+            Some(true) => query
+                .insert("pinned_size", doc! { "$gt": 0 })
+                .let_(::core::mem::drop),
+            Some(false) => query.insert("pinned_size", 0).let_(::core::mem::drop),
+            None => (),
+        }
+
+        // `$expr` takes only one, so they are joined by `$and`.
+        let mut exprs = vec![];
+
+        if let Some(n) = min_engagement {
+            exprs.push(doc! { "$gte": [{ "$add": ["$liked_size", "$pinned_size"] }, *n] });
+        }
+
+        // both are in UTC, as client side.
+        if let Some((g, l)) = created_hour {
+            let hour = doc! { "$hour": "$created" };
+
+            match *g {
+                Bound::Unbounded => (),
+                Bound::Included(n) => exprs.push(doc! { "$gte": [hour.clone(), n as i32] }),
+                Bound::Excluded(n) => exprs.push(doc! { "$gt": [hour.clone(), n as i32] }),
+            }
+
+            match *l {
+                Bound::Unbounded => (),
+                Bound::Included(n) => exprs.push(doc! { "$lte": [hour, n as i32] }),
+                Bound::Excluded(n) => exprs.push(doc! { "$lt": [hour, n as i32] }),
+            }
+        }
+
+        // `$dayOfWeek` is 1 (sunday) to 7 (saturday).
+        if let Some(set) = created_weekday {
+            let days = set
+                .iter()
+                .map(|d| d.number_from_sunday() as i32)
+                .collect::<Vec<_>>();
+            exprs.push(doc! { "$in": [{ "$dayOfWeek": "$created" }, days] });
+        }
+
+        match exprs.len() {
+            0 => (),
+            1 => query
+                .insert("$expr", exprs.swap_remove(0))
+                .let_(::core::mem::drop),
+            _ => query
+                .insert("$expr", doc! { "$and": exprs })
+                .let_(::core::mem::drop),
+        }
+
+        if let Some(u) = pinned_not_liked_by {
+            query.insert("$and", vec![
+                doc! { "pinned": u.to_string() },
+                doc! { "liked": { "$ne": u.to_string() } },
+            ]);
+        }
+
+        if let Some(s) = content_exact {
+            query.insert("content", s.as_str());
+        }
+
+        if let Some(l) = lang {
+            query.insert("lang", l.as_str());
+        }
+
+        if let Some(set) = tags {
+            if !set.is_empty() {
+                query.insert(
+                    "tags",
+                    doc! { "$all": set.iter().cloned().collect::<Vec<_>>() },
+                );
+            }
+        }
+
+        if let Some((g, l)) = created_range {
+            let mut date_q = doc! {};
+
+            match g {
+                Bound::Unbounded => (),
+                Bound::Included(d) => date_q
+                    .insert("$gte", date_to_bson(*d))
+                    .let_(::core::mem::drop),
+                Bound::Excluded(d) => date_q
+                    .insert("$gt", date_to_bson(*d))
+                    .let_(::core::mem::drop),
+            }
+
+            match l {
+                Bound::Unbounded => (),
+                Bound::Included(d) => date_q
+                    .insert("$lte", date_to_bson(*d))
+                    .let_(::core::mem::drop),
+                Bound::Excluded(d) => date_q
+                    .insert("$lt", date_to_bson(*d))
+                    .let_(::core::mem::drop),
+            }
+
+            if !date_q.is_empty() {
+                query.insert("created", date_q);
+            }
+        }
+
+        // matches if any element matches.
+        if let Some(d) = edited_after {
+            query.insert("edited", doc! { "$gt": date_to_bson(*d) });
+        }
+
+        // uses text index. (not allowed in `$or`, rejected by parser)
+        if let Some(t) = text {
+            query.insert("$text", doc! { "$search": t.as_str() });
+        }
+
+        if let Some(v) = any_of {
+            if !v.is_empty() {
+                query.insert("$or", v.iter().map(Document::from).collect::<Vec<_>>());
+            }
+        }
+
+        query
+    }
+}
+
+/// `Document::from(query)` with conditions applied to root query only.
+pub fn root_query_doc(query: &ContentQuery) -> Document {
+    let mut doc: Document = query.into();
+
+    // contents archived before archiving was introduced don't have this.
+    if !query.include_archived {
+        doc.insert("archived", doc! { "$ne": true });
+    }
+
+    doc
+}
+
+/// whether `Document::from(query)` matches exactly what `filter_content` does,
+/// so server-side skip / limit are usable.
+pub fn is_fully_translated(query: &ContentQuery) -> bool {
+    let author = match &query.author {
+        None => true,
+        Some(AuthorQuery::UserId(_)) => true,
+        Some(AuthorQuery::AnyOfUsers(set)) => !set.is_empty(),
+        Some(_) => false,
+    };
+    let posted = matches!(query.posted, None | Some(PostedQuery::UserId(_)));
+    // server-side matches any of them, but client-side matches all of them.
+    let liked = query.liked.as_ref().map(|s| s.len() <= 1).unwrap_or(true);
+    let pinned = query.pinned.as_ref().map(|s| s.len() <= 1).unwrap_or(true);
+
+    author
+        && posted
+        && liked
+        && pinned
+        && query.content.is_none()
+        && query.self_unengaged.is_none()
+        && query.sort.map(|s| sort_doc(s).is_some()).unwrap_or(true)
+        && query
+            .any_of
+            .as_ref()
+            .map(|v| v.iter().all(is_fully_translated))
+            .unwrap_or(true)
+}
+
+/// `None` if cannot sort at server side.
+pub fn sort_doc(ContentSort { key, desc }: ContentSort) -> Option<Document> {
+    let order = match desc {
+        true => -1,
+        false => 1,
+    };
+
+    match key {
+        SortKey::Created => Some(doc! { "created": order }),
+        SortKey::LikedCount => Some(doc! { "liked_size": order }),
+        SortKey::PinnedCount => Some(doc! { "pinned_size": order }),
+        // by last edit (or created), not expressible with index.
+        SortKey::Edited => None,
+    }
+}
+
 impl From<MongoUserModel> for User {
     fn from(
         MongoUserModel {
@@ -72,6 +409,8 @@ impl From<MongoUserModel> for User {
             sub_admin,
             bookmark,
             bookmark_size: _,
+            granted_by,
+            granted_at,
         }: MongoUserModel,
     ) -> User {
         User {
@@ -79,6 +418,9 @@ impl From<MongoUserModel> for User {
             admin,
             sub_admin,
             bookmark,
+            posted_count: None,
+            granted_by: granted_by.map(|s| s.parse::<u64>().unwrap().into()),
+            granted_at: granted_at.map(|s| utils::parse_date(s.as_str())),
         }
     }
 }
@@ -89,6 +431,9 @@ impl From<User> for MongoUserModel {
             admin,
             sub_admin,
             bookmark,
+            posted_count: _,
+            granted_by,
+            granted_at,
         }: User,
     ) -> Self {
         MongoUserModel {
@@ -97,6 +442,8 @@ impl From<User> for MongoUserModel {
             sub_admin,
             bookmark_size: bookmark.len() as i64,
             bookmark,
+            granted_by: granted_by.map(|i| i.to_string()),
+            granted_at: granted_at.map(utils::date_to_string),
         }
     }
 }
@@ -114,6 +461,17 @@ impl From<MongoContentModel> for Content {
             pinned_size: _,
             created,
             mut edited,
+            short_id,
+            title,
+            source_url,
+            anonymous,
+            lang,
+            mut edit_history,
+            version,
+            archived,
+            archived_at,
+            tags,
+            tags_size: _,
         }: MongoContentModel,
     ) -> Self {
         Content {
@@ -129,11 +487,23 @@ impl From<MongoContentModel> for Content {
                 .drain()
                 .map(|s| s.parse::<u64>().unwrap().into())
                 .collect(),
-            created: utils::parse_date(created.as_str()),
-            edited: edited
+            created: bson_to_date(created),
+            edited: edited.drain(..).map(bson_to_date).collect(),
+            short_id,
+            title,
+            source_url,
+            anonymous,
+            lang,
+            edit_history: edit_history
                 .drain(..)
-                .map(|s| utils::parse_date(s.as_str()))
+                .map(|MongoContentRevisionModel { replaced, content }| {
+                    (bson_to_date(replaced), content)
+                })
                 .collect(),
+            version: version as u64,
+            archived,
+            archived_at: archived_at.map(bson_to_date),
+            tags,
         }
     }
 }
@@ -148,6 +518,16 @@ impl From<Content> for MongoContentModel {
             mut pinned,
             created,
             mut edited,
+            short_id,
+            title,
+            source_url,
+            anonymous,
+            lang,
+            mut edit_history,
+            version,
+            archived,
+            archived_at,
+            tags,
         }: Content,
     ) -> Self {
         MongoContentModel {
@@ -159,8 +539,25 @@ impl From<Content> for MongoContentModel {
             liked: liked.drain().map(|n| n.to_string()).collect(),
             pinned_size: pinned.len() as i64,
             pinned: pinned.drain().map(|n| n.to_string()).collect(),
-            created: utils::date_to_string(created),
-            edited: edited.drain(..).map(utils::date_to_string).collect(),
+            created: date_to_bson(created),
+            edited: edited.drain(..).map(date_to_bson).collect(),
+            short_id,
+            title,
+            source_url,
+            anonymous,
+            lang,
+            edit_history: edit_history
+                .drain(..)
+                .map(|(replaced, content)| MongoContentRevisionModel {
+                    replaced: date_to_bson(replaced),
+                    content,
+                })
+                .collect(),
+            version: version as i64,
+            archived,
+            archived_at: archived_at.map(date_to_bson),
+            tags_size: tags.len() as i64,
+            tags,
         }
     }
 }
@@ -215,3 +612,58 @@ impl From<UserId> for Bson {
 impl From<ContentId> for Bson {
     fn from(i: ContentId) -> Self { Self::String(i.to_string()) }
 }
+
+/// `$set` converting dates of content stored as string (RFC 3339, by older
+/// versions) into date. `None` if there's nothing to convert.
+pub fn legacy_dates_set(content: &Document) -> Option<Document> {
+    let convert = |b: &Bson| match b {
+        Bson::String(s) => utils::parse_date(s.as_str())
+            .let_(date_to_bson)
+            .let_(Bson::DateTime),
+        b => b.clone(),
+    };
+    let is_str = |b: &Bson| matches!(b, Bson::String(_));
+
+    let mut set = doc! {};
+
+    for key in ["created", "archived_at"] {
+        if let Some(b) = content.get(key).filter(|b| is_str(b)) {
+            set.insert(key, convert(b));
+        }
+    }
+
+    if let Some(Bson::Array(a)) = content.get("edited") {
+        if a.iter().any(is_str) {
+            set.insert("edited", a.iter().map(convert).collect::<Vec<_>>());
+        }
+    }
+
+    if let Some(Bson::Array(a)) = content.get("edit_history") {
+        let replaced_is_str = |b: &Bson| match b {
+            Bson::Document(d) => d.get("replaced").map(is_str).unwrap_or(false),
+            _ => false,
+        };
+
+        if a.iter().any(replaced_is_str) {
+            let converted = a
+                .iter()
+                .map(|b| match b {
+                    Bson::Document(d) => {
+                        let mut d = d.clone();
+                        if let Some(r) = d.get("replaced").map(convert) {
+                            d.insert("replaced", r);
+                        }
+                        Bson::Document(d)
+                    },
+                    b => b.clone(),
+                })
+                .collect::<Vec<_>>();
+            set.insert("edit_history", converted);
+        }
+    }
+
+    match set.is_empty() {
+        true => None,
+        false => Some(set),
+    }
+}
@@ -0,0 +1,253 @@
+//! support for driving commands without discord, for integration tests.
+//!
+//! enabled by `testing` feature.
+//!
+//! ```ignore
+//! let h = Harness::in_memory(Config::default());
+//! let alice = h.executor(1, "alice");
+//!
+//! h.exec("*ip user register", &alice).await?;
+//! let out = h.exec("*ip content post -v bob -c hello", &alice).await?;
+//! ```
+
+use alloc::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use serenity::builder::CreateEmbed;
+use serenity::http::{CacheHttp, Http};
+
+use crate::cmds::Cmd;
+use crate::constructors::{in_memory_contr, Config};
+use crate::controllers::serenity::{Executor, SerenityReturnController};
+use crate::entities::UserId;
+use crate::presenters::impls::serenity::{Attachment, Response};
+use crate::usecases::user::{self, UserMutation};
+use crate::utils::LetChain;
+
+/// output of a command, with views applied.
+#[derive(Debug)]
+pub enum Output {
+    Embeds(Vec<CreateEmbed>),
+    Text(String),
+    Json(::serde_json::Value),
+    File { name: String, data: Vec<u8> },
+}
+
+impl Output {
+    /// `description` of each embed. empty if output is not embeds.
+    pub fn descriptions(&self) -> Vec<String> {
+        match self {
+            Output::Embeds(es) => es
+                .iter()
+                .filter_map(|e| e.0.get("description"))
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect(),
+            Output::Text(_) | Output::Json(_) | Output::File { .. } => vec![],
+        }
+    }
+
+    /// `title` of each embed. empty if output is not embeds.
+    pub fn titles(&self) -> Vec<String> {
+        match self {
+            Output::Embeds(es) => es
+                .iter()
+                .filter_map(|e| e.0.get("title"))
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect(),
+            Output::Text(_) | Output::Json(_) | Output::File { .. } => vec![],
+        }
+    }
+
+    /// footer text of each embed (skipping ones without). empty if output is
+    /// not embeds.
+    pub fn footers(&self) -> Vec<String> {
+        match self {
+            Output::Embeds(es) => es
+                .iter()
+                .filter_map(|e| e.0.get("footer"))
+                .filter_map(|v| v["text"].as_str())
+                .map(|s| s.to_string())
+                .collect(),
+            Output::Text(_) | Output::Json(_) | Output::File { .. } => vec![],
+        }
+    }
+
+    /// `(name, value)` of each field in `idx`-th embed. empty if output is not
+    /// embeds.
+    pub fn fields(&self, idx: usize) -> Vec<(String, String)> {
+        match self {
+            Output::Embeds(es) => es
+                .get(idx)
+                .and_then(|e| e.0.get("fields"))
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .map(|f| {
+                    let s = |k: &str| f[k].as_str().unwrap_or_default().to_string();
+                    (s("name"), s("value"))
+                })
+                .collect(),
+            Output::Text(_) | Output::Json(_) | Output::File { .. } => vec![],
+        }
+    }
+
+    /// value of first field named `name` in `idx`-th embed.
+    pub fn field(&self, idx: usize, name: &str) -> Option<String> {
+        match self {
+            Output::Embeds(es) => es
+                .get(idx)?
+                .0
+                .get("fields")?
+                .as_array()?
+                .iter()
+                .find(|f| f["name"] == name)
+                .and_then(|f| f["value"].as_str())
+                .map(|s| s.to_string()),
+            Output::Text(_) | Output::Json(_) | Output::File { .. } => None,
+        }
+    }
+}
+
+// never connected. commands requiring discord api (e.g. resolving users by id)
+// will fail.
+#[derive(Clone)]
+struct OfflineHttp(Arc<Http>);
+impl CacheHttp for OfflineHttp {
+    fn http(&self) -> &Http { &self.0 }
+}
+
+pub struct Harness {
+    contr: SerenityReturnController,
+    http: OfflineHttp,
+}
+
+/// the executor of commands, passed to [`Harness::exec`].
+#[derive(Debug, Clone)]
+pub struct TestExecutor(Executor);
+
+impl Harness {
+    /// backed by [`crate::repositories::InMemoryRepository`].
+    pub fn in_memory(config: Config) -> Self {
+        Self {
+            contr: in_memory_contr(config),
+            http: OfflineHttp(Arc::new(Http::new(""))),
+        }
+    }
+
+    /// executor in guild-less (DM) channel.
+    pub fn executor(&self, user_id: u64, name: impl Into<String>) -> TestExecutor {
+        TestExecutor(Executor {
+            user_id: UserId(user_id),
+            user_name: name.into(),
+            user_nick: None,
+            guild_id: None,
+            channel_id: 0,
+            timestamp: ::chrono::Utc::now(),
+        })
+    }
+
+    /// parses `raw` (with prefix, e.g. `*ip user get`) and executes it.
+    pub async fn exec(&self, raw: &str, ex: &TestExecutor) -> Result<Output> {
+        let cmd = self.parse(raw).await?;
+
+        self.contr
+            .handle_cmd(cmd, &ex.0, self.http.clone())
+            .await
+            .and_then(to_output)
+    }
+
+    /// same as [`Self::exec`], but destructive commands ask for confirmation
+    /// first (as on discord), answered with `accept` by the same executor.
+    /// `None` if asked and cancelled.
+    pub async fn exec_confirming(
+        &self,
+        raw: &str,
+        ex: &TestExecutor,
+        accept: bool,
+    ) -> Result<Option<Output>> {
+        let cmd = self.parse(raw).await?;
+
+        let res = match self
+            .contr
+            .handle_or_confirm(cmd, ex.0.clone(), self.http.clone())
+            .await?
+        {
+            Response::Confirm(_, p) if accept => self.contr.confirm(p, self.http.clone()).await?,
+            Response::Confirm(..) => return Ok(None),
+            r => r,
+        };
+
+        to_output(res).map(Some)
+    }
+}
+
+fn to_output(res: Response) -> Result<Output> {
+    let out = match res {
+        Response::Embeds(vs) | Response::Paged(vs) => vs
+            .into_iter()
+            .map(|v| {
+                let mut ce = CreateEmbed::default();
+                v(&mut ce);
+                ce
+            })
+            .collect::<Vec<_>>()
+            .let_(Output::Embeds),
+        Response::Text(t) => Output::Text(t),
+        Response::Json(t) => Output::Json(::serde_json::from_str(t.as_str())?),
+        Response::File(Attachment { name, data }) => Output::File { name, data },
+        Response::Confirm(..) => return Err(anyhow!("unexpected confirmation")),
+    };
+
+    Ok(out)
+}
+
+impl Harness {
+    /// makes the (registered) executor admin, without authorization.
+    pub async fn grant_admin(&self, ex: &TestExecutor) -> Result<()> {
+        self.contr
+            .user
+            .edit(user::edit::Input {
+                user_id: ex.0.user_id,
+                mutation: UserMutation {
+                    admin: Some(true),
+                    ..UserMutation::default()
+                },
+                executor_id: ex.0.user_id,
+                executed_at: ex.0.timestamp,
+            })
+            .await
+            .map(|_| ())
+    }
+
+    /// parses `raw` (same as [`Self::exec`]), then returns name of command
+    /// used in logs.
+    pub async fn cmd_name(&self, raw: &str) -> Result<&'static str> {
+        self.parse(raw).await.map(|c| c.name())
+    }
+
+    async fn parse(&self, raw: &str) -> Result<Cmd> {
+        SerenityReturnController::parse_str(
+            raw,
+            self.contr.prefix.as_str(),
+            self.contr.suggest_distance,
+        )
+        .await
+        .ok_or_else(|| anyhow!("not a command: {}", raw))?
+    }
+}
+
+impl TestExecutor {
+    pub fn in_guild(mut self, guild_id: u64, channel_id: u64) -> Self {
+        self.0.guild_id = Some(guild_id);
+        self.0.channel_id = channel_id;
+        self
+    }
+
+    /// overrides time of execution. (defaults to creation time)
+    pub fn at(mut self, timestamp: ::chrono::DateTime<::chrono::Utc>) -> Self {
+        self.0.timestamp = timestamp;
+        self
+    }
+}
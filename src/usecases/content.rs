@@ -1,9 +1,13 @@
 usecase! {
     post : {
         pub content: String,
+        pub title: Option<String>,
+        pub source_url: Option<String>,
+        pub anonymous: bool,
         pub posted: entities::Posted,
         pub author: entities::Author,
         pub created: entities::Date,
+        pub tags: ::std::collections::HashSet<String>,
     } => {
         pub content: entities::Content,
     }
@@ -12,8 +16,30 @@ usecase! {
 usecase! {
     get : {
         pub content_id: entities::ContentId,
+        /// executed user, to check own like, pin and bookmark.
+        pub requester: Option<entities::UserId>,
     } => {
         pub content: entities::Content,
+        /// `None` if no requester.
+        pub liked_by_me: Option<bool>,
+        pub pinned_by_me: Option<bool>,
+        pub bookmarked_by_me: Option<bool>,
+    }
+}
+
+usecase! {
+    history : {
+        pub content_id: entities::ContentId,
+    } => {
+        pub content: entities::Content,
+    }
+}
+
+usecase! {
+    resolve : {
+        pub short_id: String,
+    } => {
+        pub content_id: entities::ContentId,
     }
 }
 
@@ -21,9 +47,20 @@ usecase! {
     gets : {
         pub query: super::ContentQuery,
         pub page: u32,
+        /// pages by cursor instead of `page` (must be 1) if given. cannot be used
+        /// with `sort` of query.
+        pub cursor: Option<super::ContentCursor>,
+        /// refreshes names of authors (`Author::User`) if given.
+        pub resolver: Option<::alloc::sync::Arc<dyn super::AuthorResolver + Sync + Send>>,
     } => {
         pub contents: ::smallvec::SmallVec<[(u32, entities::Content); 5]>,
         pub page: u32,
+        /// number of all matched contents.
+        pub total_items: usize,
+        pub total_pages: u32,
+        /// given cursor. if `Some`, `contents` are of it (in ascending order of
+        /// created date), and `page` and totals are not counted.
+        pub cursor: Option<super::ContentCursor>,
     }
 }
 
@@ -36,9 +73,67 @@ usecase! {
     }
 }
 
+usecase! {
+    rename_virtual : {
+        pub from: String,
+        pub to: String,
+    } => {
+        pub from: String,
+        pub to: String,
+        pub count: u64,
+    }
+}
+
+usecase! {
+    reassign_posted : {
+        pub content_id: entities::ContentId,
+        pub new_user_id: entities::UserId,
+        /// looks up name and nick of new user.
+        pub resolver: ::alloc::sync::Arc<dyn super::AuthorResolver + Sync + Send>,
+    } => {
+        pub content: entities::Content,
+        pub old: entities::Posted,
+    }
+}
+
+usecase! {
+    preview_sed : {
+        pub content_id: entities::ContentId,
+        pub capture: ::regex::Regex,
+        pub replace: String,
+    } => {
+        pub content: entities::Content,
+        pub previewed: String,
+    }
+}
+
 usecase! {
     withdraw : {
         pub content_id: entities::ContentId,
+        pub archived_at: entities::Date,
+    } => {
+        pub content: entities::Content,
+    }
+}
+
+usecase! {
+    merge : {
+        pub from: entities::ContentId,
+        pub into: entities::ContentId,
+        pub archived_at: entities::Date,
+    } => {
+        /// after merged.
+        pub content: entities::Content,
+        /// archived.
+        pub from: entities::Content,
+        /// number of users whose bookmark is moved.
+        pub bookmarks: u64,
+    }
+}
+
+usecase! {
+    restore : {
+        pub content_id: entities::ContentId,
     } => {
         pub content: entities::Content,
     }
@@ -48,9 +143,23 @@ usecase! {
     get_like : {
         pub content_id: entities::ContentId,
         pub page: u32,
+        pub detailed: bool,
     } => {
         pub like: ::smallvec::SmallVec<[(u32, entities::UserId); 20]>,
+        /// registered ones of liked users, keyed by id. `None` unless detailed.
+        pub users: Option<::std::collections::HashMap<entities::UserId, entities::User>>,
         pub page: u32,
+        pub total_items: usize,
+        pub total_pages: u32,
+    }
+}
+
+usecase! {
+    count_like : {
+        pub content_id: entities::ContentId,
+    } => {
+        pub content_id: entities::ContentId,
+        pub count: u32,
     }
 }
 
@@ -74,6 +183,28 @@ usecase! {
     }
 }
 
+usecase! {
+    like_many : {
+        pub content_ids: Vec<entities::ContentId>,
+        pub user_id: entities::UserId,
+    } => {
+        pub liked: Vec<entities::ContentId>,
+        pub already: Vec<entities::ContentId>,
+        pub not_found: Vec<entities::ContentId>,
+    }
+}
+
+usecase! {
+    unlike_many : {
+        pub content_ids: Vec<entities::ContentId>,
+        pub user_id: entities::UserId,
+    } => {
+        pub unliked: Vec<entities::ContentId>,
+        pub not_liked: Vec<entities::ContentId>,
+        pub not_found: Vec<entities::ContentId>,
+    }
+}
+
 usecase! {
     get_pin : {
         pub content_id: entities::ContentId,
@@ -81,6 +212,17 @@ usecase! {
     } => {
         pub pin: ::smallvec::SmallVec<[(u32, entities::UserId); 20]>,
         pub page: u32,
+        pub total_items: usize,
+        pub total_pages: u32,
+    }
+}
+
+usecase! {
+    count_pin : {
+        pub content_id: entities::ContentId,
+    } => {
+        pub content_id: entities::ContentId,
+        pub count: u32,
     }
 }
 
@@ -104,39 +246,179 @@ usecase! {
     }
 }
 
+usecase! {
+    tag : {
+        pub content_id: entities::ContentId,
+        pub tag: String,
+    } => {
+        pub content: entities::Content,
+        pub tag: String,
+    }
+}
+
+usecase! {
+    untag : {
+        pub content_id: entities::ContentId,
+        pub tag: String,
+    } => {
+        pub content: entities::Content,
+        pub tag: String,
+    }
+}
+
+usecase! {
+    stats : {
+        /// number of contents in each ranking.
+        pub top: usize,
+    } => {
+        /// excluding archived ones.
+        pub contents: u64,
+        pub users: u64,
+        pub top_liked: Vec<entities::Content>,
+        pub top_pinned: Vec<entities::Content>,
+    }
+}
+
 use core::ops::Bound;
 use std::collections::HashSet;
 
 use regex::Regex;
 
-use crate::entities::{Author, Date, UserId};
+use crate::entities::{Author, ContentId, Date, UserId};
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, ::serde::Serialize, ::serde::Deserialize)]
+#[serde(default)]
 pub struct ContentQuery {
     pub author: Option<AuthorQuery>,
     pub posted: Option<PostedQuery>,
+    #[serde(with = "super::query_serde::option_regex")]
     pub content: Option<Regex>,
+    /// matches if content is exactly the same. (not regex)
+    pub content_exact: Option<String>,
+    /// full-text search on content. uses text index on mongo, and
+    /// case-insensitive substring matching on memory. cannot be used in
+    /// `any_of`.
+    pub text: Option<String>,
     pub liked: Option<HashSet<UserId>>,
+    #[serde(with = "super::query_serde::option_range")]
     pub liked_num: Option<(Bound<u32>, Bound<u32>)>,
+    pub liked_eq: Option<u32>,
+    /// matches if liked by anyone (or not).
+    pub has_likes: Option<bool>,
     pub pinned: Option<HashSet<UserId>>,
+    #[serde(with = "super::query_serde::option_range")]
     pub pinned_num: Option<(Bound<u32>, Bound<u32>)>,
+    pub pinned_eq: Option<u32>,
+    /// matches if pinned by anyone (or not).
+    pub has_pins: Option<bool>,
+    /// matches if (liked + pinned) >= n.
+    pub min_engagement: Option<u32>,
+    /// matches detected language (ISO 639-3, e.g. "eng", "jpn").
+    pub lang: Option<String>,
+    /// matches if poster neither liked nor pinned own content (or not).
+    pub self_unengaged: Option<bool>,
+    /// hour (in UTC) of creation.
+    #[serde(with = "super::query_serde::option_range")]
+    pub created_hour: Option<(Bound<u8>, Bound<u8>)>,
+    /// weekday (in UTC) of creation.
+    #[serde(with = "super::query_serde::option_weekdays")]
+    pub created_weekday: Option<HashSet<::chrono::Weekday>>,
+    #[serde(with = "super::query_serde::option_date_range")]
+    pub created_range: Option<(Bound<Date>, Bound<Date>)>,
+    /// matches if edited after the date (at least once).
+    #[serde(with = "super::query_serde::option_date")]
+    pub edited_after: Option<Date>,
+    /// matches if the user pinned but didn't like.
+    pub pinned_not_liked_by: Option<UserId>,
+    /// matches if bookmarked by anyone (or not). cannot be used in `any_of`.
+    pub bookmarked: Option<bool>,
+    /// matches if author is registered user (or not). virtual authors are
+    /// treated as not registered. cannot be used in `any_of`.
+    ///
+    /// checks users per distinct author after fetching contents, so costly for
+    /// broad queries.
+    pub author_registered: Option<bool>,
+    /// matches if any of sub-queries matches.
+    pub any_of: Option<Vec<ContentQuery>>,
+    /// order of results. insertion order if `None`. cannot be used in `any_of`.
+    pub sort: Option<ContentSort>,
+    /// matches if tagged with all of them.
+    pub tags: Option<HashSet<String>>,
+    /// matches archived (withdrawn) contents too. ignored in `any_of`.
+    ///
+    /// not (de)serialized, since it's given by flag (and authorized by it).
+    #[serde(skip)]
+    pub include_archived: bool,
     // FiF: times query
 }
+impl ContentQuery {
+    /// checks sets of ids (including ones in `any_of`) with
+    /// [`check_query_ids`](super::check_query_ids). every query given from
+    /// outside is checked with this before finding.
+    pub fn check_ids(&self, max: usize) -> Result<(), String> {
+        if let Some(s) = &self.liked {
+            super::check_query_ids("liked", s.len(), max)?;
+        }
+        if let Some(s) = &self.pinned {
+            super::check_query_ids("pinned", s.len(), max)?;
+        }
 
-#[derive(Debug, Clone)]
+        self.any_of
+            .iter()
+            .flatten()
+            .try_for_each(|q| q.check_ids(max))
+    }
+}
+
+#[derive(Debug, Clone, Copy, ::serde::Serialize, ::serde::Deserialize)]
+pub struct ContentSort {
+    pub key: SortKey,
+    #[serde(default)]
+    pub desc: bool,
+}
+
+/// pages by position of the content in order of created date, instead of
+/// offset. stable while contents are added.
+#[derive(Debug, Clone, Copy)]
+pub enum ContentCursor {
+    Before(ContentId),
+    After(ContentId),
+}
+
+#[derive(Debug, Clone, Copy, ::serde::Serialize, ::serde::Deserialize)]
+pub enum SortKey {
+    Created,
+    LikedCount,
+    PinnedCount,
+    /// last edited date. (or created date if not edited)
+    Edited,
+}
+
+#[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
 pub enum AuthorQuery {
     UserId(UserId),
+    #[serde(with = "super::query_serde::regex")]
     UserName(Regex),
+    #[serde(with = "super::query_serde::regex")]
     UserNick(Regex),
+    #[serde(with = "super::query_serde::regex")]
     Virtual(Regex),
+    #[serde(with = "super::query_serde::regex")]
     Any(Regex),
+    AnyOfUsers(HashSet<UserId>),
+    /// case-insensitive substring of any name (same targets as `Any`). kept
+    /// lowercased.
+    NameContains(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
 pub enum PostedQuery {
     UserId(UserId),
+    #[serde(with = "super::query_serde::regex")]
     UserName(Regex),
+    #[serde(with = "super::query_serde::regex")]
     UserNick(Regex),
+    #[serde(with = "super::query_serde::regex")]
     Any(Regex),
 }
 
@@ -144,11 +426,40 @@ pub enum PostedQuery {
 pub struct ContentMutation {
     pub author: Option<Author>,
     pub content: Option<ContentContentMutation>,
+    /// `Some(None)` removes title.
+    pub title: Option<Option<String>>,
     pub edited: Date,
 }
 
 #[derive(Debug, Clone)]
 pub enum ContentContentMutation {
     Complete(String),
-    Sed { capture: Regex, replace: String },
+    Sed {
+        capture: Regex,
+        replace: String,
+    },
+    /// concatenated as is. (no separator)
+    Append(String),
+    /// concatenated as is. (no separator)
+    Prepend(String),
+}
+impl ContentContentMutation {
+    /// result is normalized (see `utils::normalize_content`).
+    pub fn apply(self, content: &str) -> String {
+        let res = match self {
+            ContentContentMutation::Complete(s) => s,
+            ContentContentMutation::Sed { capture, replace } =>
+                capture.replace(content, replace).to_string(),
+            ContentContentMutation::Append(s) => format!("{}{}", content, s),
+            ContentContentMutation::Prepend(s) => format!("{}{}", s, content),
+        };
+
+        crate::utils::normalize_content(res.as_str())
+    }
+}
+
+/// resolves current name and nick of user.
+#[async_trait::async_trait]
+pub trait AuthorResolver: ::core::fmt::Debug {
+    async fn resolve(&self, id: UserId) -> Option<(String, Option<String>)>;
 }
@@ -18,4 +18,13 @@ macro_rules! usecase {
 }
 
 pub mod content;
+mod query_serde;
 pub mod user;
+
+/// rejects sets of ids larger than `max`, not to issue huge `$in` queries.
+pub(crate) fn check_query_ids(key: &str, len: usize, max: usize) -> Result<(), String> {
+    match len > max {
+        true => Err(format!("too many ids in {}: {} (max: {})", key, len, max)),
+        false => Ok(()),
+    }
+}
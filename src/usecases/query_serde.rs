@@ -0,0 +1,188 @@
+//! (de)serializers for fields of queries, keeping the same forms as users
+//! write. (regexes as sources, ranges as `a..b`)
+
+use core::fmt::Display;
+use core::ops::Bound;
+use core::str::FromStr;
+use std::collections::HashSet;
+
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::entities::Date;
+use crate::utils::date_to_string;
+
+/// `a..b`, `a..=b`, `a..`, `..b`, `..=b` or `..`. exclusive start cannot be
+/// written.
+fn range_to_string<T>(
+    (start, end): &(Bound<T>, Bound<T>),
+    f: impl Fn(&T) -> String,
+) -> Result<String, String> {
+    let start = match start {
+        Bound::Unbounded => String::new(),
+        Bound::Included(t) => f(t),
+        Bound::Excluded(_) => return Err("exclusive start cannot be serialized".to_string()),
+    };
+    let end = match end {
+        Bound::Unbounded => String::new(),
+        Bound::Included(t) => format!("={}", f(t)),
+        Bound::Excluded(t) => f(t),
+    };
+
+    Ok(format!("{}..{}", start, end))
+}
+
+fn string_to_range<T>(
+    s: &str,
+    f: impl Fn(&str) -> Result<T, String>,
+) -> Result<(Bound<T>, Bound<T>), String> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or_else(|| format!("invalid range: {}", s))?;
+
+    let start = match start {
+        "" => Bound::Unbounded,
+        s => Bound::Included(f(s)?),
+    };
+    let end = match end.strip_prefix('=') {
+        Some("") => return Err(format!("invalid range: {}", s)),
+        Some(s) => Bound::Included(f(s)?),
+        None if end.is_empty() => Bound::Unbounded,
+        None => Bound::Excluded(f(end)?),
+    };
+
+    Ok((start, end))
+}
+
+fn string_to_date(s: &str) -> Result<Date, String> {
+    ::chrono::DateTime::parse_from_rfc3339(s)
+        .map(|d| d.with_timezone(&::chrono::Utc))
+        .map_err(|e| format!("invalid date: {} ({})", s, e))
+}
+
+pub mod regex {
+    use ::regex::Regex;
+
+    use super::*;
+
+    pub fn serialize<S: Serializer>(r: &Regex, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(r.as_str())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Regex, D::Error> {
+        let s = String::deserialize(d)?;
+        Regex::new(s.as_str()).map_err(D::Error::custom)
+    }
+}
+
+pub mod option_regex {
+    use ::regex::Regex;
+
+    use super::*;
+
+    pub fn serialize<S: Serializer>(r: &Option<Regex>, s: S) -> Result<S::Ok, S::Error> {
+        r.as_ref().map(|r| r.as_str()).serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Regex>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|s| Regex::new(s.as_str()).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+pub mod option_range {
+    use super::*;
+
+    pub fn serialize<S, T>(r: &Option<(Bound<T>, Bound<T>)>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Display,
+    {
+        r.as_ref()
+            .map(|r| range_to_string(r, T::to_string))
+            .transpose()
+            .map_err(S::Error::custom)?
+            .serialize(s)
+    }
+
+    pub fn deserialize<'de, D, T>(d: D) -> Result<Option<(Bound<T>, Bound<T>)>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        Option::<String>::deserialize(d)?
+            .map(|s| {
+                string_to_range(s.as_str(), |s| s.parse::<T>().map_err(|e| e.to_string()))
+                    .map_err(D::Error::custom)
+            })
+            .transpose()
+    }
+}
+
+pub mod option_date_range {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        r: &Option<(Bound<Date>, Bound<Date>)>,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        r.as_ref()
+            .map(|r| range_to_string(r, |d| date_to_string(*d)))
+            .transpose()
+            .map_err(S::Error::custom)?
+            .serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<Option<(Bound<Date>, Bound<Date>)>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|s| string_to_range(s.as_str(), string_to_date).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+pub mod option_date {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(d: &Option<Date>, s: S) -> Result<S::Ok, S::Error> {
+        d.map(date_to_string).serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Date>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|s| string_to_date(s.as_str()).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+/// as `"Mon"`, `"Tue"`, ...
+pub mod option_weekdays {
+    use chrono::Weekday;
+
+    use super::*;
+
+    pub fn serialize<S: Serializer>(w: &Option<HashSet<Weekday>>, s: S) -> Result<S::Ok, S::Error> {
+        w.as_ref()
+            .map(|w| w.iter().map(|d| d.to_string()).collect::<Vec<_>>())
+            .serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<Option<HashSet<Weekday>>, D::Error> {
+        Option::<Vec<String>>::deserialize(d)?
+            .map(|v| {
+                v.iter()
+                    .map(|s| {
+                        s.parse::<Weekday>()
+                            .map_err(|_| D::Error::custom(format!("invalid weekday: {}", s)))
+                    })
+                    .collect()
+            })
+            .transpose()
+    }
+}
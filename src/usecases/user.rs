@@ -6,9 +6,21 @@ usecase! {
     }
 }
 
+usecase! {
+    status : {
+        pub user_id: entities::UserId,
+    } => {
+        pub user_id: entities::UserId,
+        pub registered: bool,
+    }
+}
+
 usecase! {
     get : {
         pub user_id: entities::UserId,
+        /// anonymous contents are counted in `posted_count` only for the user
+        /// and admins.
+        pub requester: entities::UserId,
     } => {
         pub user: entities::User,
     }
@@ -21,6 +33,19 @@ usecase! {
     } => {
         pub users: ::smallvec::SmallVec<[(u32, entities::User); 5]>,
         pub page: u32,
+        pub total_items: usize,
+        pub total_pages: u32,
+    }
+}
+
+usecase! {
+    similar : {
+        pub user_id: entities::UserId,
+        pub page: u32,
+    } => {
+        /// (index, user, number of shared bookmarks)
+        pub users: ::smallvec::SmallVec<[(u32, entities::User, u32); 5]>,
+        pub page: u32,
     }
 }
 
@@ -28,6 +53,8 @@ usecase! {
     edit : {
         pub user_id: entities::UserId,
         pub mutation: super::UserMutation,
+        pub executor_id: entities::UserId,
+        pub executed_at: entities::Date,
     } => {
         pub user: entities::User,
     }
@@ -36,8 +63,11 @@ usecase! {
 usecase! {
     unregister : {
         pub user_id: entities::UserId,
+        pub posted: super::PostedContents,
     } => {
         pub user: entities::User,
+        /// number of reassigned (or withdrawn) contents.
+        pub posted_changed: u64,
     }
 }
 
@@ -48,6 +78,40 @@ usecase! {
     } => {
         pub bookmark: ::smallvec::SmallVec<[(u32, entities::ContentId); 20]>,
         pub page: u32,
+        pub total_items: usize,
+        pub total_pages: u32,
+    }
+}
+
+usecase! {
+    get_bookmark_contents : {
+        pub user_id: entities::UserId,
+        pub page: u32,
+    } => {
+        /// `None` if content is already withdrawn.
+        pub contents: ::smallvec::SmallVec<
+            [(u32, entities::ContentId, Option<entities::Content>); 20],
+        >,
+        pub page: u32,
+    }
+}
+
+usecase! {
+    count_bookmark : {
+        pub user_id: entities::UserId,
+    } => {
+        pub user_id: entities::UserId,
+        pub count: u32,
+    }
+}
+
+usecase! {
+    export_bookmark : {
+        pub user_id: entities::UserId,
+    } => {
+        pub user_id: entities::UserId,
+        /// `None` if content is already withdrawn.
+        pub contents: Vec<(entities::ContentId, Option<entities::Content>)>,
     }
 }
 
@@ -71,19 +135,62 @@ usecase! {
     }
 }
 
+usecase! {
+    toggle_bookmark : {
+        pub user_id: entities::UserId,
+        pub content_id: entities::ContentId,
+    } => {
+        pub user: entities::User,
+        pub id: entities::ContentId,
+        /// whether bookmarked after toggled.
+        pub bookmarked: bool,
+    }
+}
+
 use core::ops::Bound;
 use std::collections::HashSet;
 
-use crate::entities::ContentId;
+use crate::entities::{ContentId, Date, UserId};
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, ::serde::Serialize, ::serde::Deserialize)]
+#[serde(default)]
 pub struct UserQuery {
     pub bookmark: Option<HashSet<ContentId>>,
+    #[serde(with = "super::query_serde::option_range")]
     pub bookmark_num: Option<(Bound<u32>, Bound<u32>)>,
+    pub admin: Option<bool>,
+    pub sub_admin: Option<bool>,
+}
+impl UserQuery {
+    /// same as [`ContentQuery::check_ids`](super::content::ContentQuery::check_ids).
+    pub fn check_ids(&self, max: usize) -> Result<(), String> {
+        match &self.bookmark {
+            Some(s) => super::check_query_ids("bookmark", s.len(), max),
+            None => Ok(()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct UserMutation {
     pub admin: Option<bool>,
     pub sub_admin: Option<bool>,
+    /// set by interactor on granting, not by users.
+    pub granted_by: Option<UserId>,
+    pub granted_at: Option<Date>,
+}
+
+/// what to do with contents posted by unregistered user.
+#[derive(Debug, Clone)]
+pub enum PostedContents {
+    /// left as is, still referring to unregistered user.
+    Keep,
+    /// `posted` of them is replaced with other (registered) user.
+    Reassign {
+        to: UserId,
+        /// looks up name and nick of new user.
+        resolver: ::alloc::sync::Arc<dyn super::content::AuthorResolver + Sync + Send>,
+    },
+    /// all of them are withdrawn (archived).
+    Withdraw { at: Date },
 }
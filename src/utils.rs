@@ -41,81 +41,35 @@ pub fn date_to_string(dt: crate::entities::Date) -> String {
     dt.to_rfc3339_opts(::chrono::SecondsFormat::Nanos, true)
 }
 
-pub trait ConvertRange<T>: ::core::ops::RangeBounds<T> {
-    fn to_turple(self) -> (::core::ops::Bound<T>, ::core::ops::Bound<T>);
-}
-impl<T> ConvertRange<T> for ::core::ops::Range<T> {
-    fn to_turple(self) -> (::core::ops::Bound<T>, ::core::ops::Bound<T>) {
-        let ::core::ops::Range { start, end } = self;
-        (
-            ::core::ops::Bound::Included(start),
-            ::core::ops::Bound::Excluded(end),
-        )
-    }
-}
-impl<T> ConvertRange<T> for ::core::ops::RangeFrom<T> {
-    fn to_turple(self) -> (::core::ops::Bound<T>, ::core::ops::Bound<T>) {
-        let ::core::ops::RangeFrom { start } = self;
-        (
-            ::core::ops::Bound::Included(start),
-            ::core::ops::Bound::Unbounded,
-        )
-    }
-}
-impl<T> ConvertRange<T> for ::core::ops::RangeFull {
-    fn to_turple(self) -> (::core::ops::Bound<T>, ::core::ops::Bound<T>) {
-        (::core::ops::Bound::Unbounded, ::core::ops::Bound::Unbounded)
-    }
-}
-impl<T> ConvertRange<T> for ::core::ops::RangeInclusive<T> {
-    fn to_turple(self) -> (::core::ops::Bound<T>, ::core::ops::Bound<T>) {
-        let (start, end) = self.into_inner();
-        (
-            ::core::ops::Bound::Included(start),
-            ::core::ops::Bound::Included(end),
-        )
-    }
-}
-impl<T> ConvertRange<T> for ::core::ops::RangeTo<T> {
-    fn to_turple(self) -> (::core::ops::Bound<T>, ::core::ops::Bound<T>) {
-        let ::core::ops::RangeTo { end } = self;
-        (
-            ::core::ops::Bound::Unbounded,
-            ::core::ops::Bound::Excluded(end),
-        )
-    }
-}
-impl<T> ConvertRange<T> for ::core::ops::RangeToInclusive<T> {
-    fn to_turple(self) -> (::core::ops::Bound<T>, ::core::ops::Bound<T>) {
-        let ::core::ops::RangeToInclusive { end } = self;
-        (
-            ::core::ops::Bound::Unbounded,
-            ::core::ops::Bound::Included(end),
-        )
-    }
-}
-impl<T> ConvertRange<T> for (::core::ops::Bound<T>, ::core::ops::Bound<T>) {
-    fn to_turple(self) -> (::core::ops::Bound<T>, ::core::ops::Bound<T>) { self }
-}
+/// trims whitespaces around content and at end of each line, and collapses
+/// runs of blank lines into at most two. CRLF (and lone CR) becomes LF.
+/// indentation and whitespaces inside lines are kept.
+pub fn normalize_content(s: &str) -> String {
+    const MAX_BLANK_LINES: usize = 2;
 
-pub fn convert_range_display<T: ConvertRange<R> + Clone, R: ToString>(t: T) -> String {
-    use ::core::ops::Bound::*;
+    let s = s.replace("\r\n", "\n").replace('\r', "\n");
 
-    let (s, e) = t.to_turple();
+    let mut lines = vec![];
+    let mut blanks = 0;
+    for line in s.trim().split('\n').map(str::trim_end) {
+        match line.is_empty() {
+            true => blanks += 1,
+            false => blanks = 0,
+        }
 
-    let ss = match s {
-        Included(n) => n.to_string(),
-        Excluded(n) => n.to_string(),
-        Unbounded => String::new(),
-    };
+        if blanks <= MAX_BLANK_LINES {
+            lines.push(line);
+        }
+    }
 
-    let es = match e {
-        Included(n) => n.to_string(),
-        Excluded(n) => n.to_string(),
-        Unbounded => String::new(),
-    };
+    lines.join("\n")
+}
 
-    format!("{}..{}", ss, es)
+/// best-effort. unreliable results are discarded.
+pub fn detect_lang(s: &str) -> Option<String> {
+    ::whatlang::detect(s)
+        .filter(|i| i.is_reliable())
+        .map(|i| i.lang().code().to_string())
 }
 
 pub trait FutureTranspose {
@@ -138,3 +92,30 @@ where F: ::core::future::Future<Output = O>
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_crlf_and_blank_lines() {
+        assert_eq!(normalize_content("a\r\nb\rc"), "a\nb\nc");
+        assert_eq!(normalize_content("a\r\n\r\n\r\n\r\n\r\nb"), "a\n\n\nb");
+        // lines of only whitespaces are blank too.
+        assert_eq!(normalize_content("a\n \n\t\n  \n\nb"), "a\n\n\nb");
+    }
+
+    #[test]
+    fn normalize_keeps_inner_whitespaces() {
+        assert_eq!(
+            normalize_content("\t\n  a\tb  \n\t  c\t\n\n"),
+            "a\tb\n\t  c"
+        );
+        // ideographic space and no-break space.
+        assert_eq!(
+            normalize_content("\u{3000}a\u{3000}b\u{a0}\n"),
+            "a\u{3000}b"
+        );
+        assert_eq!(normalize_content(" \r\n\u{3000}\t"), "");
+    }
+}
@@ -0,0 +1,1638 @@
+#![cfg(feature = "testing")]
+
+use icey_pudding::testing::{Harness, Output};
+use icey_pudding::{Config, Locale};
+
+fn content_id(out: &Output) -> String {
+    out.descriptions()[0]
+        .split_whitespace()
+        .next()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn register_post_like_get() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let posted = h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    let id = content_id(&posted);
+
+    let liked = h
+        .exec(format!("*ip content like do {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert!(liked.descriptions()[0].ends_with(id.as_str()));
+
+    let got = h
+        .exec(format!("*ip content get {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "like").as_deref(), Some("1"));
+    assert_eq!(got.field(0, "content").as_deref(), Some("hello"));
+}
+
+#[tokio::test]
+async fn get_shows_own_flags() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    let bob = h.executor(2, "bob");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+    let posted = h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    let id = content_id(&posted);
+
+    h.exec(format!("*ip content like do {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    h.exec(format!("*ip user bookmark do {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+
+    let get = format!("*ip content get {}", id);
+    let got = h.exec(get.as_str(), &alice).await.unwrap();
+    assert_eq!(got.field(0, "you liked").as_deref(), Some("true"));
+    assert_eq!(got.field(0, "you pinned").as_deref(), Some("false"));
+    assert_eq!(got.field(0, "you bookmarked").as_deref(), Some("true"));
+
+    // not registered.
+    let got = h.exec(get.as_str(), &bob).await.unwrap();
+    assert_eq!(got.field(0, "you liked").as_deref(), Some("false"));
+    assert_eq!(got.field(0, "you bookmarked").as_deref(), Some("false"));
+}
+
+#[tokio::test]
+async fn like_show_pages_in_order() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let posted = h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    let id = content_id(&posted);
+
+    for i in (2..=22).rev() {
+        let ex = h.executor(i, format!("user{}", i));
+        h.exec("*ip user register", &ex).await.unwrap();
+        h.exec(format!("*ip content like do {}", id).as_str(), &ex)
+            .await
+            .unwrap();
+    }
+
+    let first = h
+        .exec(format!("*ip content like show {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert_eq!(first.descriptions().len(), 20);
+    assert_eq!(first.field(0, "id").as_deref(), Some("2"));
+
+    let last = h
+        .exec(format!("*ip content like show {} 2", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert_eq!(last.descriptions(), vec!["0 in 2"]);
+    assert_eq!(last.field(0, "id").as_deref(), Some("22"));
+
+    assert!(h
+        .exec(format!("*ip content like show {} 3", id).as_str(), &alice)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn bookmark_show_sorted() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    for i in 0..5 {
+        let posted = h
+            .exec(
+                format!("*ip content post -v bob -c hello{}", i).as_str(),
+                &alice,
+            )
+            .await
+            .unwrap();
+        h.exec(
+            format!("*ip user bookmark do {}", content_id(&posted)).as_str(),
+            &alice,
+        )
+        .await
+        .unwrap();
+    }
+
+    let shown = h.exec("*ip user bookmark show", &alice).await.unwrap();
+    let ids = (0..5)
+        .map(|i| shown.field(i, "id").unwrap())
+        .collect::<Vec<_>>();
+    let mut sorted = ids.clone();
+    sorted.sort();
+    assert_eq!(ids, sorted);
+}
+
+#[tokio::test]
+async fn like_twice_fails() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+    let posted = h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    let id = content_id(&posted);
+
+    let like = format!("*ip content like do {}", id);
+    h.exec(like.as_str(), &alice).await.unwrap();
+    assert!(h.exec(like.as_str(), &alice).await.is_err());
+}
+
+#[tokio::test]
+async fn count_like_and_bookmark() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    let bob = h.executor(2, "bob");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+    h.exec("*ip user register", &bob).await.unwrap();
+    let posted = h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    let id = content_id(&posted);
+
+    let count = format!("*ip content like count {}", id);
+    let counted = h.exec(count.as_str(), &alice).await.unwrap();
+    assert!(counted.descriptions()[0].starts_with("0 users"));
+
+    let like = format!("*ip content like do {}", id);
+    h.exec(like.as_str(), &alice).await.unwrap();
+    h.exec(like.as_str(), &bob).await.unwrap();
+    let counted = h.exec(count.as_str(), &alice).await.unwrap();
+    assert!(counted.descriptions()[0].starts_with("2 users"));
+
+    h.exec(format!("*ip user bookmark do {}", id).as_str(), &bob)
+        .await
+        .unwrap();
+    let counted = h.exec("*ip user bookmark count 2", &alice).await.unwrap();
+    assert!(counted.descriptions()[0].ends_with("bookmarked 1 contents"));
+}
+
+#[tokio::test]
+async fn titles_follow_locale() {
+    let h = Harness::in_memory(Config {
+        locale: Locale::Ja,
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+
+    let registered = h.exec("*ip user register", &alice).await.unwrap();
+    assert_eq!(registered.titles(), vec!["ユーザーを登録しました"]);
+
+    let posted = h
+        .exec("*ip content post -v bob -c hello -t greeting", &alice)
+        .await
+        .unwrap();
+    assert_eq!(posted.titles(), vec!["投稿されたコンテンツ: greeting"]);
+}
+
+#[tokio::test]
+async fn status_without_registration() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    let status = h.exec("*ip user status", &alice).await.unwrap();
+    assert_eq!(status.descriptions(), vec!["1"]);
+    assert_eq!(status.field(0, "registered").as_deref(), Some("false"));
+
+    h.exec("*ip user register", &alice).await.unwrap();
+    let status = h.exec("*ip user status", &alice).await.unwrap();
+    assert_eq!(status.field(0, "registered").as_deref(), Some("true"));
+}
+
+#[tokio::test]
+async fn unregistered_cannot_post() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    assert!(h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn unregister_reassign_and_purge_conflict() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let e = h
+        .exec("*ip user unregister 1 --reassign-to 2 --purge", &alice)
+        .await
+        .unwrap_err();
+    assert!(e.to_string().contains("cannot be used with"));
+}
+
+#[tokio::test]
+async fn cmd_names_ignore_args_and_flags() {
+    let h = Harness::in_memory(Config::default());
+
+    assert_eq!(h.cmd_name("*ip ping").await.unwrap(), "ping");
+    assert_eq!(
+        h.cmd_name("*ip content like do abc123").await.unwrap(),
+        "content.like.do"
+    );
+    assert_eq!(
+        h.cmd_name("*ip -C -l -d abc123").await.unwrap(),
+        "content.like.do"
+    );
+    assert_eq!(
+        h.cmd_name("*ip user bookmark export").await.unwrap(),
+        "user.bookmark.export"
+    );
+}
+
+#[tokio::test]
+async fn gets_footer_shows_pages_and_total() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    for i in 0..7 {
+        h.exec(
+            format!("*ip content post -v bob -c hello{}", i).as_str(),
+            &alice,
+        )
+        .await
+        .unwrap();
+    }
+
+    let got = h.exec("*ip content gets '{}' 2", &alice).await.unwrap();
+    assert_eq!(got.footers(), vec!["page 2 of 2 — 7 total"; 2]);
+}
+
+#[tokio::test]
+async fn content_cache_invalidated_on_mutation() {
+    let h = Harness::in_memory(Config {
+        content_cache: Some(1),
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let posted = h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    let id = content_id(&posted);
+    let posted = h
+        .exec("*ip content post -v bob -c other", &alice)
+        .await
+        .unwrap();
+    let other = content_id(&posted);
+    let get = format!("*ip content get {}", id);
+
+    // cached here.
+    let got = h.exec(get.as_str(), &alice).await.unwrap();
+    assert_eq!(got.field(0, "like").as_deref(), Some("0"));
+
+    h.exec(format!("*ip content like do {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    let got = h.exec(get.as_str(), &alice).await.unwrap();
+    assert_eq!(got.field(0, "like").as_deref(), Some("1"));
+
+    h.exec(
+        format!(
+            r#"*ip content edit {} '{{"content": {{"Complete": "world"}}}}'"#,
+            id
+        )
+        .as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+    let got = h.exec(get.as_str(), &alice).await.unwrap();
+    assert_eq!(got.field(0, "content").as_deref(), Some("world"));
+
+    // evicts the first one.
+    h.exec(format!("*ip content get {}", other).as_str(), &alice)
+        .await
+        .unwrap();
+    let got = h.exec(get.as_str(), &alice).await.unwrap();
+    assert_eq!(got.field(0, "content").as_deref(), Some("world"));
+
+    h.exec(format!("*ip content withdraw {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert!(h.exec(get.as_str(), &alice).await.is_err());
+}
+
+#[tokio::test]
+async fn edit_author_to_virtual_placeholder() {
+    for fallback in [false, true] {
+        let h = Harness::in_memory(Config {
+            virtual_author_fallback: fallback,
+            ..Config::default()
+        });
+        let alice = h.executor(1, "alice");
+        h.exec("*ip user register", &alice).await.unwrap();
+
+        let posted = h
+            .exec("*ip content post -v bob -c hello", &alice)
+            .await
+            .unwrap();
+        let id = content_id(&posted);
+
+        let edited = h
+            .exec(
+                format!(
+                    r#"*ip content edit {} '{{"author": {{"VirtualFromUser": {{"id": 5}}}}}}'"#,
+                    id
+                )
+                .as_str(),
+                &alice,
+            )
+            .await
+            .unwrap();
+        assert_eq!(edited.field(0, "author").as_deref(), Some("user:5"));
+
+        // looking up users always fails offline.
+        let edited = h
+            .exec(
+                format!(r#"*ip content edit {} '{{"author": {{"User": 6}}}}'"#, id).as_str(),
+                &alice,
+            )
+            .await;
+        match fallback {
+            true => assert_eq!(
+                edited.unwrap().field(0, "author").as_deref(),
+                Some("user:6")
+            ),
+            false => assert!(edited.is_err()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn gets_by_created_range() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    for (i, at) in [
+        "2022-01-01T00:00:00Z",
+        "2022-06-01T00:00:00.5Z",
+        "2023-01-01T00:00:00Z",
+    ]
+    .iter()
+    .enumerate()
+    {
+        let at = chrono::DateTime::parse_from_rfc3339(at)
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        h.exec(
+            format!("*ip content post -v bob -c hello{}", i).as_str(),
+            &alice.clone().at(at),
+        )
+        .await
+        .unwrap();
+    }
+
+    let got = h
+        .exec(
+            r#"*ip content gets '{"created_range": "2022-01-01T00:00:00Z..2023-01-01T00:00:00Z", "sort": {"key": "Created"}}'"#,
+            &alice,
+        )
+        .await
+        .unwrap();
+    let contents = (0..2)
+        .map(|i| got.field(i, "content").unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(contents, vec!["hello0", "hello1"]);
+    assert_eq!(got.footers(), vec!["page 1 of 1 — 2 total"; 2]);
+}
+
+#[tokio::test]
+async fn json_output() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    let bob = h.executor(2, "bob");
+    h.exec("*ip user register", &alice).await.unwrap();
+    h.exec("*ip user register", &bob).await.unwrap();
+
+    let json = |o: Output| match o {
+        Output::Json(v) => v,
+        o => panic!("expected json, found: {:?}", o),
+    };
+
+    let got = json(h.exec("*ip --json user get", &alice).await.unwrap());
+    assert_eq!(got["id"], 1);
+    assert_eq!(got["admin"], false);
+
+    let id = content_id(
+        &h.exec("*ip content post -v carol -c hello --anon", &alice)
+            .await
+            .unwrap(),
+    );
+
+    // also accepted after subcommand.
+    let got = json(
+        h.exec(format!("*ip content get {} --json", id).as_str(), &bob)
+            .await
+            .unwrap(),
+    );
+    assert_eq!(got["content"]["content"], "hello");
+    assert_eq!(got["content"]["author"]["Virtual"], "carol");
+    assert!(got["content"]["posted"].is_null());
+    assert_eq!(got["liked_by_me"], false);
+
+    let got = json(h.exec("*ip --json content gets", &bob).await.unwrap());
+    assert_eq!(got["contents"].as_array().unwrap().len(), 1);
+    assert_eq!(got["total_items"], 1);
+
+    // not serializable ones.
+    assert!(h.exec("*ip --json user status", &alice).await.is_err());
+}
+
+#[tokio::test]
+async fn gets_rejects_too_many_query_ids() {
+    let h = Harness::in_memory(Config {
+        max_query_ids: 2,
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let ids = |n: u64| {
+        (1..=n)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    // rejected by parser, regardless of config.
+    let e = h
+        .exec(
+            format!(r#"*ip content gets '{{"liked": [{}]}}'"#, ids(101)).as_str(),
+            &alice,
+        )
+        .await
+        .unwrap_err();
+    assert!(e.to_string().contains("too many ids in liked"));
+
+    // rejected by config.
+    let e = h
+        .exec(
+            format!(
+                r#"*ip content gets '{{"any_of": [{{"pinned": [{}]}}]}}'"#,
+                ids(3)
+            )
+            .as_str(),
+            &alice,
+        )
+        .await
+        .unwrap_err();
+    assert!(e.to_string().contains("too many ids in pinned"));
+
+    h.exec(
+        format!(r#"*ip content gets '{{"liked": [{}]}}'"#, ids(2)).as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn stats_shows_counts_and_rankings() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    let bob = h.executor(2, "bob");
+    h.exec("*ip user register", &alice).await.unwrap();
+    h.exec("*ip user register", &bob).await.unwrap();
+
+    let got = h.exec("*ip stats", &alice).await.unwrap();
+    assert_eq!(got.field(0, "contents").as_deref(), Some("0"));
+    assert_eq!(got.field(0, "top liked").as_deref(), Some("None"));
+
+    let first = content_id(
+        &h.exec("*ip content post -v x -c first", &alice)
+            .await
+            .unwrap(),
+    );
+    let second = content_id(
+        &h.exec("*ip content post -v x -c second", &alice)
+            .await
+            .unwrap(),
+    );
+    for ex in [&alice, &bob] {
+        h.exec(format!("*ip content like do {}", second).as_str(), ex)
+            .await
+            .unwrap();
+    }
+    h.exec(format!("*ip content like do {}", first).as_str(), &bob)
+        .await
+        .unwrap();
+
+    let got = h.exec("*ip stats", &alice).await.unwrap();
+    assert_eq!(got.field(0, "contents").as_deref(), Some("2"));
+    assert_eq!(got.field(0, "users").as_deref(), Some("2"));
+    assert_eq!(
+        got.field(0, "top liked"),
+        Some(format!("1. {} (2)\n2. {} (1)", second, first))
+    );
+    // ties are older first.
+    assert_eq!(
+        got.field(0, "top pinned"),
+        Some(format!("1. {} (0)\n2. {} (0)", first, second))
+    );
+}
+
+#[tokio::test]
+async fn withdraw_needs_confirmation() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let id = content_id(
+        &h.exec("*ip content post -v bob -c hello", &alice)
+            .await
+            .unwrap(),
+    );
+    let get = format!("*ip content get {}", id);
+    let withdraw = format!("*ip content withdraw {}", id);
+
+    // non-destructive ones are executed regardless of answer.
+    assert!(h
+        .exec_confirming(get.as_str(), &alice, false)
+        .await
+        .unwrap()
+        .is_some());
+
+    let cancelled = h
+        .exec_confirming(withdraw.as_str(), &alice, false)
+        .await
+        .unwrap();
+    assert!(cancelled.is_none());
+    h.exec(get.as_str(), &alice).await.unwrap();
+
+    let confirmed = h
+        .exec_confirming(withdraw.as_str(), &alice, true)
+        .await
+        .unwrap();
+    assert!(confirmed.is_some());
+    assert!(h.exec(get.as_str(), &alice).await.is_err());
+
+    // expired before answered.
+    let h = Harness::in_memory(Config {
+        confirm_timeout: Some(::core::time::Duration::ZERO),
+        ..Config::default()
+    });
+    h.exec("*ip user register", &alice).await.unwrap();
+    let id = content_id(
+        &h.exec("*ip content post -v bob -c hello", &alice)
+            .await
+            .unwrap(),
+    );
+    let e = h
+        .exec_confirming(
+            format!("*ip content withdraw {}", id).as_str(),
+            &alice,
+            true,
+        )
+        .await
+        .unwrap_err();
+    assert!(e.to_string().contains("expired"));
+}
+
+#[tokio::test]
+async fn gets_by_has_likes_and_pins() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let liked = content_id(
+        &h.exec("*ip content post -v bob -c liked", &alice)
+            .await
+            .unwrap(),
+    );
+    h.exec("*ip content post -v bob -c unnoticed", &alice)
+        .await
+        .unwrap();
+    h.exec(format!("*ip content like do {}", liked).as_str(), &alice)
+        .await
+        .unwrap();
+
+    let got = h
+        .exec(r#"*ip content gets '{"has_likes": false}'"#, &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "content").as_deref(), Some("unnoticed"));
+    assert_eq!(got.field(1, "content"), None);
+
+    let got = h
+        .exec(r#"*ip content gets '{"has_likes": true}'"#, &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "content").as_deref(), Some("liked"));
+    assert_eq!(got.field(1, "content"), None);
+
+    assert!(h
+        .exec(r#"*ip content gets '{"has_pins": true}'"#, &alice)
+        .await
+        .is_err());
+
+    let e = h
+        .exec(
+            r#"*ip content gets '{"has_likes": true, "liked_eq": 1}'"#,
+            &alice,
+        )
+        .await
+        .unwrap_err();
+    assert!(e.to_string().contains("cannot be used with"));
+}
+
+#[tokio::test]
+async fn page_out_of_range_tells_max() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    // 5 per page.
+    for i in 0..10 {
+        h.exec(
+            format!("*ip content post -v bob -c hello{}", i).as_str(),
+            &alice,
+        )
+        .await
+        .unwrap();
+    }
+
+    h.exec("*ip content gets '{}' 2", &alice).await.unwrap();
+    let e = h.exec("*ip content gets '{}' 3", &alice).await.unwrap_err();
+    assert_eq!(e.to_string(), "page 3 out of range (max 2)");
+    let e = h
+        .exec("*ip content gets '{}' 99", &alice)
+        .await
+        .unwrap_err();
+    assert_eq!(e.to_string(), "page 99 out of range (max 2)");
+
+    let e = h.exec("*ip user bookmark show", &alice).await.unwrap_err();
+    assert_eq!(e.to_string(), "page 1 out of range (no items)");
+}
+
+#[tokio::test]
+async fn reject_duplicate_body() {
+    let h = Harness::in_memory(Config {
+        reject_duplicate_body: true,
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+    let bob = h.executor(2, "bob");
+    h.exec("*ip user register", &alice).await.unwrap();
+    h.exec("*ip user register", &bob).await.unwrap();
+
+    let posted = h
+        .exec("*ip content post -v x -c hello", &alice)
+        .await
+        .unwrap();
+    let e = h
+        .exec("*ip content post -v y -c hello", &alice)
+        .await
+        .unwrap_err();
+    assert!(e.to_string().contains("already posted"));
+
+    // by other user.
+    h.exec("*ip content post -v x -c hello", &bob)
+        .await
+        .unwrap();
+
+    h.exec(
+        format!("*ip content withdraw {}", content_id(&posted)).as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+    h.exec("*ip content post -v x -c hello", &alice)
+        .await
+        .unwrap();
+
+    // off by default.
+    let h = Harness::in_memory(Config::default());
+    h.exec("*ip user register", &alice).await.unwrap();
+    for _ in 0..2 {
+        h.exec("*ip content post -v x -c hello", &alice)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn post_not_affected_by_failing_notification() {
+    let h = Harness::in_memory(Config {
+        // nothing listens on discard port.
+        notify_url: Some("http://127.0.0.1:9/".to_string()),
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let posted = h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    h.exec(
+        format!("*ip content get {}", content_id(&posted)).as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn help_renders_subcommand() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    let got = h.exec("*ip help", &alice).await.unwrap();
+    assert_eq!(got.titles(), vec!["help".to_string()]);
+    let short_forms = got.field(0, "short forms").unwrap();
+    assert!(short_forms.contains("`-C` content"));
+    assert!(short_forms.contains("`-H` help"));
+
+    let got = h.exec("*ip help content like", &alice).await.unwrap();
+    assert_eq!(got.titles(), vec!["help: content like".to_string()]);
+    let description = &got.descriptions()[0];
+    assert!(description.contains("do"));
+    assert!(description.contains("undo"));
+
+    assert!(h.exec("*ip help content nothing", &alice).await.is_err());
+}
+
+#[tokio::test]
+async fn like_show_detailed() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    let bob = h.executor(2, "bob");
+    h.exec("*ip user register", &alice).await.unwrap();
+    h.exec("*ip user register", &bob).await.unwrap();
+
+    let id = content_id(
+        &h.exec("*ip content post -v x -c hello", &alice)
+            .await
+            .unwrap(),
+    );
+    h.exec(format!("*ip content like do {}", id).as_str(), &bob)
+        .await
+        .unwrap();
+
+    let got = h
+        .exec(format!("*ip content like show {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "admin"), None);
+
+    let got = h
+        .exec(
+            format!("*ip content like show --detailed {}", id).as_str(),
+            &alice,
+        )
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "id").as_deref(), Some("2"));
+    assert_eq!(got.field(0, "name").as_deref(), Some("<@2>"));
+    assert_eq!(got.field(0, "admin").as_deref(), Some("false"));
+}
+
+#[tokio::test]
+async fn gets_nothing_matched_is_not_error() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let got = h.exec("*ip content gets", &alice).await.unwrap();
+    assert_eq!(got.descriptions(), vec!["no contents matched your query."]);
+
+    let got = h
+        .exec(r#"*ip user gets '{"admin": true}'"#, &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.descriptions(), vec!["no users matched your query."]);
+}
+
+#[tokio::test]
+async fn configured_page_sizes() {
+    let h = Harness::in_memory(Config {
+        gets_page_size: 2,
+        list_page_size: 3,
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let mut id = None;
+    for i in 0..3 {
+        let posted = h
+            .exec(
+                format!("*ip content post -v bob -c hello{}", i).as_str(),
+                &alice,
+            )
+            .await
+            .unwrap();
+        id = Some(content_id(&posted));
+    }
+    let id = id.unwrap();
+
+    let got = h.exec("*ip content gets '{}'", &alice).await.unwrap();
+    assert_eq!(got.descriptions().len(), 2);
+    let got = h.exec("*ip content gets '{}' 2", &alice).await.unwrap();
+    assert_eq!(got.descriptions().len(), 1);
+    let e = h.exec("*ip content gets '{}' 3", &alice).await.unwrap_err();
+    assert_eq!(e.to_string(), "page 3 out of range (max 2)");
+
+    for i in 2..=5 {
+        let ex = h.executor(i, format!("user{}", i));
+        h.exec("*ip user register", &ex).await.unwrap();
+        h.exec(format!("*ip content like do {}", id).as_str(), &ex)
+            .await
+            .unwrap();
+    }
+
+    let got = h
+        .exec(format!("*ip content like show {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.descriptions().len(), 3);
+    let got = h
+        .exec(format!("*ip content like show {} 2", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.descriptions(), vec!["0 in 2"]);
+}
+
+#[tokio::test]
+async fn merge_moves_reactions_and_bookmarks() {
+    let h = Harness::in_memory(Config {
+        confirm_timeout: None,
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+    let bob = h.executor(2, "bob");
+    let carol = h.executor(3, "carol");
+    for ex in [&alice, &bob, &carol] {
+        h.exec("*ip user register", ex).await.unwrap();
+    }
+
+    let from = content_id(
+        &h.exec("*ip content post -v x -c dup", &alice)
+            .await
+            .unwrap(),
+    );
+    let into = content_id(
+        &h.exec("*ip content post -v x -c original", &alice)
+            .await
+            .unwrap(),
+    );
+
+    // bob reacted to both, carol only to `from`.
+    for id in [&from, &into] {
+        h.exec(format!("*ip content like do {}", id).as_str(), &bob)
+            .await
+            .unwrap();
+        h.exec(format!("*ip user bookmark do {}", id).as_str(), &bob)
+            .await
+            .unwrap();
+    }
+    h.exec(format!("*ip content like do {}", from).as_str(), &carol)
+        .await
+        .unwrap();
+    h.exec(format!("*ip content pin do {}", from).as_str(), &carol)
+        .await
+        .unwrap();
+    h.exec(format!("*ip user bookmark do {}", from).as_str(), &carol)
+        .await
+        .unwrap();
+
+    let merge = format!("*ip content merge {} {}", from, into);
+    assert!(h.exec(merge.as_str(), &alice).await.is_err());
+
+    h.grant_admin(&alice).await.unwrap();
+    assert!(h
+        .exec(
+            format!("*ip content merge {} {}", into, into).as_str(),
+            &alice
+        )
+        .await
+        .is_err());
+
+    let got = h.exec(merge.as_str(), &alice).await.unwrap();
+    assert_eq!(got.field(0, "like_times").as_deref(), Some("2"));
+    assert_eq!(got.field(0, "pinned_times").as_deref(), Some("1"));
+    assert_eq!(got.field(0, "moved_bookmarks").as_deref(), Some("2"));
+
+    for ex in [&bob, &carol] {
+        let got = h.exec("*ip user bookmark show", ex).await.unwrap();
+        assert_eq!(got.descriptions().len(), 1);
+        assert_eq!(got.field(0, "id"), Some(into.clone()));
+    }
+
+    // `from` is withdrawn.
+    assert!(h
+        .exec(format!("*ip content get {}", from).as_str(), &alice)
+        .await
+        .is_err());
+    assert!(h.exec(merge.as_str(), &alice).await.is_err());
+}
+
+#[tokio::test]
+async fn post_with_source_url() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    for bad in ["nope", "ftp://example.com/a", "https://"] {
+        let raw = format!("*ip content post -v x -c a --source-url {}", bad);
+        assert!(h.exec(raw.as_str(), &alice).await.is_err());
+    }
+
+    let posted = h
+        .exec(
+            "*ip content post -v x -c a --source-url https://example.com/a",
+            &alice,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        posted.field(0, "source").as_deref(),
+        Some("https://example.com/a")
+    );
+
+    let got = h
+        .exec(
+            format!("*ip content get {}", content_id(&posted)).as_str(),
+            &alice,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        got.field(0, "source").as_deref(),
+        Some("https://example.com/a")
+    );
+
+    let plain = h.exec("*ip content post -v x -c b", &alice).await.unwrap();
+    assert_eq!(plain.field(0, "source"), None);
+}
+
+#[tokio::test]
+async fn gets_by_cursor() {
+    let h = Harness::in_memory(Config {
+        gets_page_size: 2,
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let start = chrono::Utc::now();
+    let post = |i: i64, anon: bool| {
+        let raw = format!(
+            "*ip content post -v bob -c hello{}{}",
+            i,
+            if anon { " --anon" } else { "" }
+        );
+        let ex = alice.clone().at(start + chrono::Duration::seconds(i));
+        let h = &h;
+        async move { content_id(&h.exec(raw.as_str(), &ex).await.unwrap()) }
+    };
+    let mut ids = vec![];
+    for i in 0..4 {
+        ids.push(post(i, i == 1).await);
+    }
+
+    let ids_of = |out: Output| {
+        out.descriptions()
+            .iter()
+            .map(|d| {
+                d.split(" | ")
+                    .nth(1)
+                    .unwrap()
+                    .split(' ')
+                    .next()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+    };
+    let gets = |args: String| {
+        let h = &h;
+        let alice = &alice;
+        async move {
+            h.exec(format!("*ip content gets {}", args).as_str(), alice)
+                .await
+        }
+    };
+
+    let got = gets(format!("'{{}}' --after {}", ids[0])).await.unwrap();
+    assert_eq!(ids_of(got), ids[1..3]);
+    let got = gets(format!("'{{}}' --before {}", ids[3])).await.unwrap();
+    assert_eq!(ids_of(got), ids[1..3]);
+    let got = gets(format!("'{{}}' --before {}", ids[0])).await.unwrap();
+    assert_eq!(got.descriptions(), vec!["no contents matched your query."]);
+
+    // contents added later don't shift the ones already seen.
+    ids.push(post(4, false).await);
+    let got = gets(format!("'{{}}' --after {}", ids[2])).await.unwrap();
+    assert_eq!(ids_of(got), ids[3..5]);
+
+    // filtered ones are skipped, and the page is still filled.
+    let got = gets(format!(
+        r#"'{{"posted": {{"UserId": 1}}}}' --after {}"#,
+        ids[0]
+    ))
+    .await
+    .unwrap();
+    assert_eq!(ids_of(got), ids[2..4]);
+
+    for args in [
+        format!(r#"'{{"sort": {{"key": "Created"}}}}' --after {}"#, ids[0]),
+        format!("'{{}}' --after {} 2", ids[0]),
+        format!("'{{}}' --after {} --before {}", ids[0], ids[3]),
+    ] {
+        assert!(gets(args).await.is_err());
+    }
+}
+
+#[tokio::test]
+async fn large_sets_fit_embed_limits() {
+    // discord rejects embeds over these.
+    const FIELD_VALUE_LIMIT: usize = 1024;
+    const EMBED_TOTAL_LIMIT: usize = 6000;
+
+    let h = Harness::in_memory(Config {
+        confirm_timeout: None,
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let tags = (0..300)
+        .map(|i| format!("--tag tag{:03}", i))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let id = content_id(
+        &h.exec(
+            format!("*ip content post -v x -c hello {}", tags).as_str(),
+            &alice,
+        )
+        .await
+        .unwrap(),
+    );
+    for i in 2..302 {
+        let ex = h.executor(i, format!("user{}", i));
+        h.exec("*ip user register", &ex).await.unwrap();
+        h.exec(format!("*ip content like do {}", id).as_str(), &ex)
+            .await
+            .unwrap();
+        h.exec(format!("*ip content pin do {}", id).as_str(), &ex)
+            .await
+            .unwrap();
+    }
+
+    // shown sets are capped, with summary.
+    for (raw, set, summary) in [
+        (
+            format!("*ip content get {}", id),
+            "tags",
+            "(300 tags, showing 20)",
+        ),
+        (
+            format!("*ip content withdraw {}", id),
+            "liked",
+            "(300 likers, showing 20",
+        ),
+    ] {
+        let got = h.exec(raw.as_str(), &alice).await.unwrap();
+        assert!(got.field(0, set).unwrap().contains(summary));
+
+        let fields = got.fields(0);
+        for (_, v) in &fields {
+            assert!(v.chars().count() <= FIELD_VALUE_LIMIT);
+        }
+        let total = got.titles()[0].chars().count()
+            + got.descriptions()[0].chars().count()
+            + fields
+                .iter()
+                .map(|(n, v)| n.chars().count() + v.chars().count())
+                .sum::<usize>();
+        assert!(total <= EMBED_TOTAL_LIMIT);
+    }
+}
+
+#[tokio::test]
+async fn edit_redetects_lang() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let posted = h
+        .exec(
+            "*ip content post -v bob -c 'The quick brown fox jumps over the lazy dog while the children are playing in the garden behind the house.'",
+            &alice,
+        )
+        .await
+        .unwrap();
+    let id = content_id(&posted);
+    let got = h
+        .exec(format!("*ip content get {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "lang").as_deref(), Some("eng"));
+
+    h.exec(
+        format!(
+            r#"*ip content edit {} '{{"content": {{"Complete": "Bonjour tout le monde, je suis très content de vous voir aujourd hui dans cette belle ville."}}}}'"#,
+            id
+        )
+        .as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+    let got = h
+        .exec(format!("*ip content get {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "lang").as_deref(), Some("fra"));
+
+    let got = h
+        .exec(r#"*ip content gets '{"lang": "fra"}'"#, &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(1, "content"), None);
+    assert!(got.field(0, "content").unwrap().starts_with("Bonjour"));
+}
+
+#[tokio::test]
+async fn posted_count_hides_anonymous_from_others() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    let bob = h.executor(2, "bob");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+    h.exec("*ip user register", &bob).await.unwrap();
+
+    h.exec("*ip content post -v carol -c open", &alice)
+        .await
+        .unwrap();
+    h.exec("*ip content post -v carol -c hidden --anon", &alice)
+        .await
+        .unwrap();
+
+    let own = h.exec("*ip user get", &alice).await.unwrap();
+    assert_eq!(own.field(0, "posted").as_deref(), Some("2"));
+
+    let other = h.exec("*ip user get 1", &bob).await.unwrap();
+    assert_eq!(other.field(0, "posted").as_deref(), Some("1"));
+}
+
+#[tokio::test]
+async fn archived_marked_in_title() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+    h.grant_admin(&alice).await.unwrap();
+
+    let id = content_id(
+        &h.exec("*ip content post -v bob -c hello", &alice)
+            .await
+            .unwrap(),
+    );
+
+    let got = h.exec("*ip content gets", &alice).await.unwrap();
+    assert!(!got.titles()[0].contains("archived"));
+
+    h.exec(format!("*ip content withdraw {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+
+    let got = h
+        .exec("*ip content gets --include-archived", &alice)
+        .await
+        .unwrap();
+    assert!(got.titles()[0].contains("(archived since "));
+}
+
+#[tokio::test]
+async fn post_limit_boundary() {
+    let h = Harness::in_memory(Config {
+        max_contents_per_user: Some(2),
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+    let carol = h.executor(2, "carol");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+    h.exec("*ip user register", &carol).await.unwrap();
+
+    let post = "*ip content post -v bob -c hello";
+    h.exec(post, &alice).await.unwrap();
+    h.exec(post, &alice).await.unwrap();
+    assert!(h.exec(post, &alice).await.is_err());
+
+    // counted per user.
+    h.exec(post, &carol).await.unwrap();
+}
+
+#[tokio::test]
+async fn suggests_close_subcommand() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    let e = h.exec("*ip user regster", &alice).await.unwrap_err();
+    assert!(e.to_string().contains("did you mean `*ip user register`"));
+
+    let e = h.exec("*ip user zzzzzzzz", &alice).await.unwrap_err();
+    assert!(!e.to_string().contains("did you mean"));
+}
+
+#[tokio::test]
+async fn post_cooldown_boundary() {
+    let h = Harness::in_memory(Config {
+        post_cooldown: Some(std::time::Duration::from_secs(60)),
+        ..Config::default()
+    });
+    let now = chrono::Utc::now();
+    let alice = h.executor(1, "alice").at(now);
+
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let post = "*ip content post -v bob -c hello";
+    h.exec(post, &alice).await.unwrap();
+
+    let soon = alice.clone().at(now + chrono::Duration::seconds(59));
+    let e = h.exec(post, &soon).await.unwrap_err();
+    assert!(e.to_string().contains("wait 1s"));
+
+    let later = alice.at(now + chrono::Duration::seconds(60));
+    h.exec(post, &later).await.unwrap();
+}
+
+#[tokio::test]
+async fn similar_users_by_bookmarks() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    let carol = h.executor(2, "carol");
+    let dave = h.executor(3, "dave");
+
+    for ex in [&alice, &carol, &dave] {
+        h.exec("*ip user register", ex).await.unwrap();
+    }
+
+    let posted = h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    let id = content_id(&posted);
+
+    let bookmark = format!("*ip user bookmark do {}", id);
+    h.exec(bookmark.as_str(), &alice).await.unwrap();
+    h.exec(bookmark.as_str(), &carol).await.unwrap();
+
+    let similar = h.exec("*ip user similar", &alice).await.unwrap();
+    assert_eq!(similar.descriptions(), vec!["0 in 1 | 2".to_string()]);
+    assert_eq!(similar.field(0, "shared_bookmark").as_deref(), Some("1"));
+}
+
+#[tokio::test]
+async fn user_id_accepts_mentions() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    for arg in ["1", "<@1>", "<@!1>"] {
+        let got = h
+            .exec(format!("*ip user get {}", arg).as_str(), &alice)
+            .await
+            .unwrap();
+        assert_eq!(got.descriptions(), vec!["1".to_string()]);
+    }
+
+    assert!(h.exec("*ip user get <#1>", &alice).await.is_err());
+}
+
+#[tokio::test]
+async fn gets_sorted_by_liked_count() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    h.exec("*ip content post -v bob -c first", &alice)
+        .await
+        .unwrap();
+    let second = h
+        .exec("*ip content post -v bob -c second", &alice)
+        .await
+        .unwrap();
+    h.exec(
+        format!("*ip content like do {}", content_id(&second)).as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+
+    let got = h
+        .exec(
+            r#"*ip content gets '{"sort": {"key": "LikedCount", "desc": true}}'"#,
+            &alice,
+        )
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "content").as_deref(), Some("second"));
+    assert_eq!(got.field(1, "content").as_deref(), Some("first"));
+
+    let got = h.exec("*ip content gets '{}'", &alice).await.unwrap();
+    assert_eq!(got.field(0, "content").as_deref(), Some("first"));
+}
+
+#[tokio::test]
+async fn gets_save_and_load() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    let bob = h.executor(2, "bob");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+    h.exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    h.exec("*ip content post -v bob -c world", &alice)
+        .await
+        .unwrap();
+
+    let saved = h
+        .exec(
+            r#"*ip content gets '{"content": "^he", "liked_num": "0..=1"}' --save hi"#,
+            &alice,
+        )
+        .await
+        .unwrap();
+    assert_eq!(saved.field(0, "content").as_deref(), Some("hello"));
+    assert_eq!(saved.field(1, "content"), None);
+
+    let loaded = h.exec("*ip content gets --load hi", &alice).await.unwrap();
+    assert_eq!(loaded.descriptions(), saved.descriptions());
+    assert_eq!(loaded.field(0, "content").as_deref(), Some("hello"));
+
+    // saved per user.
+    assert!(h.exec("*ip content gets --load hi", &bob).await.is_err());
+    assert!(h
+        .exec("*ip content gets --load nothing", &alice)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn content_length_boundary() {
+    let h = Harness::in_memory(Config {
+        max_content_len: 5,
+        ..Config::default()
+    });
+    let alice = h.executor(1, "alice");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let err = h
+        .exec("*ip content post -v bob -c hello!", &alice)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("max: 5 chars, actual: 6 chars"));
+
+    let posted = h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    let id = content_id(&posted);
+
+    let edit = |json: &str| format!("*ip content edit {} '{}'", id, json);
+    h.exec(
+        edit(r#"{"content": {"Complete": "world"}}"#).as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+    assert!(h
+        .exec(
+            edit(r#"{"content": {"Complete": "world!"}}"#).as_str(),
+            &alice
+        )
+        .await
+        .is_err());
+    // checked after applied.
+    assert!(h
+        .exec(
+            edit(r#"{"content": {"Sed": {"capture": "d$", "replace": "d!"}}}"#).as_str(),
+            &alice
+        )
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn bookmark_contents_with_withdrawn() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let kept = h
+        .exec("*ip content post -v bob -c kept", &alice)
+        .await
+        .unwrap();
+    let withdrawn = h
+        .exec("*ip content post -v bob -c withdrawn", &alice)
+        .await
+        .unwrap();
+    for out in [&kept, &withdrawn] {
+        h.exec(
+            format!("*ip user bookmark do {}", content_id(out)).as_str(),
+            &alice,
+        )
+        .await
+        .unwrap();
+    }
+    h.exec(
+        format!("*ip content withdraw {}", content_id(&withdrawn)).as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+
+    let got = h.exec("*ip user bookmark contents", &alice).await.unwrap();
+    let mut contents = (0..2)
+        .map(|i| got.field(i, "content").unwrap())
+        .collect::<Vec<_>>();
+    contents.sort();
+    assert_eq!(
+        contents,
+        vec!["(withdrawn)".to_string(), "kept".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn gets_by_exact_content() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+    h.exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    h.exec("*ip content post -v bob -c 'hello world'", &alice)
+        .await
+        .unwrap();
+    h.exec("*ip content post -v bob -c 'h.llo'", &alice)
+        .await
+        .unwrap();
+
+    let got = h
+        .exec(r#"*ip content gets '{"content_exact": "hello"}'"#, &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "content").as_deref(), Some("hello"));
+    assert_eq!(got.field(1, "content"), None);
+
+    // not regex.
+    let got = h
+        .exec(r#"*ip content gets '{"content_exact": "h.llo"}'"#, &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "content").as_deref(), Some("h.llo"));
+    assert_eq!(got.field(1, "content"), None);
+}
+
+#[tokio::test]
+async fn edit_author_and_append_at_once() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+    let posted = h
+        .exec("*ip content post -v bob -c hello", &alice)
+        .await
+        .unwrap();
+    let id = content_id(&posted);
+
+    h.exec(
+        format!(
+            r#"*ip content edit {} '{{"author": {{"Virtual": "carol"}}, "content": {{"Append": ", world"}}}}'"#,
+            id
+        )
+        .as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+    h.exec(
+        format!(
+            r#"*ip content edit {} '{{"content": {{"Prepend": "> "}}}}'"#,
+            id
+        )
+        .as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+
+    let got = h
+        .exec(format!("*ip content get {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "content").as_deref(), Some("> hello, world"));
+    assert_eq!(got.field(0, "author").as_deref(), Some("carol"));
+    // once per edit.
+    assert_eq!(got.field(0, "edited_times").as_deref(), Some("2"));
+}
+
+#[tokio::test]
+async fn gets_paging_boundary() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+
+    h.exec("*ip user register", &alice).await.unwrap();
+    for i in 0..10 {
+        h.exec(format!("*ip content post -v bob -c {}", i).as_str(), &alice)
+            .await
+            .unwrap();
+    }
+
+    // paged by repository, and by interactor (filtered after finding).
+    let queries = ["{}", r#"{"author_registered": false}"#];
+    let gets = |query: &str, page: u32| format!("*ip content gets '{}' {}", query, page);
+
+    for query in queries {
+        // exactly filled.
+        let got = h.exec(gets(query, 2).as_str(), &alice).await.unwrap();
+        assert_eq!(got.descriptions().len(), 5);
+        assert!(h.exec(gets(query, 3).as_str(), &alice).await.is_err());
+    }
+
+    h.exec("*ip content post -v bob -c last", &alice)
+        .await
+        .unwrap();
+
+    for query in queries {
+        // partially filled.
+        let got = h.exec(gets(query, 3).as_str(), &alice).await.unwrap();
+        assert_eq!(got.descriptions().len(), 1);
+        assert_eq!(got.field(0, "content").as_deref(), Some("last"));
+        assert!(h.exec(gets(query, 4).as_str(), &alice).await.is_err());
+    }
+}
+
+#[tokio::test]
+async fn post_and_edit_normalize_content() {
+    let h = Harness::in_memory(Config::default());
+    let alice = h.executor(1, "alice");
+    h.exec("*ip user register", &alice).await.unwrap();
+
+    let posted = h
+        .exec("*ip content post -v bob -c \"  hello  \"", &alice)
+        .await
+        .unwrap();
+    assert_eq!(posted.field(0, "content").as_deref(), Some("hello"));
+    let id = content_id(&posted);
+
+    h.exec(
+        format!(
+            r#"*ip content edit {} '{{"content": {{"Append": "  \r\n"}}}}'"#,
+            id
+        )
+        .as_str(),
+        &alice,
+    )
+    .await
+    .unwrap();
+    let got = h
+        .exec(format!("*ip content get {}", id).as_str(), &alice)
+        .await
+        .unwrap();
+    assert_eq!(got.field(0, "content").as_deref(), Some("hello"));
+
+    assert!(h
+        .exec("*ip content post -v bob -c \"   \"", &alice)
+        .await
+        .is_err());
+}